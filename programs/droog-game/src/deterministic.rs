@@ -0,0 +1,744 @@
+// ============================================================================
+// DETERMINISTIC ALGORITHMS
+// ============================================================================
+//
+// The trust core clients must mirror exactly: delivery rotation buckets and
+// seeds, delivery spot selection, plant variant ids, and growth jitter.
+//
+// This module is pure Rust with no Anchor, account, or Solana dependencies
+// (player keys are plain 32-byte arrays), so it can be compiled on its own
+// as a client library or to WASM for byte-identical behavior. The state
+// modules wrap these functions; never fork the logic there.
+//
+// Changing any output here breaks replay of existing matches. Add a new
+// algo_version instead.
+
+/// Deterministic selection algorithm versions.
+/// Stored per match at init so historical matches replay with the algorithms
+/// they were created under; new matches always use CURRENT_ALGO_VERSION.
+pub const ALGO_VERSION_LEGACY: u8 = 0;
+pub const ALGO_VERSION_V1: u8 = 1;
+/// v2: variant selection is weighted by the planter's reputation
+pub const ALGO_VERSION_REP_WEIGHTED: u8 = 2;
+/// v3: delivery selection never drops a spot on a collision (always 3 + extras)
+pub const ALGO_VERSION_FULL_BOARD: u8 = 3;
+pub const CURRENT_ALGO_VERSION: u8 = ALGO_VERSION_FULL_BOARD;
+
+/// Delivery slot rotation interval in seconds
+/// Updated for fast-paced 10-minute matches
+pub const DELIVERY_ROTATION_INTERVAL: i64 = 60;
+
+/// Guaranteed delivery spots per board (one per layer)
+pub const MIN_DELIVERY_SPOTS: usize = 3;
+
+/// Most extra spots a match may configure beyond the per-layer minimums
+pub const MAX_EXTRA_DELIVERY_SPOTS: u8 = 4;
+
+/// Extra spots when a match doesn't configure them (the original 3 + 2 board)
+pub const DEFAULT_EXTRA_DELIVERY_SPOTS: u8 = 2;
+
+/// Maximum number of active delivery spots at any time
+pub const MAX_DELIVERY_SPOTS: usize = MIN_DELIVERY_SPOTS + MAX_EXTRA_DELIVERY_SPOTS as usize;
+
+/// Variant count for deterministic variant selection
+pub const VARIANT_COUNT: u8 = 3;
+
+/// Rep-weighted variants (v2): roll range, split into VARIANT_COUNT equal bands
+pub const VARIANT_ROLL_RANGE: u64 = 99;
+
+/// Rep-weighted variants (v2): points of the roll moved from variant 0 to
+/// variant 2 at full bias (33/33/33 -> 22/33/44)
+pub const VARIANT_REP_BIAS_MAX: u64 = 11;
+
+/// Rep-weighted variants (v2): reputation at which the bias is saturated.
+/// Reputation <= 0 gets the unbiased distribution.
+pub const VARIANT_REP_BIAS_CAP: i32 = 100;
+
+/// Marks an unused delivery spot
+pub const INVALID_INDEX: u8 = 255;
+
+/// Display flavors (names/sprites) a customer can be drawn as
+pub const CUSTOMER_FLAVOR_COUNT: u8 = 16;
+
+/// Salt separating the flavor roll from other per-customer rolls on the same seed
+const CUSTOMER_FLAVOR_SALT: u64 = 0x666c_6176_6f72_7321;
+
+/// Salt separating the growth jitter roll from the variant roll
+const GROWTH_JITTER_SALT: u64 = 0x6a69_7474_6572_2121;
+
+/// SplitMix64 finalizer used by the v1 deterministic algorithms
+pub fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Delivery rotation bucket for a timestamp
+/// 
+/// Floors (div_euclid) rather than truncating toward zero, so [-60, 0) and
+/// [0, 60) are distinct buckets. The i64 -> u64 cast is a two's complement
+/// reinterpretation: distinct buckets stay distinct across the whole range
+/// (identical to plain division for ts >= 0, so replays are unaffected).
+pub fn rotation_bucket(current_ts: i64) -> u64 {
+    current_ts.div_euclid(DELIVERY_ROTATION_INTERVAL) as u64
+}
+
+/// Deterministic seed for a delivery rotation
+/// 
+/// Hash formula: mix(match_id, timestamp_bucket) where timestamp_bucket = ts / 60
+/// - v0 (legacy): XOR before mixing, so (match_id ^ bucket) collisions share a seed
+/// - v1: match_id is pre-multiplied before combining, then fully avalanched
+pub fn delivery_seed(algo_version: u8, match_id: u64, current_ts: i64) -> u64 {
+    let timestamp_bucket = rotation_bucket(current_ts);
+    
+    match algo_version {
+        ALGO_VERSION_LEGACY => {
+            // Simple deterministic hash using XOR and multiplication
+            let mut hash: u64 = match_id;
+            hash ^= timestamp_bucket;
+            
+            // Avalanche mixing to ensure good distribution
+            hash = hash.wrapping_mul(0x517cc1b727220a95);
+            hash ^= hash >> 32;
+            hash = hash.wrapping_mul(0x7fb5d329728ea185);
+            hash ^= hash >> 27;
+            
+            hash
+        }
+        _ => mix64(match_id.wrapping_mul(0x9e3779b97f4a7c15) ^ timestamp_bucket),
+    }
+}
+
+fn contains_spot(spots: &[u8; MAX_DELIVERY_SPOTS], count: u8, value: u8) -> bool {
+    spots[..count as usize].contains(&value)
+}
+
+/// First customer not yet on the board, walking forward from `pick` within
+/// [start, start + len) and then, if that layer is exhausted, the whole roster
+fn next_free_spot(
+    spots: &[u8; MAX_DELIVERY_SPOTS],
+    count: u8,
+    pick: u8,
+    start: u8,
+    len: u8,
+    roster_size: u8,
+) -> u8 {
+    let offset = pick - start;
+    for step in 0..len {
+        let candidate = start + (offset + step) % len;
+        if !contains_spot(spots, count, candidate) {
+            return candidate;
+        }
+    }
+    let mut candidate = pick;
+    while contains_spot(spots, count, candidate) {
+        candidate = (candidate + 1) % roster_size;
+    }
+    candidate
+}
+
+/// Select delivery spots deterministically from a seed
+/// 
+/// Layers are contiguous index ranges: layer 3 (inner) first, then layer 2,
+/// then layer 1 (outer), with the given counts.
+/// 
+/// Guarantees:
+/// - Exactly 1 spot from each layer
+/// - `extra_count` additional spots (capped at MAX_EXTRA_DELIVERY_SPOTS):
+///   the first two from the original weighted picks, any further ones from
+///   any layer
+/// - v3+: exactly 3 + extra_count spots. When the second weighted pick and
+///   its fallback both collide, candidates are walked forward until a free
+///   one is found. Earlier versions drop that spot (replays keep the short board).
+/// 
+/// A board with more extras extends the smaller board for the same seed, and
+/// DEFAULT_EXTRA_DELIVERY_SPOTS reproduces the original 3 + 2 selection.
+/// 
+/// Returns array of customer indices and count of valid entries
+pub fn select_delivery_spots(
+    algo_version: u8,
+    seed: u64,
+    layer3_count: u8,
+    layer2_count: u8,
+    layer1_count: u8,
+    extra_count: u8,
+) -> ([u8; MAX_DELIVERY_SPOTS], u8) {
+    let mut spots = [INVALID_INDEX; MAX_DELIVERY_SPOTS];
+    let mut count: u8 = 0;
+    let extra_count = extra_count.min(MAX_EXTRA_DELIVERY_SPOTS);
+    let roster_size = layer3_count + layer2_count + layer1_count;
+    
+    let layer3_start = 0u8;
+    let layer2_start = layer3_count;
+    let layer1_start = layer3_count + layer2_count;
+    
+    // Layer 3: inner core
+    let layer3_count = layer3_count as u64;
+    let layer3_pick = layer3_start + ((seed % layer3_count) as u8);
+    spots[count as usize] = layer3_pick;
+    count += 1;
+    
+    // Layer 2: middle ring
+    let layer2_count = layer2_count as u64;
+    let layer2_pick = layer2_start + (((seed >> 8) % layer2_count) as u8);
+    spots[count as usize] = layer2_pick;
+    count += 1;
+    
+    // Layer 1: outer ring
+    let layer1_count = layer1_count as u64;
+    let layer1_pick = layer1_start + (((seed >> 16) % layer1_count) as u8);
+    spots[count as usize] = layer1_pick;
+    count += 1;
+    
+    if extra_count == 0 {
+        return (spots, count);
+    }
+    
+    // Additional spot 1: from Layer 1 or Layer 2 (weighted toward outer layers)
+    // Use different seed bits to avoid correlation
+    let additional1_seed = seed >> 24;
+    if additional1_seed % 3 == 0 {
+        // Layer 2 pick (different from first L2 pick)
+        let l2_offset = ((additional1_seed >> 4) % layer2_count) as u8;
+        let pick = layer2_start + l2_offset;
+        // Avoid duplicate
+        if !contains_spot(&spots, count, pick) {
+            spots[count as usize] = pick;
+            count += 1;
+        } else {
+            // Fallback to next index
+            let fallback = layer2_start + ((l2_offset + 1) % layer2_count as u8);
+            spots[count as usize] = fallback;
+            count += 1;
+        }
+    } else {
+        // Layer 1 pick (different from first L1 pick)
+        let l1_offset = ((additional1_seed >> 4) % layer1_count) as u8;
+        let pick = layer1_start + l1_offset;
+        if !contains_spot(&spots, count, pick) {
+            spots[count as usize] = pick;
+            count += 1;
+        } else {
+            let fallback = layer1_start + ((l1_offset + 1) % layer1_count as u8);
+            spots[count as usize] = fallback;
+            count += 1;
+        }
+    }
+    
+    if extra_count == 1 {
+        return (spots, count);
+    }
+    
+    // Additional spot 2: from any layer (weighted toward skill challenge)
+    let additional2_seed = seed >> 40;
+    let layer_choice = additional2_seed % 6;
+    let count_before = count;
+    
+    // Layer the pick came from, for the v3 walk below
+    let (pick_start, pick_len, first_pick) = if layer_choice < 2 {
+        // Layer 3 (rare second L3 spot for high-skill play)
+        let l3_offset = ((additional2_seed >> 4) % layer3_count) as u8;
+        let pick = layer3_start + l3_offset;
+        if !contains_spot(&spots, count, pick) {
+            spots[count as usize] = pick;
+            count += 1;
+        }
+        (layer3_start, layer3_count as u8, pick)
+    } else if layer_choice < 4 {
+        // Layer 2
+        let l2_offset = ((additional2_seed >> 4) % layer2_count) as u8;
+        let pick = layer2_start + l2_offset;
+        if !contains_spot(&spots, count, pick) {
+            spots[count as usize] = pick;
+            count += 1;
+        } else {
+            let fallback = layer2_start + ((l2_offset + 2) % layer2_count as u8);
+            if !contains_spot(&spots, count, fallback) {
+                spots[count as usize] = fallback;
+                count += 1;
+            }
+        }
+        (layer2_start, layer2_count as u8, pick)
+    } else {
+        // Layer 1
+        let l1_offset = ((additional2_seed >> 4) % layer1_count) as u8;
+        let pick = layer1_start + l1_offset;
+        if !contains_spot(&spots, count, pick) {
+            spots[count as usize] = pick;
+            count += 1;
+        } else {
+            let fallback = layer1_start + ((l1_offset + 2) % layer1_count as u8);
+            if !contains_spot(&spots, count, fallback) {
+                spots[count as usize] = fallback;
+                count += 1;
+            }
+        }
+        (layer1_start, layer1_count as u8, pick)
+    };
+    
+    // v3: never drop the spot - walk forward from the pick instead
+    if count == count_before && algo_version >= ALGO_VERSION_FULL_BOARD {
+        spots[count as usize] = next_free_spot(&spots, count, first_pick, pick_start, pick_len, roster_size);
+        count += 1;
+    }
+    
+    // Further extras: remixed pick over the whole roster, walking forward
+    // past customers already on the board
+    for k in 2..extra_count {
+        let mut pick = (mix64(seed ^ (k as u64).wrapping_mul(0x9e3779b97f4a7c15)) % roster_size as u64) as u8;
+        while contains_spot(&spots, count, pick) {
+            pick = (pick + 1) % roster_size;
+        }
+        spots[count as usize] = pick;
+        count += 1;
+    }
+    
+    (spots, count)
+}
+
+/// Roll bias toward better variants for a planter's reputation (v2)
+/// Linear from 0 at reputation <= 0 to VARIANT_REP_BIAS_MAX at VARIANT_REP_BIAS_CAP
+pub fn variant_rep_bias(reputation: i32) -> u64 {
+    let rep = reputation.clamp(0, VARIANT_REP_BIAS_CAP) as u64;
+    rep * VARIANT_REP_BIAS_MAX / VARIANT_REP_BIAS_CAP as u64
+}
+
+/// Deterministic variant ID for a planting
+/// Uses slot number instead of timestamp for better entropy (slot changes every ~400ms, timestamp changes every 1s)
+/// - v0 (legacy): (match_id XOR player_key_bytes XOR slot_index XOR slot_number) % VARIANT_COUNT
+/// - v1: each input is folded through a full avalanche, so inputs can't cancel out via XOR
+/// - v2: v1 hash, mapped onto bands shifted by variant_rep_bias(reputation)
+/// 
+/// `reputation` is the planter's reputation when planting; v0/v1 ignore it.
+pub fn variant_id(
+    algo_version: u8,
+    match_id: u64,
+    player: &[u8; 32],
+    slot_index: u8,
+    slot_number: u64,
+    reputation: i32,
+) -> u8 {
+    let hash = match algo_version {
+        ALGO_VERSION_LEGACY => {
+            // Simple deterministic hash using XOR and byte mixing
+            let mut hash: u64 = match_id;
+            
+            // Mix in player pubkey bytes
+            for chunk in player.chunks(8) {
+                let mut bytes = [0u8; 8];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                hash ^= u64::from_le_bytes(bytes);
+            }
+            
+            // Mix in slot index and slot number (better entropy than timestamp)
+            hash ^= slot_index as u64;
+            hash ^= slot_number;
+            
+            // Final mixing (simple avalanche)
+            hash = hash.wrapping_mul(0x517cc1b727220a95);
+            hash ^= hash >> 32;
+            hash
+        }
+        _ => {
+            let mut hash = mix64(match_id);
+            for chunk in player.chunks(8) {
+                let mut bytes = [0u8; 8];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                hash = mix64(hash ^ u64::from_le_bytes(bytes));
+            }
+            hash = mix64(hash ^ slot_index as u64);
+            mix64(hash ^ slot_number)
+        }
+    };
+    
+    if algo_version < ALGO_VERSION_REP_WEIGHTED {
+        return (hash % VARIANT_COUNT as u64) as u8;
+    }
+    
+    // Shift the band edges down: variant 0 shrinks, variant 2 grows
+    let band = VARIANT_ROLL_RANGE / VARIANT_COUNT as u64;
+    let bias = variant_rep_bias(reputation);
+    let roll = hash % VARIANT_ROLL_RANGE;
+    if roll < band - bias {
+        0
+    } else if roll < 2 * band - bias {
+        1
+    } else {
+        2
+    }
+}
+
+/// Deterministic display flavor for a customer, in [0, CUSTOMER_FLAVOR_COUNT)
+/// 
+/// match_seed is the map seed (match_id). Presentation only: no game rule
+/// reads it, so both clients can render identical customers without shared
+/// off-chain config. Salted so a customer's look doesn't give away their
+/// patience roll.
+pub fn customer_flavor(match_seed: u64, customer_index: u8) -> u8 {
+    let hash = mix64(mix64(match_seed ^ CUSTOMER_FLAVOR_SALT) ^ customer_index as u64);
+    (hash % CUSTOMER_FLAVOR_COUNT as u64) as u8
+}
+
+/// Deterministic growth time jitter for a planting, in seconds
+/// 
+/// Uniform in [-span, +span] where span = growth_time * jitter_percent / 100
+/// (0 when jitter is off). Seeded by the full match_id_hash, so every match
+/// (rematches and double-or-nothing continuations included) rolls its own
+/// sequence; plant_count is the planter's running planting number, so
+/// replanting the same slot rolls again. Rolled once at planting and stored
+/// with the plant.
+pub fn growth_jitter(
+    match_id_hash: &[u8; 32],
+    player: &[u8; 32],
+    slot_index: u8,
+    plant_count: u32,
+    growth_time: i64,
+    jitter_percent: u8,
+) -> i64 {
+    let span = growth_time.max(0).saturating_mul(jitter_percent as i64) / 100;
+    if span == 0 {
+        return 0;
+    }
+    
+    let mut hash = mix64(GROWTH_JITTER_SALT);
+    for chunk in match_id_hash.chunks(8).chain(player.chunks(8)) {
+        let mut bytes = [0u8; 8];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        hash = mix64(hash ^ u64::from_le_bytes(bytes));
+    }
+    hash = mix64(hash ^ slot_index as u64);
+    hash = mix64(hash ^ plant_count as u64);
+    
+    let width = span as u64 * 2 + 1;
+    (hash % width) as i64 - span
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use crate::state::{LayerConfig, MatchDeliveryState, MatchGrowState};
+    
+    /// Outputs over a large pseudo-random input sample, folded into checksums.
+    /// `pure` selects this module's functions or the state-module wrappers.
+    fn sample_checksums(pure: bool) -> (u64, u64, u64) {
+        let mut x: u64 = 0x1234_5678_9abc_def0;
+        let mut next = || {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            x
+        };
+        let layouts = [
+            LayerConfig::DEFAULT,
+            LayerConfig { layer3_count: 1, layer2_count: 2, layer1_count: 20 },
+            LayerConfig { layer3_count: 5, layer2_count: 8, layer1_count: 10 },
+        ];
+        let (mut seeds, mut variants, mut spots_sum) = (0u64, 0u64, 0u64);
+        
+        for i in 0..4000u64 {
+            let match_id = next();
+            let ts = (next() >> 2) as i64 % 10_000_000_000;
+            for algo in [ALGO_VERSION_LEGACY, ALGO_VERSION_V1] {
+                let seed = if pure {
+                    delivery_seed(algo, match_id, ts)
+                } else {
+                    MatchDeliveryState::compute_delivery_seed(algo, match_id, ts)
+                };
+                seeds = mix64(seeds ^ seed);
+                
+                let mut player = [0u8; 32];
+                for chunk in player.chunks_mut(8) {
+                    chunk.copy_from_slice(&next().to_le_bytes());
+                }
+                let slot_index = (i % 6) as u8;
+                let slot_number = next();
+                let vid = if pure {
+                    variant_id(algo, match_id, &player, slot_index, slot_number, 0)
+                } else {
+                    MatchGrowState::compute_variant_id(
+                        algo,
+                        match_id,
+                        &Pubkey::new_from_array(player),
+                        slot_index,
+                        slot_number,
+                        0,
+                    )
+                };
+                variants = mix64(variants ^ vid as u64);
+            }
+            
+            let seed = next();
+            let layers = &layouts[(i % 3) as usize];
+            let (spots, count, bucket) = if pure {
+                let (spots, count) = select_delivery_spots(
+                    ALGO_VERSION_V1,
+                    seed,
+                    layers.layer3_count,
+                    layers.layer2_count,
+                    layers.layer1_count,
+                    DEFAULT_EXTRA_DELIVERY_SPOTS,
+                );
+                (spots, count, rotation_bucket(ts))
+            } else {
+                let (spots, count) =
+                    MatchDeliveryState::select_delivery_spots(ALGO_VERSION_V1, seed, layers, DEFAULT_EXTRA_DELIVERY_SPOTS);
+                (spots, count, MatchDeliveryState::get_rotation_bucket(ts))
+            };
+            // Only the original 5-spot board existed when these were captured
+            let mut acc = count as u64;
+            for &spot in spots.iter().take(5) {
+                acc = (acc << 8) ^ spot as u64;
+            }
+            spots_sum = mix64(spots_sum ^ acc ^ bucket);
+        }
+        
+        (seeds, variants, spots_sum)
+    }
+    
+    #[test]
+    fn test_extracted_functions_match_originals() {
+        // Checksums captured from the pre-extraction implementations in the
+        // state modules; any behavioral drift changes them.
+        const ORIGINAL: (u64, u64, u64) = (0x7ea3d9a491e7c0db, 0xd9a2c789a9f89ccc, 0xa4e49376bd13b192);
+        
+        assert_eq!(sample_checksums(true), ORIGINAL);
+        assert_eq!(sample_checksums(false), ORIGINAL);
+    }
+    
+    #[test]
+    fn test_rep_weighted_variants_favor_reputation() {
+        let player = [9u8; 32];
+        let sample = |algo: u8, rep: i32| -> [u32; VARIANT_COUNT as usize] {
+            let mut counts = [0u32; VARIANT_COUNT as usize];
+            for n in 0..30_000u64 {
+                counts[variant_id(algo, 77, &player, (n % 6) as u8, n, rep) as usize] += 1;
+            }
+            counts
+        };
+        // Mean variant rep bonus (-1 / 0 / +1) in thousandths
+        let score = |c: [u32; 3]| (c[2] as i64 - c[0] as i64) * 1000 / 30_000;
+        
+        let neutral = sample(ALGO_VERSION_REP_WEIGHTED, 0);
+        let mid = sample(ALGO_VERSION_REP_WEIGHTED, VARIANT_REP_BIAS_CAP / 2);
+        let top = sample(ALGO_VERSION_REP_WEIGHTED, VARIANT_REP_BIAS_CAP);
+        
+        // Zero rep is ~uniform; bias grows with rep (~+0.11 mean at 50, ~+0.22 at cap)
+        assert!(score(neutral).abs() < 20);
+        assert!(score(mid) > score(neutral) + 80);
+        assert!(score(top) > score(mid) + 80);
+        assert!(top[2] > neutral[2] && top[0] < neutral[0]);
+        
+        // Saturates at the cap; negative rep is never penalized
+        assert_eq!(sample(ALGO_VERSION_REP_WEIGHTED, VARIANT_REP_BIAS_CAP * 5), top);
+        assert_eq!(sample(ALGO_VERSION_REP_WEIGHTED, -500), neutral);
+        
+        // Reproducible
+        assert_eq!(sample(ALGO_VERSION_REP_WEIGHTED, 37), sample(ALGO_VERSION_REP_WEIGHTED, 37));
+        
+        // Older versions ignore reputation entirely
+        for algo in [ALGO_VERSION_LEGACY, ALGO_VERSION_V1] {
+            assert_eq!(sample(algo, 0), sample(algo, VARIANT_REP_BIAS_CAP));
+        }
+    }
+    
+    #[test]
+    fn test_configurable_extra_spots() {
+        let layouts = [(3, 8, 12), (1, 2, 20), (5, 8, 10)];
+        for n in 0..2_000u64 {
+            let seed = mix64(n);
+            let (l3, l2, l1) = layouts[(n % 3) as usize];
+            let algo = if n % 2 == 0 { ALGO_VERSION_V1 } else { CURRENT_ALGO_VERSION };
+            let mut previous: Option<([u8; MAX_DELIVERY_SPOTS], u8)> = None;
+            
+            for extra in 0..=MAX_EXTRA_DELIVERY_SPOTS {
+                let (spots, count) = select_delivery_spots(algo, seed, l3, l2, l1, extra);
+                
+                // Minimums only at 0; otherwise 3 + extra, minus a possible
+                // collision on the second original pick before v3
+                let max = MIN_DELIVERY_SPOTS as u8 + extra;
+                let min = if extra >= 2 && algo < ALGO_VERSION_FULL_BOARD { max - 1 } else { max };
+                assert!((min..=max).contains(&count), "extra {extra} gave {count} spots");
+                
+                // One per layer up front, no duplicates, rest unused
+                assert!(spots[0] < l3);
+                assert!((l3..l3 + l2).contains(&spots[1]));
+                assert!((l3 + l2..l3 + l2 + l1).contains(&spots[2]));
+                for i in 0..count as usize {
+                    assert!(spots[i] < l3 + l2 + l1);
+                    assert!(!spots[..i].contains(&spots[i]));
+                }
+                assert!(spots[count as usize..].iter().all(|&s| s == INVALID_INDEX));
+                
+                // Larger boards extend smaller ones for the same seed
+                if let Some((prev_spots, prev_count)) = previous {
+                    assert_eq!(spots[..prev_count as usize], prev_spots[..prev_count as usize]);
+                }
+                previous = Some((spots, count));
+            }
+            
+            // Requests above the cap are clamped
+            assert_eq!(
+                select_delivery_spots(algo, seed, l3, l2, l1, MAX_EXTRA_DELIVERY_SPOTS + 3),
+                select_delivery_spots(algo, seed, l3, l2, l1, MAX_EXTRA_DELIVERY_SPOTS)
+            );
+        }
+        
+        // The full board is reachable
+        assert!((0..200u64).any(|n| select_delivery_spots(ALGO_VERSION_V1, mix64(n), 3, 8, 12, MAX_EXTRA_DELIVERY_SPOTS).1
+            == MAX_DELIVERY_SPOTS as u8));
+    }
+    
+    #[test]
+    fn test_full_board_always_filled() {
+        let mut seeds: Vec<u64> = vec![0, 1, 2, 3, u64::MAX, u64::MAX - 1, 1 << 40, 1 << 63];
+        seeds.extend(0..5_000u64);
+        seeds.extend((0..20_000u64).map(mix64));
+        
+        let mut legacy_short = 0;
+        for &seed in &seeds {
+            for (l3, l2, l1) in [(3u8, 8u8, 12u8), (1, 2, 20), (5, 8, 10)] {
+                let (spots, count) =
+                    select_delivery_spots(CURRENT_ALGO_VERSION, seed, l3, l2, l1, DEFAULT_EXTRA_DELIVERY_SPOTS);
+                assert_eq!(count, 5, "seed {seed} gave {count} spots");
+                
+                // Still one per layer up front, no duplicates
+                assert!(spots[0] < l3);
+                assert!((l3..l3 + l2).contains(&spots[1]));
+                assert!((l3 + l2..l3 + l2 + l1).contains(&spots[2]));
+                for i in 0..count as usize {
+                    assert!(spots[i] < l3 + l2 + l1);
+                    assert!(!spots[..i].contains(&spots[i]));
+                }
+                
+                // Identical to the old selection whenever that one was full
+                let (legacy_spots, legacy_count) =
+                    select_delivery_spots(ALGO_VERSION_REP_WEIGHTED, seed, l3, l2, l1, DEFAULT_EXTRA_DELIVERY_SPOTS);
+                if legacy_count == 5 {
+                    assert_eq!(legacy_spots, spots);
+                } else {
+                    legacy_short += 1;
+                    assert_eq!(legacy_spots[..4], spots[..4]);
+                }
+            }
+        }
+        
+        // The sweep exercised the collision path
+        assert!(legacy_short > 0);
+    }
+    
+    #[test]
+    fn test_rotation_bucket_and_seed_boundaries() {
+        assert_eq!(rotation_bucket(0), 0);
+        assert_eq!(rotation_bucket(DELIVERY_ROTATION_INTERVAL - 1), 0);
+        assert_eq!(rotation_bucket(DELIVERY_ROTATION_INTERVAL), 1);
+        
+        for algo in [ALGO_VERSION_LEGACY, CURRENT_ALGO_VERSION] {
+            assert_eq!(delivery_seed(algo, 7, 60), delivery_seed(algo, 7, 119));
+            assert_ne!(delivery_seed(algo, 7, 119), delivery_seed(algo, 7, 120));
+        }
+        
+        // Extremes: no overflow, and adjacent buckets never merge
+        assert_eq!(rotation_bucket(i64::MAX), (i64::MAX / DELIVERY_ROTATION_INTERVAL) as u64);
+        assert_ne!(rotation_bucket(i64::MAX), rotation_bucket(i64::MAX - DELIVERY_ROTATION_INTERVAL));
+        assert_ne!(rotation_bucket(i64::MIN), rotation_bucket(i64::MIN + DELIVERY_ROTATION_INTERVAL));
+        
+        // Negative timestamps floor instead of sharing bucket 0
+        assert_ne!(rotation_bucket(-1), rotation_bucket(0));
+        assert_eq!(rotation_bucket(-1), rotation_bucket(-DELIVERY_ROTATION_INTERVAL));
+        assert_ne!(rotation_bucket(-DELIVERY_ROTATION_INTERVAL - 1), rotation_bucket(-1));
+    }
+    
+    #[test]
+    fn test_extreme_inputs_stay_well_distributed() {
+        let match_ids = [0, 1, 1 << 63, u64::MAX - 1, u64::MAX];
+        let timestamps = [i64::MIN, -1, 0, 1_700_000_000, i64::MAX - DELIVERY_ROTATION_INTERVAL, i64::MAX];
+        
+        // v1 seeds are pairwise distinct across every extreme combination
+        let mut seeds = std::collections::HashSet::new();
+        for &match_id in &match_ids {
+            for &ts in &timestamps {
+                let seed = delivery_seed(CURRENT_ALGO_VERSION, match_id, ts);
+                assert!(seeds.insert(seed), "seed collision at ({match_id}, {ts})");
+                
+                // Selection from an extreme seed is still a full, duplicate-free board
+                let (spots, count) =
+                    select_delivery_spots(CURRENT_ALGO_VERSION, seed, 3, 8, 12, DEFAULT_EXTRA_DELIVERY_SPOTS);
+                assert_eq!(count, 5);
+                for i in 0..count as usize {
+                    assert!(spots[i] < 23);
+                    assert!(!spots[..i].contains(&spots[i]));
+                }
+            }
+        }
+        
+        // Consecutive buckets at the extremes still avalanche: about half the
+        // seed bits flip between neighbors
+        for &match_id in &match_ids {
+            for &ts in &[i64::MAX - 10 * DELIVERY_ROTATION_INTERVAL, 0] {
+                let flips: u32 = (0..10)
+                    .map(|k| {
+                        let a = delivery_seed(CURRENT_ALGO_VERSION, match_id, ts + k * DELIVERY_ROTATION_INTERVAL);
+                        let b = delivery_seed(CURRENT_ALGO_VERSION, match_id, ts + (k + 1) * DELIVERY_ROTATION_INTERVAL);
+                        (a ^ b).count_ones()
+                    })
+                    .sum();
+                assert!((200..=440).contains(&flips), "weak avalanche: {flips} bits over 10 steps");
+            }
+        }
+        
+        // Variants near u64::MAX slot numbers hit every variant about evenly
+        let player = [0xFFu8; 32];
+        for &match_id in &[0, u64::MAX] {
+            let mut counts = [0u32; VARIANT_COUNT as usize];
+            for n in 0..3_000u64 {
+                counts[variant_id(ALGO_VERSION_V1, match_id, &player, 5, u64::MAX - n, 0) as usize] += 1;
+            }
+            assert!(counts.iter().all(|&c| (850..=1150).contains(&c)), "{counts:?}");
+        }
+    }
+    
+    #[test]
+    fn test_customer_flavors_deterministic_per_match() {
+        let flavors = |seed: u64| -> Vec<u8> { (0..23u8).map(|i| customer_flavor(seed, i)).collect() };
+        
+        for seed in [0u64, 1, 12345, u64::MAX] {
+            let first = flavors(seed);
+            assert_eq!(first, flavors(seed));
+            assert!(first.iter().all(|&f| f < CUSTOMER_FLAVOR_COUNT));
+            // A map isn't one flavor repeated
+            assert!(first.iter().any(|&f| f != first[0]));
+        }
+        
+        // Different matches draw different crowds
+        let base = flavors(12345);
+        assert!((12346..12356u64).all(|seed| flavors(seed) != base));
+    }
+    
+    #[test]
+    fn test_growth_jitter_deterministic_and_bounded() {
+        let player = Pubkey::new_unique().to_bytes();
+        let seed = [42u8; 32];
+        let roll = |count: u32| growth_jitter(&seed, &player, 3, count, 60, 20);
+        
+        // Same inputs, same jitter; within +/- 20% of 60s
+        let rolls: Vec<i64> = (0..500).map(roll).collect();
+        assert_eq!(rolls, (0..500).map(roll).collect::<Vec<_>>());
+        assert!(rolls.iter().all(|j| (-12..=12).contains(j)));
+        
+        // Both extremes are reachable and plants don't all roll alike
+        assert!(rolls.contains(&-12) && rolls.contains(&12));
+        assert!(rolls.iter().any(|&j| j != rolls[0]));
+        
+        // Off, or too short to jitter a whole second: exactly the base time
+        assert!((0..50).all(|count| growth_jitter(&seed, &player, 3, count, 60, 0) == 0));
+        assert!((0..50).all(|count| growth_jitter(&seed, &player, 3, count, 1, 50) == 0));
+        
+        // Each input changes the roll
+        let base: Vec<i64> = (0..20).map(roll).collect();
+        let mut other_match = seed;
+        other_match[31] ^= 1;
+        assert_ne!(base, (0..20).map(|c| growth_jitter(&other_match, &player, 3, c, 60, 20)).collect::<Vec<_>>());
+        assert_ne!(base, (0..20).map(|c| growth_jitter(&seed, &player, 4, c, 60, 20)).collect::<Vec<_>>());
+        let other = Pubkey::new_unique().to_bytes();
+        assert_ne!(base, (0..20).map(|c| growth_jitter(&seed, &other, 3, c, 60, 20)).collect::<Vec<_>>());
+    }
+}
@@ -41,7 +41,7 @@ pub enum DroogError {
     #[msg("Match cannot be finalized before end time")]
     MatchFinalizationTooEarly,
     
-    #[msg("Only match participants can finalize the match")]
+    #[msg("Only match participants can finalize the match before the grace window ends")]
     UnauthorizedFinalization,
     
     // ========== New Grow/Harvest System Errors ==========
@@ -67,7 +67,7 @@ pub enum DroogError {
     #[msg("Insufficient inventory to complete this sale")]
     InsufficientInventory,
     
-    #[msg("Inventory is at capacity (6 items max)")]
+    #[msg("Inventory is at capacity (6 items before upgrades and expansions)")]
     InventoryFull,
     
     // ========== Delivery State Errors ==========
@@ -78,7 +78,7 @@ pub enum DroogError {
     #[msg("Delivery slots have not rotated yet (60s minimum between refreshes)")]
     DeliveryRotationTooSoon,
     
-    #[msg("Delivery state has not been initialized for this match")]
+    #[msg("Delivery state has not been initialized for this match - call init_delivery_state first")]
     DeliveryStateNotInitialized,
     
     #[msg("Player A must have a lower pubkey than Player B for deterministic PDA derivation")]
@@ -101,7 +101,7 @@ pub enum DroogError {
     #[msg("Cannot cancel - Player B has already joined")]
     PlayerBAlreadyJoined,
     
-    #[msg("Stake amount exceeds maximum (1 token)")]
+    #[msg("Stake amount exceeds maximum (MAX_STAKE_AMOUNT)")]
     StakeExceedsMaximum,
     
     #[msg("Player has already staked")]
@@ -109,4 +109,335 @@ pub enum DroogError {
     
     #[msg("Arithmetic overflow in calculation")]
     CalculationOverflow,
+    
+    #[msg("Match has not been abandoned long enough to reap (30 days)")]
+    ReapTooEarly,
+    
+    #[msg("Match has not been activated yet (waiting for Player B to stake)")]
+    MatchNotActivated,
+    
+    #[msg("Only a growing plant can be replaced")]
+    PlantNotGrowing,
+    
+    #[msg("Account is already initialized for a different match")]
+    AlreadyInitialized,
+    
+    #[msg("No settlement is pending for this match")]
+    SettlementNotPending,
+    
+    #[msg("Settlement is frozen pending dispute")]
+    SettlementFrozen,
+    
+    #[msg("Settlement challenge window is still open")]
+    SettlementWindowOpen,
+    
+    #[msg("Settlement challenge window has closed")]
+    SettlementWindowClosed,
+    
+    #[msg("Layer counts must sum to the customer roster (min 1 inner, 2 middle, 2 outer)")]
+    InvalidLayerConfig,
+    
+    #[msg("Delivery system is disabled for this match")]
+    DeliveryDisabled,
+    
+    #[msg("An inventory expansion is already active")]
+    ExpansionAlreadyActive,
+    
+    #[msg("Not enough reputation for this purchase")]
+    InsufficientReputation,
+    
+    #[msg("Plant state strain level does not match the slot's strain level")]
+    SlotStateInconsistent,
+    
+    #[msg("Only the dispute admin can perform this action")]
+    NotDisputeAdmin,
+    
+    #[msg("Payout destination must be a token account owned by the player being paid")]
+    InvalidPayoutDestination,
+    
+    #[msg("Practice matches have no stake to settle")]
+    PracticeMatchNoStake,
+    
+    #[msg("Token and escrow accounts are required for staked matches")]
+    StakeAccountsRequired,
+    
+    #[msg("Opening plants have already been seeded for this match")]
+    OpeningPlantsAlreadySeeded,
+    
+    #[msg("Extra delivery spots exceed the board maximum")]
+    InvalidDeliverySpotCount,
+    
+    #[msg("Planting and selling open after the start grace period")]
+    StartGraceActive,
+    
+    #[msg("Match window must end more than the minimum duration after it starts")]
+    InvalidMatchTiming,
+    
+    #[msg("Stake can only be raised by a positive amount before Player B joins")]
+    InvalidStakeRaise,
+    
+    #[msg("Stake amount must be nonzero")]
+    InvalidStakeAmount,
+    
+    #[msg("Sales cap for this rotation bucket reached - sell again next rotation")]
+    SalesRateExceeded,
+    
+    #[msg("Batch must plant between 1 and 6 slots")]
+    InvalidPlantBatch,
+    
+    #[msg("Program is paused by the admin kill-switch")]
+    ProgramPaused,
+    
+    #[msg("Rematch requires a finalized or cancelled source match")]
+    RematchSourceNotSettled,
+    
+    #[msg("Player's smell is below the raid threshold")]
+    SmellBelowRaidThreshold,
+    
+    #[msg("Player was already raided in this rotation")]
+    RaidAlreadyTriggered,
+    
+    #[msg("Reputation too low to plant this strain level")]
+    StrainLevelLocked,
+    
+    #[msg("Strain unlock thresholds must be non-negative, with Level 3 at least Level 2")]
+    InvalidStrainUnlock,
+    
+    #[msg("Plant rotted before it was harvested - clear the slot instead")]
+    PlantRotted,
+    
+    #[msg("Only a rotten plant can be cleared")]
+    PlantNotRotted,
+    
+    #[msg("Pot split shares must be at most 10000 basis points")]
+    InvalidPotSplit,
+    
+    #[msg("Pot split requires immediate settlement (not delayed)")]
+    PotSplitRequiresImmediateSettlement,
+    
+    #[msg("Error code is not a DroogError")]
+    UnknownErrorCode,
+    
+    #[msg("Match accounts can only be closed once the match is finalized or cancelled")]
+    MatchNotClosed,
+    
+    #[msg("Shared land is not enabled for this match")]
+    SharedLandDisabled,
+    
+    #[msg("Shared slot is claimed by the opponent")]
+    SharedSlotClaimed,
+    
+    #[msg("Token account is not for this match's stake mint")]
+    MintMismatch,
+    
+    #[msg("No fertilizer charges left")]
+    NoFertilizerCharges,
+    
+    #[msg("Inventory capacity is already fully upgraded")]
+    CapacityUpgradeMaxed,
+    
+    #[msg("Game config tunables must have positive growth times and rotation periods")]
+    InvalidGameConfig,
+    
+    #[msg("Only the game config authority can change the tunables")]
+    NotConfigAuthority,
+    
+    #[msg("The double-or-nothing offer was already accepted or declined")]
+    DoubleOrNothingNotOffered,
+    
+    #[msg("Settlement is not frozen")]
+    SettlementNotFrozen,
+    
+    #[msg("Stake to match changed since the joiner read it")]
+    StakeAmountChanged,
+}
+
+/// How serious an error is for the player (see get_error_hint)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HintSeverity {
+    /// Expected during play (timing, cooldowns)
+    Info,
+    /// The player needs to do something different
+    Warning,
+    /// The client sent a bad request
+    Error,
+    /// An on-chain invariant failed
+    Critical,
+}
+
+/// What the client should suggest to the player
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HintAction {
+    /// Nothing to change - resend later
+    WaitAndRetry,
+    /// Pick another customer, slot or strain
+    ChooseAnother,
+    /// Earn more reputation first
+    EarnReputation,
+    /// Add tokens to the wallet
+    TopUpBalance,
+    /// Reload match accounts; the match has moved on
+    RefreshState,
+    /// Fix the request's arguments, accounts or config
+    FixRequest,
+    /// Needs the dispute admin
+    ContactSupport,
+}
+
+/// Structured, client-facing guidance for a DroogError
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ErrorHint {
+    pub severity: HintSeverity,
+    pub action: HintAction,
+    /// The same transaction may succeed if resent later
+    pub retryable: bool,
+}
+
+impl DroogError {
+    /// Every variant in declaration order (code = ERROR_CODE_OFFSET + index)
+    pub const ALL: [DroogError; 81] = [
+        Self::MatchNotStarted,
+        Self::MatchEnded,
+        Self::GrowthTimeNotElapsed,
+        Self::RegrowthLockoutActive,
+        Self::StrainNotActive,
+        Self::CustomerOnCooldown,
+        Self::InvalidStrainLevel,
+        Self::InvalidCustomerIndex,
+        Self::InvalidPlayer,
+        Self::CustomerNotAvailable,
+        Self::InvalidLayer,
+        Self::MatchAlreadyFinalized,
+        Self::MatchFinalizationTooEarly,
+        Self::UnauthorizedFinalization,
+        Self::MatchIdMismatch,
+        Self::EndgamePlantingLocked,
+        Self::InvalidSlotIndex,
+        Self::SlotOccupied,
+        Self::SlotEmpty,
+        Self::PlantWontBeReady,
+        Self::InsufficientInventory,
+        Self::InventoryFull,
+        Self::CustomerNotAvailableForDelivery,
+        Self::DeliveryRotationTooSoon,
+        Self::DeliveryStateNotInitialized,
+        Self::InvalidPlayerOrder,
+        Self::InsufficientStakeBalance,
+        Self::MatchNotPending,
+        Self::MatchNotActive,
+        Self::CancelTooEarly,
+        Self::PlayerBAlreadyJoined,
+        Self::StakeExceedsMaximum,
+        Self::AlreadyStaked,
+        Self::CalculationOverflow,
+        Self::ReapTooEarly,
+        Self::MatchNotActivated,
+        Self::PlantNotGrowing,
+        Self::AlreadyInitialized,
+        Self::SettlementNotPending,
+        Self::SettlementFrozen,
+        Self::SettlementWindowOpen,
+        Self::SettlementWindowClosed,
+        Self::InvalidLayerConfig,
+        Self::DeliveryDisabled,
+        Self::ExpansionAlreadyActive,
+        Self::InsufficientReputation,
+        Self::SlotStateInconsistent,
+        Self::NotDisputeAdmin,
+        Self::InvalidPayoutDestination,
+        Self::PracticeMatchNoStake,
+        Self::StakeAccountsRequired,
+        Self::OpeningPlantsAlreadySeeded,
+        Self::InvalidDeliverySpotCount,
+        Self::StartGraceActive,
+        Self::InvalidMatchTiming,
+        Self::InvalidStakeRaise,
+        Self::InvalidStakeAmount,
+        Self::SalesRateExceeded,
+        Self::InvalidPlantBatch,
+        Self::ProgramPaused,
+        Self::RematchSourceNotSettled,
+        Self::SmellBelowRaidThreshold,
+        Self::RaidAlreadyTriggered,
+        Self::StrainLevelLocked,
+        Self::InvalidStrainUnlock,
+        Self::PlantRotted,
+        Self::PlantNotRotted,
+        Self::InvalidPotSplit,
+        Self::PotSplitRequiresImmediateSettlement,
+        Self::UnknownErrorCode,
+        Self::MatchNotClosed,
+        Self::SharedLandDisabled,
+        Self::SharedSlotClaimed,
+        Self::MintMismatch,
+        Self::NoFertilizerCharges,
+        Self::CapacityUpgradeMaxed,
+        Self::InvalidGameConfig,
+        Self::NotConfigAuthority,
+        Self::DoubleOrNothingNotOffered,
+        Self::SettlementNotFrozen,
+        Self::StakeAmountChanged,
+    ];
+    
+    /// Variant for an Anchor error code, if it is one of ours
+    pub fn from_code(code: u32) -> Option<Self> {
+        let index = code.checked_sub(anchor_lang::error::ERROR_CODE_OFFSET)?;
+        Self::ALL.get(index as usize).copied()
+    }
+    
+    /// Client guidance for this error. Deliberately no wildcard arm: a new
+    /// variant does not compile until it is given a hint here.
+    pub fn hint(self) -> ErrorHint {
+        let (severity, action) = match self {
+            // Timing: the same transaction succeeds later
+            Self::MatchNotStarted | Self::GrowthTimeNotElapsed | Self::RegrowthLockoutActive |
+            Self::CustomerOnCooldown | Self::MatchFinalizationTooEarly |
+            Self::UnauthorizedFinalization | Self::DeliveryRotationTooSoon | Self::CancelTooEarly |
+            Self::ReapTooEarly | Self::MatchNotActivated | Self::SettlementWindowOpen |
+            Self::ExpansionAlreadyActive | Self::StartGraceActive | Self::SalesRateExceeded |
+            Self::SmellBelowRaidThreshold | Self::RaidAlreadyTriggered | Self::MatchNotClosed
+                => (HintSeverity::Info, HintAction::WaitAndRetry),
+            // Admin pause: retry once lifted
+            Self::ProgramPaused => (HintSeverity::Warning, HintAction::WaitAndRetry),
+            // Pick another customer, slot or strain
+            Self::StrainNotActive | Self::InvalidStrainLevel | Self::CustomerNotAvailable |
+            Self::EndgamePlantingLocked | Self::SlotOccupied | Self::SlotEmpty |
+            Self::PlantWontBeReady | Self::InsufficientInventory | Self::InventoryFull |
+            Self::CustomerNotAvailableForDelivery | Self::PlantNotGrowing | Self::PlantRotted |
+            Self::PlantNotRotted | Self::SharedSlotClaimed | Self::NoFertilizerCharges |
+            Self::CapacityUpgradeMaxed
+                => (HintSeverity::Warning, HintAction::ChooseAnother),
+            // Reputation gates
+            Self::InsufficientReputation | Self::StrainLevelLocked => (HintSeverity::Warning, HintAction::EarnReputation),
+            // Wallet balance
+            Self::InsufficientStakeBalance => (HintSeverity::Warning, HintAction::TopUpBalance),
+            // The match moved on - reload accounts
+            Self::MatchEnded | Self::MatchAlreadyFinalized | Self::MatchNotPending |
+            Self::MatchNotActive | Self::PlayerBAlreadyJoined | Self::AlreadyStaked |
+            Self::AlreadyInitialized | Self::SettlementNotPending | Self::SettlementWindowClosed |
+            Self::OpeningPlantsAlreadySeeded | Self::RematchSourceNotSettled |
+            Self::DoubleOrNothingNotOffered | Self::SettlementNotFrozen | Self::StakeAmountChanged
+                => (HintSeverity::Warning, HintAction::RefreshState),
+            // Bad arguments, accounts or match config (client bug)
+            Self::InvalidCustomerIndex | Self::InvalidPlayer | Self::InvalidLayer |
+            Self::MatchIdMismatch | Self::InvalidSlotIndex | Self::DeliveryStateNotInitialized |
+            Self::InvalidPlayerOrder | Self::StakeExceedsMaximum | Self::InvalidLayerConfig |
+            Self::DeliveryDisabled | Self::NotDisputeAdmin | Self::InvalidPayoutDestination |
+            Self::PracticeMatchNoStake | Self::StakeAccountsRequired |
+            Self::InvalidDeliverySpotCount | Self::InvalidMatchTiming | Self::InvalidStakeRaise |
+            Self::InvalidStakeAmount | Self::InvalidPlantBatch | Self::InvalidStrainUnlock |
+            Self::InvalidPotSplit | Self::PotSplitRequiresImmediateSettlement |
+            Self::UnknownErrorCode | Self::SharedLandDisabled | Self::MintMismatch |
+            Self::InvalidGameConfig | Self::NotConfigAuthority => (HintSeverity::Error, HintAction::FixRequest),
+            // Held for the dispute admin
+            Self::SettlementFrozen => (HintSeverity::Warning, HintAction::ContactSupport),
+            // Broken invariant
+            Self::CalculationOverflow | Self::SlotStateInconsistent => (HintSeverity::Critical, HintAction::ContactSupport),
+        };
+        ErrorHint {
+            severity,
+            action,
+            retryable: action == HintAction::WaitAndRetry,
+        }
+    }
 }
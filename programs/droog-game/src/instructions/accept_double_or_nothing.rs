@@ -0,0 +1,380 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{
+    MatchState, MatchStakeState, MatchStatus, MatchGrowState, MatchDeliveryState, LifecycleAction,
+    DoubleOrNothingOffer, DoubleOrNothingAcceptedEvent, MatchActivatedEvent, ESCROW_AUTH_SEED,
+    DOUBLE_OR_NOTHING_SEED, ProgramConfig, PROGRAM_CONFIG_SEED, SettlementRecord, SETTLEMENT_RECORD_SEED,
+};
+use crate::errors::DroogError;
+use super::init_delivery_state::DeliveryStateInitializedEvent;
+use super::init_grow_state::GrowStateInitializedEvent;
+
+/// Accept a double-or-nothing offer: roll the pot into a new Active match
+/// 
+/// The source match must still be Settling (not frozen) with its challenge
+/// window closed, so the result being doubled can no longer be frozen or
+/// reversed. expected_stake_amount is the pot the loser read from the offer;
+/// accept fails with StakeAmountChanged if it differs. Creates the match,
+/// stake, grow and (unless delivery-less) delivery accounts under a fresh
+/// match_id_hash for the same player_a/player_b, with the source's rules like
+/// rematch. The winner's pending share moves into the new escrow and the
+/// loser escrows the same amount again, so the pot doubles. The other shares
+/// recorded at finalize are paid out of the source escrow as execute_settlement
+/// would: the rake to treasury_token_account, the loser's consolation to
+/// loser_token_account, and the finalizer's reward to whichever player
+/// finalized (winner_token_account when that was the winner). Nothing is burned and there is no join step: the match starts
+/// now, Active, winner-take-all under delayed settlement (so it can be
+/// offered again). The source match ends Finalized with an empty escrow, and
+/// its SettlementRecord is rewritten as rolled over.
+pub fn accept_double_or_nothing(
+    ctx: Context<AcceptDoubleOrNothing>,
+    match_id_hash: [u8; 32],
+    expected_stake_amount: u64,
+) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let source_match = ctx.accounts.source_match_state.load()?;
+    let source_stake = &mut ctx.accounts.source_stake_state;
+    let offer = &mut ctx.accounts.offer;
+    let stake_amount = offer.accept(source_stake, match_id_hash, expected_stake_amount, current_ts)?;
+    
+    // Delivery state exists exactly when the rules use deliveries
+    if source_match.delivery_less() {
+        require!(ctx.accounts.delivery_state.is_none(), DroogError::DeliveryDisabled);
+    } else {
+        require!(ctx.accounts.delivery_state.is_some(), DroogError::DeliveryStateNotInitialized);
+    }
+    
+    let match_id = MatchState::derive_match_id(&match_id_hash);
+    
+    // ========== Match State ==========
+    let mut match_state = ctx.accounts.match_state.load_init()?;
+    match_state.initialize(
+        match_id,
+        match_id_hash,
+        source_match.player_a,
+        source_match.player_b,
+        current_ts,
+        source_match.layer_config,
+        ctx.bumps.match_state,
+    )?;
+    match_state.copy_rules_from(&source_match);
+    
+    // ========== Stake State (Active, Pot Doubled) ==========
+    let stake_state = &mut ctx.accounts.stake_state;
+    stake_state.initialize_continuation(
+        &match_state,
+        source_stake,
+        stake_amount,
+        current_ts,
+        ctx.bumps.stake_state,
+        ctx.bumps.escrow_token_account,
+    );
+    
+    // ========== Grow / Delivery State ==========
+    ctx.accounts.grow_state.initialize(&match_state, ctx.bumps.grow_state);
+    if let Some(delivery_state) = ctx.accounts.delivery_state.as_mut() {
+        delivery_state.initialize(&match_state, ctx.bumps.delivery_state.unwrap_or_default(), current_ts);
+        emit!(DeliveryStateInitializedEvent {
+            match_id,
+            initial_spots: delivery_state.available_customers,
+            active_count: delivery_state.active_count,
+            demanded_variants: delivery_state.demanded_variants,
+            rush_customer: delivery_state.rush_customer,
+            rush_expires_ts: delivery_state.rush_expires_ts,
+            rotation_bucket: MatchDeliveryState::get_rotation_bucket(current_ts),
+            timestamp: current_ts,
+        });
+    }
+    
+    let mint = &ctx.accounts.mint;
+    
+    // ========== Carry the Pot Over (NO BURN) ==========
+    // Escrow balance is authoritative; the source escrow is drained: the
+    // winner's share moves on, the other recorded shares are paid out
+    let pending = source_stake.pending_settlement;
+    let carried = pending.winner_payout(ctx.accounts.source_escrow_token_account.amount)?;
+    let new_escrow = ctx.accounts.escrow_token_account.to_account_info();
+    let loser_account = ctx.accounts.loser_token_account.to_account_info();
+    let mut payouts = [
+        (new_escrow.clone(), carried),
+        (new_escrow.clone(), 0),
+        (loser_account.clone(), pending.loser_amount),
+        (new_escrow, 0),
+    ];
+    if pending.rake > 0 {
+        let Some(treasury_token_account) = ctx.accounts.treasury_token_account.as_ref() else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        let treasury = ProgramConfig::treasury(&ctx.accounts.program_config)?;
+        MatchStakeState::validate_treasury_destination(&treasury_token_account.owner, treasury)?;
+        payouts[1] = (treasury_token_account.to_account_info(), pending.rake);
+    }
+    if pending.finalizer_reward > 0 {
+        let destination = if pending.finalizer == offer.loser {
+            loser_account
+        } else {
+            let Some(winner_token_account) = ctx.accounts.winner_token_account.as_ref() else {
+                return err!(DroogError::StakeAccountsRequired);
+            };
+            MatchStakeState::validate_payout_destination(&winner_token_account.owner, &pending.finalizer)?;
+            winner_token_account.to_account_info()
+        };
+        payouts[3] = (destination, pending.finalizer_reward);
+    }
+    
+    let source_match_id_hash = source_stake.match_id_hash;
+    let escrow_auth_bump = ctx.bumps.source_escrow_authority;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ESCROW_AUTH_SEED,
+        source_match_id_hash.as_ref(),
+        &[escrow_auth_bump],
+    ]];
+    for (destination, amount) in payouts {
+        if amount == 0 {
+            continue;
+        }
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.source_escrow_token_account.to_account_info(),
+            to: destination,
+            mint: mint.to_account_info(),
+            authority: ctx.accounts.source_escrow_authority.to_account_info(),
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_ctx, amount, mint.decimals)?;
+    }
+    
+    // ========== Loser Matches the Pot ==========
+    require!(
+        ctx.accounts.loser_token_account.amount >= stake_amount,
+        DroogError::InsufficientStakeBalance
+    );
+    
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.loser_token_account.to_account_info(),
+        to: ctx.accounts.escrow_token_account.to_account_info(),
+        mint: mint.to_account_info(),
+        authority: ctx.accounts.loser.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer_checked(cpi_ctx, stake_amount, mint.decimals)?;
+    
+    // The source pot now lives in the continuation; nothing is left to settle
+    source_stake.status = MatchStatus::Finalized;
+    ctx.accounts.source_settlement_record.record_rollover(&pending, carried, current_ts);
+    
+    let pot = carried
+        .checked_add(stake_amount)
+        .ok_or(DroogError::CalculationOverflow)?;
+    
+    emit!(GrowStateInitializedEvent {
+        match_id,
+        player_a: match_state.player_a,
+        player_b: match_state.player_b,
+    });
+    
+    emit!(MatchActivatedEvent {
+        match_id,
+        player_a: match_state.player_a,
+        player_b: match_state.player_b,
+        total_escrowed: pot,
+        amount_burned: 0,
+        final_pot: pot,
+        timestamp: current_ts,
+    });
+    
+    emit!(DoubleOrNothingAcceptedEvent {
+        previous_match_id: source_match.match_id,
+        match_id,
+        winner: offer.winner,
+        loser: offer.loser,
+        pot,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(match_id_hash: [u8; 32])]
+pub struct AcceptDoubleOrNothing<'info> {
+    // ========== Source Match ==========
+    
+    /// The settling match whose pot is carried over
+    #[account(
+        seeds = [
+            b"match",
+            source_match_state.load()?.match_id_hash.as_ref(),
+            source_match_state.load()?.player_a.as_ref(),
+            source_match_state.load()?.player_b.as_ref()
+        ],
+        bump = source_match_state.load()?.bump
+    )]
+    pub source_match_state: AccountLoader<'info, MatchState>,
+    
+    /// Source stake state (Settling; moves to Finalized)
+    #[account(
+        mut,
+        seeds = [b"stake", source_match_state.load()?.match_id_hash.as_ref()],
+        bump = source_stake_state.bump,
+        constraint = source_stake_state.allows(LifecycleAction::DoubleOrNothing) @ DroogError::SettlementNotPending,
+    )]
+    pub source_stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        mut,
+        seeds = [DOUBLE_OR_NOTHING_SEED, source_stake_state.match_id_hash.as_ref()],
+        bump = offer.bump,
+        constraint = offer.loser == loser.key() @ DroogError::InvalidPlayer,
+    )]
+    pub offer: Box<Account<'info, DoubleOrNothingOffer>>,
+    
+    /// Source escrow (drained into the new escrow)
+    #[account(
+        mut,
+        seeds = [b"escrow", source_stake_state.match_id_hash.as_ref()],
+        bump = source_stake_state.escrow_bump,
+    )]
+    pub source_escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Source escrow authority PDA (signs the carry-over transfer)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, source_stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub source_escrow_authority: UncheckedAccount<'info>,
+    
+    /// Source settlement history PDA (rewritten as rolled over)
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_RECORD_SEED, source_stake_state.match_id_hash.as_ref()],
+        bump = source_settlement_record.bump,
+    )]
+    pub source_settlement_record: Box<Account<'info, SettlementRecord>>,
+    
+    // ========== New Match PDAs ==========
+    
+    #[account(
+        init,
+        payer = loser,
+        space = 8 + MatchState::SIZE,
+        seeds = [
+            b"match",
+            match_id_hash.as_ref(),
+            source_match_state.load()?.player_a.as_ref(),
+            source_match_state.load()?.player_b.as_ref()
+        ],
+        bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        init,
+        payer = loser,
+        space = MatchStakeState::SIZE,
+        seeds = [b"stake", match_id_hash.as_ref()],
+        bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        init,
+        payer = loser,
+        space = MatchGrowState::SIZE,
+        seeds = [b"grow", match_id_hash.as_ref()],
+        bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// Required unless the source match is delivery-less
+    #[account(
+        init,
+        payer = loser,
+        space = MatchDeliveryState::SIZE,
+        seeds = [b"delivery", match_id_hash.as_ref()],
+        bump
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+    
+    // ========== Token Accounts ==========
+    
+    /// $PACKS token mint
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// Loser's $PACKS token account (matches the pot; receives the loser's
+    /// recorded consolation)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = loser,
+    )]
+    pub loser_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// New escrow token account (PDA-controlled)
+    /// Seeds: ["escrow", match_id_hash]
+    #[account(
+        init,
+        payer = loser,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        seeds = [b"escrow", match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// New escrow authority PDA (signs for escrow transfers)
+    /// CHECK: This is a PDA used only as signing authority for escrow
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+    
+    /// Treasury's token account for the source match's recorded rake (owned
+    /// by ProgramConfig's treasury); required unless that rake is zero
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == mint.key() @ DroogError::MintMismatch
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    /// Winner's token account for the recorded finalizer reward; required
+    /// only when the winner finalized the source match with a nonzero reward
+    #[account(
+        mut,
+        constraint = winner_token_account.mint == mint.key() @ DroogError::MintMismatch
+    )]
+    pub winner_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    // ========== Players ==========
+    
+    /// The player the offer was made to (stakes the pot and pays rent)
+    #[account(mut)]
+    pub loser: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+    
+    // ========== Programs ==========
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use crate::state::{MatchStakeState, LifecycleAction, StakeRaisedEvent, ProgramConfig, PROGRAM_CONFIG_SEED};
+use crate::errors::DroogError;
+
+/// Raise Player A's stake before Player B joins
+/// 
+/// Requirements:
+/// - Status must be Pending (Player B hasn't joined)
+/// - Only Player A can call this; not available for practice matches
+/// - amount > 0
+/// 
+/// The extra tokens go straight to escrow (NO BURN - the burn at join is
+/// computed on the final combined total). Player B must match the raised
+/// stake to join, so they should re-read stake_state before joining.
+/// cancel_match and reap_abandoned refund the full raised amount.
+pub fn add_stake(ctx: Context<AddStake>, amount: u64) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    
+    let new_stake = stake_state.raise_stake(amount)?;
+    
+    require!(
+        ctx.accounts.player_a_token_account.amount >= amount,
+        DroogError::InsufficientStakeBalance
+    );
+    
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.player_a_token_account.to_account_info(),
+        to: ctx.accounts.escrow_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.player_a.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    
+    emit!(StakeRaisedEvent {
+        match_id: stake_state.match_id,
+        player_a: stake_state.player_a,
+        amount_added: amount,
+        new_stake,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.player_a == player_a.key() @ DroogError::InvalidPlayer,
+        constraint = stake_state.allows(LifecycleAction::RaiseStake) @ DroogError::MatchNotPending,
+    )]
+    pub stake_state: Account<'info, MatchStakeState>,
+    
+    /// $PACKS token mint
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Player A's $PACKS token account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = player_a,
+    )]
+    pub player_a_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Escrow token account (initialized by init_match)
+    #[account(
+        mut,
+        seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub player_a: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -4,7 +4,8 @@ use anchor_spl::token_interface::{
 };
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::{
-    MatchStakeState, MatchStatus, CANCEL_TIMEOUT_SECONDS, MatchCancelledEvent,
+    MatchStakeState, MatchStatus, LifecycleAction, CANCEL_TIMEOUT_SECONDS, ESCROW_AUTH_SEED, MatchCancelledEvent,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
 };
 use crate::errors::DroogError;
 
@@ -20,9 +21,15 @@ use crate::errors::DroogError;
 /// - Timeout must have elapsed (CANCEL_TIMEOUT_SECONDS)
 /// - Only Player A can call this
 /// 
+/// Practice matches have nothing escrowed: the token accounts may be
+/// omitted and no transfer happens.
+/// 
 /// Authority: Solana ONLY
 /// - Refund is 100% (no burn occurred in Pending state)
 pub fn cancel_match(ctx: Context<CancelMatch>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let stake_state = &mut ctx.accounts.stake_state;
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
@@ -30,10 +37,7 @@ pub fn cancel_match(ctx: Context<CancelMatch>) -> Result<()> {
     // ========== Invariant Checks ==========
     
     // Must be in Pending status
-    require!(
-        stake_state.status == MatchStatus::Pending,
-        DroogError::MatchNotPending
-    );
+    stake_state.check_lifecycle(LifecycleAction::Cancel)?;
     
     // Player B must NOT have joined
     require!(
@@ -50,28 +54,39 @@ pub fn cancel_match(ctx: Context<CancelMatch>) -> Result<()> {
     // ========== Refund Player A 100% ==========
     // No burn occurred because match never activated
     
-    let refund_amount = stake_state.player_a_escrowed;
-    
-    let match_id_hash = stake_state.match_id_hash;
-    let escrow_auth_bump = ctx.bumps.escrow_authority;
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"escrow_auth",
-        match_id_hash.as_ref(),
-        &[escrow_auth_bump],
-    ]];
-    
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.escrow_token_account.to_account_info(),
-        to: ctx.accounts.player_a_token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        authority: ctx.accounts.escrow_authority.to_account_info(),
-    };
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-        signer_seeds,
-    );
-    transfer_checked(transfer_ctx, refund_amount, ctx.accounts.mint.decimals)?;
+    let refund_amount = stake_state.stake_amount;
+    
+    if !stake_state.practice {
+        let (Some(mint), Some(player_a_token_account), Some(escrow_token_account), Some(escrow_authority)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.player_a_token_account.as_ref(),
+            ctx.accounts.escrow_token_account.as_ref(),
+            ctx.accounts.escrow_authority.as_ref(),
+        ) else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        
+        let match_id_hash = stake_state.match_id_hash;
+        let escrow_auth_bump = ctx.bumps.escrow_authority.unwrap_or_default();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            ESCROW_AUTH_SEED,
+            match_id_hash.as_ref(),
+            &[escrow_auth_bump],
+        ]];
+        
+        let transfer_accounts = TransferChecked {
+            from: escrow_token_account.to_account_info(),
+            to: player_a_token_account.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: escrow_authority.to_account_info(),
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_ctx, refund_amount, mint.decimals)?;
+    }
     
     // ========== Update State ==========
     stake_state.status = MatchStatus::Cancelled;
@@ -82,6 +97,7 @@ pub fn cancel_match(ctx: Context<CancelMatch>) -> Result<()> {
         match_id: stake_state.match_id,
         player_a: stake_state.player_a,
         amount_refunded: refund_amount,
+        mutual: false,
         timestamp: current_ts,
     });
     
@@ -97,14 +113,15 @@ pub struct CancelMatch<'info> {
         seeds = [b"stake", stake_state.match_id_hash.as_ref()],
         bump = stake_state.bump,
         constraint = stake_state.player_a == player_a.key() @ DroogError::InvalidPlayer,
-        constraint = stake_state.status == MatchStatus::Pending @ DroogError::MatchNotPending,
+        constraint = stake_state.allows(LifecycleAction::Cancel) @ DroogError::MatchNotPending,
     )]
     pub stake_state: Account<'info, MatchStakeState>,
     
     // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
     
     /// $PACKS token mint
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
     
     /// Player A's $PACKS token account (receives refund)
     #[account(
@@ -112,7 +129,7 @@ pub struct CancelMatch<'info> {
         associated_token::mint = mint,
         associated_token::authority = player_a,
     )]
-    pub player_a_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub player_a_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow token account
     #[account(
@@ -120,15 +137,15 @@ pub struct CancelMatch<'info> {
         seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
         bump = stake_state.escrow_bump,
     )]
-    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow authority PDA (signs for refund transfer)
     /// CHECK: This is a PDA used only as signing authority
     #[account(
-        seeds = [b"escrow_auth", stake_state.match_id_hash.as_ref()],
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
         bump
     )]
-    pub escrow_authority: UncheckedAccount<'info>,
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
     
     // ========== Players ==========
     
@@ -141,4 +158,9 @@ pub struct CancelMatch<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{
+    MatchStakeState, MatchStatus, LifecycleAction, ESCROW_AUTH_SEED, MatchCancelledEvent,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Cancel a pending match by mutual consent and refund Player A immediately
+/// 
+/// cancel_match makes Player A wait out CANCEL_TIMEOUT_SECONDS so they cannot
+/// pull the stake out from under a Player B who is about to join. When both
+/// players sign there is nothing to protect, so the timeout is skipped.
+/// 
+/// Constraints:
+/// - Status must be Pending
+/// - Player B must NOT have staked yet (so only Player A is refunded)
+/// - Both Player A and Player B must sign
+/// 
+/// Practice matches have nothing escrowed: the token accounts may be
+/// omitted and no transfer happens.
+/// 
+/// Authority: Solana ONLY
+/// - Refund is 100% (no burn occurred in Pending state)
+pub fn cancel_match_mutual(ctx: Context<CancelMatchMutual>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    // ========== Invariant Checks ==========
+    
+    // Must be in Pending status
+    stake_state.check_lifecycle(LifecycleAction::Cancel)?;
+    
+    // Player B must NOT have joined
+    require!(
+        stake_state.can_cancel_mutual(),
+        DroogError::PlayerBAlreadyJoined
+    );
+    
+    // ========== Refund Player A 100% ==========
+    // No burn occurred because match never activated
+    
+    let refund_amount = stake_state.stake_amount;
+    
+    if !stake_state.practice {
+        let (Some(mint), Some(player_a_token_account), Some(escrow_token_account), Some(escrow_authority)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.player_a_token_account.as_ref(),
+            ctx.accounts.escrow_token_account.as_ref(),
+            ctx.accounts.escrow_authority.as_ref(),
+        ) else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        
+        let match_id_hash = stake_state.match_id_hash;
+        let escrow_auth_bump = ctx.bumps.escrow_authority.unwrap_or_default();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            ESCROW_AUTH_SEED,
+            match_id_hash.as_ref(),
+            &[escrow_auth_bump],
+        ]];
+        
+        let transfer_accounts = TransferChecked {
+            from: escrow_token_account.to_account_info(),
+            to: player_a_token_account.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: escrow_authority.to_account_info(),
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_ctx, refund_amount, mint.decimals)?;
+    }
+    
+    // ========== Update State ==========
+    stake_state.status = MatchStatus::Cancelled;
+    stake_state.player_a_escrowed = 0;
+    
+    emit!(MatchCancelledEvent {
+        match_id: stake_state.match_id,
+        player_a: stake_state.player_a,
+        amount_refunded: refund_amount,
+        mutual: true,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelMatchMutual<'info> {
+    // ========== Stake State ==========
+    
+    #[account(
+        mut,
+        seeds = [b"stake", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.player_a == player_a.key() @ DroogError::InvalidPlayer,
+        constraint = stake_state.player_b == player_b.key() @ DroogError::InvalidPlayer,
+        constraint = stake_state.allows(LifecycleAction::Cancel) @ DroogError::MatchNotPending,
+    )]
+    pub stake_state: Account<'info, MatchStakeState>,
+    
+    // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
+    
+    /// $PACKS token mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// Player A's $PACKS token account (receives refund)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = player_a,
+    )]
+    pub player_a_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow token account
+    #[account(
+        mut,
+        seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow authority PDA (signs for refund transfer)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
+    
+    // ========== Players ==========
+    
+    /// Player A (receives the refund)
+    #[account(mut)]
+    pub player_a: Signer<'info>,
+    
+    /// Player B must co-sign so neither side can be griefed
+    pub player_b: Signer<'info>,
+    
+    // ========== Programs ==========
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, SLOTS_PER_PLAYER,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Clear a rotten plant out of a grow slot
+/// 
+/// A Ready plant left unharvested for ROT_WINDOW_SECONDS rots: it can no
+/// longer be harvested (PlantRotted) and blocks the slot until cleared here.
+/// The slot is freed for replanting with no inventory gain.
+pub fn clear_slot(ctx: Context<ClearSlot>, slot_index: u8) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let match_state = ctx.accounts.match_state.load()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    // Validate slot index
+    require!(
+        (slot_index as usize) < SLOTS_PER_PLAYER,
+        DroogError::InvalidSlotIndex
+    );
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
+    let tunables = grow_state.tunables;
+    let slot = if is_player_a {
+        &mut grow_state.player_a_slots[slot_index as usize]
+    } else {
+        &mut grow_state.player_b_slots[slot_index as usize]
+    };
+    let strain_level = slot.clear_rotten(&tunables, current_ts)?;
+    
+    emit!(SlotClearedEvent {
+        match_id: grow_state.match_id,
+        player,
+        slot_index,
+        strain_level,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClearSlot<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation)
+    #[account(
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player clearing their slot
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted when a rotten plant is cleared from a slot
+#[event]
+pub struct SlotClearedEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub slot_index: u8,
+    /// Strain level of the plant that rotted
+    pub strain_level: u8,
+    pub timestamp: i64,
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchState, MatchStakeState, MatchGrowState, MatchDeliveryState, LifecycleAction,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Close a settled match's stake, grow and delivery accounts, returning their
+/// rent to a participant
+/// 
+/// Requirements:
+/// 1. Stake is Finalized, Cancelled or Disputed - nothing left in escrow
+///    (Pending, Active and Settling fail with MatchNotClosed)
+/// 2. Signer is player_a or player_b
+/// 3. recipient is player_a or player_b
+/// 
+/// grow_state and delivery_state are optional: a cancelled match may never
+/// have created them, and delivery-less matches have no delivery state.
+/// 
+/// MatchState and the SettlementRecord stay open: the match account keeps its
+/// PDAs from being reused for the same match_id_hash, and the record is the
+/// permanent audit trail. Closing the stake also ends rematch from this match.
+pub fn close_match_accounts(ctx: Context<CloseMatchAccounts>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    ctx.accounts.stake_state.check_lifecycle(LifecycleAction::CloseAccounts)?;
+    
+    let match_state = ctx.accounts.match_state.load()?;
+    let caller = ctx.accounts.player.key();
+    require!(match_state.opponent_of(caller).is_some(), DroogError::InvalidPlayer);
+    
+    // Lamports move when the instruction exits (Anchor `close`)
+    let stake_lamports = ctx.accounts.stake_state.to_account_info().lamports();
+    let grow_lamports = ctx.accounts.grow_state
+        .as_ref()
+        .map(|g| g.to_account_info().lamports())
+        .unwrap_or(0);
+    let delivery_lamports = ctx.accounts.delivery_state
+        .as_ref()
+        .map(|d| d.to_account_info().lamports())
+        .unwrap_or(0);
+    
+    emit!(AccountsClosedEvent {
+        match_id: match_state.match_id,
+        recipient: ctx.accounts.recipient.key(),
+        closed_by: caller,
+        stake_lamports,
+        grow_lamports,
+        delivery_lamports,
+        total_lamports: stake_lamports + grow_lamports + delivery_lamports,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseMatchAccounts<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"grow", match_state.load()?.match_id_hash.as_ref()],
+        bump = grow_state.bump,
+    )]
+    pub grow_state: Option<Box<Account<'info, MatchGrowState>>>,
+    
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"delivery", match_state.load()?.match_id_hash.as_ref()],
+        bump = delivery_state.bump,
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+    
+    pub player: Signer<'info>,
+    
+    /// Receives the reclaimed rent (player_a or player_b)
+    /// CHECK: Validated against match_state's players
+    #[account(
+        mut,
+        constraint = match_state.load()?.opponent_of(recipient.key()).is_some() @ DroogError::InvalidPlayer
+    )]
+    pub recipient: UncheckedAccount<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Rent reclaimed by close_match_accounts (0 for an account not supplied)
+#[event]
+pub struct AccountsClosedEvent {
+    pub match_id: u64,
+    pub recipient: Pubkey,
+    pub closed_by: Pubkey,
+    pub stake_lamports: u64,
+    pub grow_lamports: u64,
+    pub delivery_lamports: u64,
+    pub total_lamports: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    DoubleOrNothingOffer, DoubleOrNothingDeclinedEvent, MatchStakeState, DOUBLE_OR_NOTHING_SEED,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Decline a double-or-nothing offer
+/// 
+/// Nothing moves: the source match's pending settlement stays as recorded and
+/// execute_settlement pays the original winner once the challenge window
+/// passes. The offer PDA keeps the Declined status, so it can't be reopened.
+pub fn decline_double_or_nothing(ctx: Context<DeclineDoubleOrNothing>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let offer = &mut ctx.accounts.offer;
+    let clock = Clock::get()?;
+    
+    offer.decline()?;
+    
+    emit!(DoubleOrNothingDeclinedEvent {
+        match_id: ctx.accounts.stake_state.match_id,
+        winner: offer.winner,
+        loser: offer.loser,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeclineDoubleOrNothing<'info> {
+    /// Source match's stake state (for the match_id)
+    #[account(
+        seeds = [b"stake", offer.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        mut,
+        seeds = [DOUBLE_OR_NOTHING_SEED, offer.match_id_hash.as_ref()],
+        bump = offer.bump,
+        constraint = offer.loser == loser.key() @ DroogError::InvalidPlayer,
+    )]
+    pub offer: Account<'info, DoubleOrNothingOffer>,
+    
+    /// The player the offer was made to
+    pub loser: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use crate::state::{
+    MatchState, MatchStakeState, MatchStatus, LifecycleAction, ESCROW_AUTH_SEED, StakePayoutEvent, MatchDrawEvent,
+    ProgramConfig, PROGRAM_CONFIG_SEED, SettlementRecord, SETTLEMENT_RECORD_SEED,
+};
+use crate::errors::DroogError;
+
+/// Pay out a delayed settlement recorded by finalize_match
+/// 
+/// Requirements:
+/// 1. Stake status == Settling
+/// 2. Challenge window has passed
+/// 3. Settlement is not frozen, or the freeze outlasted the resolution window
+/// 
+/// Permissionless: pays the split finalize_match recorded, same as an
+/// immediate settlement would have. The rake goes to treasury_token_account
+/// (owned by ProgramConfig's treasury), the loser's consolation or draw half
+/// to loser_token_account, and the finalizer's reward to
+/// finalizer_token_account; each is required only when its share is nonzero.
+/// The recorded winner receives the rest of the escrow, and the
+/// SettlementRecord and MatchState's result snapshot are rewritten with what
+/// was actually paid.
+pub fn execute_settlement(ctx: Context<ExecuteSettlement>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    stake_state.check_execute_settlement(current_ts)?;
+    
+    let pending = stake_state.pending_settlement;
+    let winner = pending.winner;
+    let (loser, winner_sales, loser_sales) = if winner == match_state.player_a {
+        (match_state.player_b, match_state.player_a_sales, match_state.player_b_sales)
+    } else {
+        (match_state.player_a, match_state.player_b_sales, match_state.player_a_sales)
+    };
+    
+    // Escrow balance is authoritative; the recorded shares come out of it first
+    let rake = pending.rake;
+    let payout_amount = pending.winner_payout(ctx.accounts.escrow_token_account.amount)?;
+    
+    let winner_token_account = &ctx.accounts.winner_token_account;
+    let mut payouts = [
+        (winner_token_account, 0),
+        (winner_token_account, payout_amount),
+        (winner_token_account, 0),
+        (winner_token_account, 0),
+    ];
+    if rake > 0 {
+        let Some(treasury_token_account) = ctx.accounts.treasury_token_account.as_ref() else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        let treasury = ProgramConfig::treasury(&ctx.accounts.program_config)?;
+        MatchStakeState::validate_treasury_destination(&treasury_token_account.owner, treasury)?;
+        payouts[0] = (treasury_token_account, rake);
+    }
+    
+    // The loser's half of a draw, or their consolation
+    if pending.loser_amount > 0 {
+        let Some(loser_token_account) = ctx.accounts.loser_token_account.as_ref() else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        MatchStakeState::validate_payout_destination(&loser_token_account.owner, &loser)?;
+        payouts[2] = (loser_token_account, pending.loser_amount);
+    }
+    
+    // The participant who finalized
+    if pending.finalizer_reward > 0 {
+        let Some(finalizer_token_account) = ctx.accounts.finalizer_token_account.as_ref() else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        MatchStakeState::validate_payout_destination(&finalizer_token_account.owner, &pending.finalizer)?;
+        payouts[3] = (finalizer_token_account, pending.finalizer_reward);
+    }
+    
+    let match_id_hash = stake_state.match_id_hash;
+    let escrow_auth_bump = ctx.bumps.escrow_authority;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ESCROW_AUTH_SEED,
+        match_id_hash.as_ref(),
+        &[escrow_auth_bump],
+    ]];
+    
+    for (destination, amount) in payouts {
+        if amount == 0 {
+            continue;
+        }
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: destination.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.escrow_authority.to_account_info(),
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_ctx, amount, ctx.accounts.mint.decimals)?;
+    }
+    
+    stake_state.status = MatchStatus::Finalized;
+    ctx.accounts.settlement_record.record_execution(&pending, payout_amount, current_ts);
+    
+    if pending.is_draw {
+        match_state.revise_result(None, payout_amount + pending.loser_amount);
+        emit!(MatchDrawEvent {
+            match_id: match_state.match_id,
+            player_a: match_state.player_a,
+            player_b: match_state.player_b,
+            player_a_amount: payout_amount,
+            player_b_amount: pending.loser_amount,
+            sales: match_state.player_a_sales,
+            rake,
+            timestamp: current_ts,
+        });
+        return Ok(());
+    }
+    
+    match_state.revise_result(Some(winner), payout_amount);
+    emit!(StakePayoutEvent {
+        match_id: match_state.match_id,
+        winner,
+        loser,
+        amount: payout_amount,
+        winner_sales,
+        loser_sales,
+        decided_by: match_state.determine_winner().decided_by,
+        loser_consolation: pending.loser_amount,
+        rake,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSettlement<'info> {
+    /// Result snapshot updated with the payout actually made
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.allows(LifecycleAction::ExecuteSettlement) @ DroogError::SettlementNotPending,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// Settlement history PDA written by finalize_match
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_RECORD_SEED, stake_state.match_id_hash.as_ref()],
+        bump = settlement_record.bump,
+    )]
+    pub settlement_record: Box<Account<'info, SettlementRecord>>,
+    
+    /// $PACKS token mint
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Escrow token account
+    #[account(
+        mut,
+        seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Escrow authority PDA (signs for payout transfer)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+    
+    /// Recorded winner's token account
+    #[account(
+        mut,
+        constraint = winner_token_account.owner == stake_state.pending_settlement.winner @ DroogError::InvalidPlayer
+    )]
+    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Treasury's token account (owned by ProgramConfig's treasury);
+    /// required unless the recorded rake is zero
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == mint.key() @ DroogError::MintMismatch
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The other player's token account (Player B's in a draw); required
+    /// unless the recorded loser share is zero
+    #[account(
+        mut,
+        constraint = loser_token_account.mint == mint.key() @ DroogError::MintMismatch
+    )]
+    pub loser_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Finalizer's token account for the recorded reward; required unless
+    /// the reward is zero (may be the winner's or loser's account)
+    #[account(
+        mut,
+        constraint = finalizer_token_account.mint == mint.key() @ DroogError::MintMismatch
+    )]
+    pub finalizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Anyone may execute once the window has passed
+    pub executor: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, FERTILIZER_BOOST_SECONDS,
+    REPLAY_ACTION_FERTILIZE,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Spend a fertilizer charge to speed up a Growing plant
+/// 
+/// This instruction:
+/// 1. Validates the player, match timing, and stake lifecycle
+/// 2. Requires a Growing plant in the slot and a remaining charge
+///    (FERTILIZER_CHARGES_PER_PLAYER per match)
+/// 3. Moves the plant's planted_at up to FERTILIZER_BOOST_SECONDS earlier,
+///    capped so it is still Growing now (never ripens on the spot)
+/// 
+/// Smell is derived from planted_at, so a fertilized plant smells as if it
+/// had been planted earlier; exposure is accrued before the shift.
+pub fn fertilize_slot(ctx: Context<FertilizeSlot>, slot_index: u8) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
+    let (strain_level, seconds_skipped) = grow_state.fertilize(is_player_a, slot_index, current_ts)?;
+    let slot = if is_player_a {
+        &grow_state.player_a_slots[slot_index as usize]
+    } else {
+        &grow_state.player_b_slots[slot_index as usize]
+    };
+    let ready_ts = slot.projected_ready_ts(&grow_state.tunables).unwrap_or_default();
+    let charges_left = if is_player_a {
+        grow_state.player_a_fertilizer_charges
+    } else {
+        grow_state.player_b_fertilizer_charges
+    };
+    
+    emit!(FertilizeEvent {
+        match_id: grow_state.match_id,
+        player,
+        slot_index,
+        strain_level,
+        seconds_skipped,
+        ready_ts,
+        charges_left,
+        timestamp: current_ts,
+    });
+    
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_FERTILIZE,
+        player,
+        slot_index,
+        strain_level,
+        seconds_skipped.min(FERTILIZER_BOOST_SECONDS) as u8,
+        current_ts,
+    ));
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FertilizeSlot<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation and replay seq)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player fertilizing their own slot
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted when a player spends a fertilizer charge
+#[event]
+pub struct FertilizeEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub slot_index: u8,
+    pub strain_level: u8,
+    /// How far planted_at moved back (< FERTILIZER_BOOST_SECONDS when capped)
+    pub seconds_skipped: i64,
+    /// When the plant now becomes Ready
+    pub ready_ts: i64,
+    pub charges_left: u8,
+    pub timestamp: i64,
+}
@@ -3,22 +3,82 @@ use anchor_spl::token_interface::{
     Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
 };
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{MatchState, MatchStakeState, MatchStatus, StakePayoutEvent};
+use crate::state::{
+    MatchState, MatchGrowState, MatchStakeState, MatchDeliveryState, MatchStatus, LifecycleAction, ESCROW_AUTH_SEED, DecidedBy,
+    StakePayoutEvent, SettlementPendingEvent, PendingSettlement, MatchDrawEvent, SettlementRecord, SETTLEMENT_RECORD_SEED,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
 use crate::errors::DroogError;
 
 /// Finalize a match after it has ended and pay out winner
 /// 
 /// This instruction enforces strict invariants:
 /// 1. Can only be called once (is_finalized must be false)
-/// 2. Cannot be called early (current_ts >= end_ts)
+/// 2. Cannot be called early (current_ts >= end_ts, or mercy rule triggered)
 /// 3. Cannot be called by non-participant (signer must be player_a or player_b)
+///    until FINALIZE_GRACE_SECONDS after end_ts; then any keeper may finalize
+///    an abandoned match (payouts still go to the players' own accounts)
 /// 4. Stake must be Active (both players committed)
-/// 5. Winner is determined purely by sales count
+/// 5. Winner is determined by sales count, then reputation on a tie, then
+///    Player A (decided_by says which rule applied)
+/// 
+/// Draws: equal sales and reputation split the escrow 50/50 between both players (odd
+/// remainder to the lower pubkey; a half that rounds to zero is skipped).
+/// winner_token_account must then be Player A's, loser_token_account Player
+/// B's, and payout_destination is ignored.
+/// 
+/// Loser consolation: a loser with positive reputation gets a capped share
+/// carved from the pot (see MatchStakeState::loser_consolation), paid to
+/// loser_token_account.
+/// 
+/// Pot split (opt-in at init for handicapped matches): each player instead
+/// receives their agreed share of the pot for the outcome - decisive or
+/// narrow win for either side, or a draw - with no consolation.
+/// 
+/// Treasury rake: RAKE_BPS of the pot goes to treasury_token_account (owned
+/// by the treasury named in ProgramConfig) before anything else is split;
+/// skipped when it rounds to zero or no treasury is configured. Delayed
+/// settlements record it and pay it out in execute_settlement.
+/// 
+/// Finalizer reward: finalizer_reward_bps of the pot (from the match's
+/// GameConfig snapshot; 0 = off) is carved out first and paid to the
+/// participant who calls finalize, winner or loser, via
+/// finalizer_token_account. The split above applies to the rest. Keepers
+/// get no reward.
+/// 
+/// If grow_state is supplied, sales are cross-checked against harvests and
+/// any discrepancy is flagged in MatchFinalizedEvent (settlement proceeds).
+/// 
+/// Low engagement: near-zero sales, finalized right at end_ts, and no
+/// delivery rotations (read from delivery_state if supplied, else 0) are
+/// flagged in MatchFinalizedEvent as an anti-collusion signal (settlement proceeds).
+/// 
+/// Delayed settlement (opt-in at init): the same split - rake, finalizer
+/// reward, draw halves or consolation - is recorded on stake_state (status ->
+/// Settling) and only transferred by execute_settlement after the challenge
+/// window. The match itself is still finalized here; no token accounts beyond
+/// the escrow are needed.
+/// 
+/// Payout destination: winner_token_account, or payout_destination when
+/// supplied. Either may be any token account (ATA or not) of the payout mint,
+/// but it must be owned by the winner. A token account of another mint fails
+/// with MintMismatch.
+/// 
+/// Practice matches finalize normally but move no tokens; the token
+/// accounts may be omitted.
+/// 
+/// Settlement record: the full money flow (escrowed, burned, fee, loser and
+/// winner payouts) is written to a new SettlementRecord PDA, paid for by the
+/// caller.
 /// 
 /// This is settlement code - treat it as sacred.
-/// Winner receives entire remaining escrow balance.
+/// Winner receives the remaining escrow balance (less the rake, finalizer
+/// reward, and any loser consolation; split on a draw).
 pub fn finalize_match(ctx: Context<FinalizeMatch>) -> Result<()> {
-    let match_state = &mut ctx.accounts.match_state;
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
     let stake_state = &mut ctx.accounts.stake_state;
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
@@ -26,73 +86,191 @@ pub fn finalize_match(ctx: Context<FinalizeMatch>) -> Result<()> {
     // ========== Invariant Checks ==========
     
     // Invariant 1: Can only be called once
-    require!(!match_state.is_finalized, DroogError::MatchAlreadyFinalized);
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
     
     // Invariant 2: Cannot be called early
-    require!(current_ts >= match_state.end_ts, DroogError::MatchFinalizationTooEarly);
+    // (unless the mercy rule flagged a blowout)
+    require!(match_state.can_finalize_at(current_ts), DroogError::MatchFinalizationTooEarly);
     
-    // Invariant 3: Cannot be called by non-participant
-    let is_player_a = ctx.accounts.player.key() == match_state.player_a;
-    let is_player_b = ctx.accounts.player.key() == match_state.player_b;
-    require!(is_player_a || is_player_b, DroogError::UnauthorizedFinalization);
+    // Invariant 3: Cannot be called by non-participant (until the grace window lapses)
+    match_state.check_finalize_caller(ctx.accounts.player.key(), current_ts)?;
     
     // Invariant 4: Stake must be Active (both players committed)
-    require!(
-        stake_state.status == MatchStatus::Active,
-        DroogError::MatchNotActive
-    );
+    stake_state.check_lifecycle(LifecycleAction::Finalize)?;
     
     // ========== Determine Winner ==========
-    // Winner is purely determined by sales count (on-chain authoritative)
-    // In case of tie, Player A wins (first mover advantage)
-    
-    let (winner, loser, winner_sales, loser_sales) = if match_state.player_a_sales >= match_state.player_b_sales {
-        (
-            match_state.player_a,
-            match_state.player_b,
-            match_state.player_a_sales,
-            match_state.player_b_sales,
-        )
-    } else {
-        (
-            match_state.player_b,
-            match_state.player_a,
-            match_state.player_b_sales,
-            match_state.player_a_sales,
-        )
-    };
+    // Winner is determined by sales count (on-chain authoritative)
+    // Equal sales: higher reputation wins; equal on both: Player A wins
+    // (first mover advantage) unless the pot is split
+    
+    // Map coverage pays reputation first (tiebreak and consolation see it)
+    let (player_a_diversity_bonus, player_b_diversity_bonus) = match_state.apply_diversity_bonus();
+    
+    let standings = match_state.determine_winner();
+    let (winner, loser) = (standings.winner, standings.loser);
+    let is_draw = stake_state.settles_as_draw(&standings);
+    let decided_by = if is_draw { DecidedBy::Draw } else { standings.decided_by };
+    
+    // ========== Grow Activity Cross-Check ==========
+    // Sales require inventory which requires harvests - flag any desync
+    let inventory_discrepancy = ctx.accounts.grow_state
+        .as_ref()
+        .map(|g| g.has_sales_discrepancy(match_state.player_a_sales, match_state.player_b_sales))
+        .unwrap_or(false);
+    
+    // Smell exposure stats (accrued lazily during play; 0 without grow_state)
+    let (player_a_smell_exposure, player_b_smell_exposure) = ctx.accounts.grow_state
+        .as_ref()
+        .map(|g| (g.player_a_smell_exposure, g.player_b_smell_exposure))
+        .unwrap_or((0, 0));
+    
+    // Anti-collusion signal for off-chain monitoring
+    let rotation_count = ctx.accounts.delivery_state
+        .as_ref()
+        .map(|d| d.rotation_count)
+        .unwrap_or(0);
+    let low_engagement = match_state.is_low_engagement(rotation_count, current_ts);
     
     // ========== Transfer Escrow to Winner ==========
     // Escrow balance is authoritative (post-burn amount)
+    // Practice matches have no escrow: nothing is paid out
+    
+    // Shared with preview_payout
+    let payout_amount = stake_state.payout_amount(
+        ctx.accounts.escrow_token_account.as_ref().map(|e| e.amount),
+    )?;
+    let finalizer = ctx.accounts.player.key();
     
-    let payout_amount = ctx.accounts.escrow_token_account.amount;
+    // The treasury rake comes off the top, whenever the pot is settled
+    let treasury = ProgramConfig::treasury(&ctx.accounts.program_config)?;
+    let rake = MatchStakeState::treasury_rake(payout_amount, treasury)?;
+    let payout_amount = payout_amount - rake;
+    
+    // After the rake, the finalizer's reward comes off the top; then draw
+    // halves, agreed pot-split shares, or the loser's consolation carved from
+    // the rest (shared with preview_payout)
+    let finalizer_reward = stake_state.finalizer_reward(
+        payout_amount,
+        match_state.tunables.finalizer_reward_bps,
+        &finalizer,
+    );
+    let loser_reputation = match_state.reputation_of(loser == match_state.player_a);
+    let (winner_amount, loser_amount) =
+        stake_state.settlement_split(&standings, payout_amount - finalizer_reward, loser_reputation);
     
-    if payout_amount > 0 {
+    if stake_state.delayed_settlement {
+        // The split waits in escrow for execute_settlement
+        stake_state.record_pending_settlement(
+            PendingSettlement {
+                winner,
+                amount: winner_amount,
+                rake,
+                loser_amount,
+                finalizer,
+                finalizer_reward,
+                is_draw,
+                ..Default::default()
+            },
+            current_ts,
+        );
+    } else if !stake_state.practice {
+        let (Some(mint), Some(escrow_token_account), Some(escrow_authority), Some(winner_token_account)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.escrow_token_account.as_ref(),
+            ctx.accounts.escrow_authority.as_ref(),
+            ctx.accounts.winner_token_account.as_ref(),
+        ) else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        
+        // Winners may route the payout to any token account they own (not in a draw)
+        let destination = match ctx.accounts.payout_destination.as_ref() {
+            Some(payout_destination) if !is_draw => payout_destination,
+            _ => winner_token_account,
+        };
+        MatchStakeState::validate_payout_destination(&destination.owner, &winner)?;
+        MatchStakeState::validate_payout_mint(&destination.mint, &mint.key())?;
+        let mut payouts = [(destination, 0), (destination, winner_amount), (destination, 0), (destination, 0)];
+        
+        // Treasury rake goes out before the winner is paid
+        if rake > 0 {
+            let Some(treasury_token_account) = ctx.accounts.treasury_token_account.as_ref() else {
+                return err!(DroogError::StakeAccountsRequired);
+            };
+            MatchStakeState::validate_treasury_destination(&treasury_token_account.owner, treasury)?;
+            MatchStakeState::validate_payout_mint(&treasury_token_account.mint, &mint.key())?;
+            payouts[0] = (treasury_token_account, rake);
+        }
+        
+        // The loser's half of a draw, or their consolation
+        if loser_amount > 0 {
+            let Some(loser_token_account) = ctx.accounts.loser_token_account.as_ref() else {
+                return err!(DroogError::StakeAccountsRequired);
+            };
+            MatchStakeState::validate_payout_destination(&loser_token_account.owner, &loser)?;
+            MatchStakeState::validate_payout_mint(&loser_token_account.mint, &mint.key())?;
+            payouts[2] = (loser_token_account, loser_amount);
+        }
+        
+        // The calling participant's reward for paying the finalize fee
+        if finalizer_reward > 0 {
+            let Some(finalizer_token_account) = ctx.accounts.finalizer_token_account.as_ref() else {
+                return err!(DroogError::StakeAccountsRequired);
+            };
+            MatchStakeState::validate_payout_destination(&finalizer_token_account.owner, &finalizer)?;
+            MatchStakeState::validate_payout_mint(&finalizer_token_account.mint, &mint.key())?;
+            payouts[3] = (finalizer_token_account, finalizer_reward);
+        }
+        
         let match_id_hash = stake_state.match_id_hash;
-        let escrow_auth_bump = ctx.bumps.escrow_authority;
+        let escrow_auth_bump = ctx.bumps.escrow_authority.unwrap_or_default();
         let signer_seeds: &[&[&[u8]]] = &[&[
-            b"escrow_auth",
+            ESCROW_AUTH_SEED,
             match_id_hash.as_ref(),
             &[escrow_auth_bump],
         ]];
         
-        let transfer_accounts = TransferChecked {
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.winner_token_account.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            authority: ctx.accounts.escrow_authority.to_account_info(),
-        };
-        let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_accounts,
-            signer_seeds,
-        );
-        transfer_checked(transfer_ctx, payout_amount, ctx.accounts.mint.decimals)?;
+        for (destination, amount) in payouts {
+            // Skip empty transfers (including a draw half that rounded to zero)
+            if amount == 0 {
+                continue;
+            }
+            let transfer_accounts = TransferChecked {
+                from: escrow_token_account.to_account_info(),
+                to: destination.to_account_info(),
+                mint: mint.to_account_info(),
+                authority: escrow_authority.to_account_info(),
+            };
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+            transfer_checked(transfer_ctx, amount, mint.decimals)?;
+        }
     }
     
     // ========== Update State ==========
-    match_state.is_finalized = true;
-    stake_state.status = MatchStatus::Finalized;
+    if is_draw {
+        match_state.record_result(None, winner_amount + loser_amount, current_ts);
+    } else {
+        match_state.record_result(Some(winner), winner_amount, current_ts);
+    }
+    if !stake_state.delayed_settlement {
+        stake_state.status = MatchStatus::Finalized;
+    }
+    ctx.accounts.settlement_record.record(
+        stake_state,
+        &standings,
+        decided_by,
+        winner_amount,
+        loser_amount,
+        finalizer,
+        finalizer_reward,
+        rake,
+        current_ts,
+        ctx.bumps.settlement_record,
+    );
     
     // Emit finalization event (original)
     emit!(MatchFinalizedEvent {
@@ -102,16 +280,57 @@ pub fn finalize_match(ctx: Context<FinalizeMatch>) -> Result<()> {
         player_b_sales: match_state.player_b_sales,
         player_a_reputation: match_state.player_a_reputation,
         player_b_reputation: match_state.player_b_reputation,
+        inventory_discrepancy,
+        player_a_smell_exposure,
+        player_b_smell_exposure,
+        decided_by,
+        low_engagement,
+        player_a_diversity_bonus,
+        player_b_diversity_bonus,
+        finalizer,
+        finalizer_reward,
     });
     
+    if stake_state.delayed_settlement {
+        emit!(SettlementPendingEvent {
+            match_id: match_state.match_id,
+            winner,
+            amount: winner_amount,
+            rake,
+            loser_amount,
+            finalizer_reward,
+            is_draw,
+            executable_at_ts: stake_state.pending_settlement.executable_at_ts,
+            timestamp: current_ts,
+        });
+        return Ok(());
+    }
+    
+    if is_draw {
+        emit!(MatchDrawEvent {
+            match_id: match_state.match_id,
+            player_a: match_state.player_a,
+            player_b: match_state.player_b,
+            player_a_amount: winner_amount,
+            player_b_amount: loser_amount,
+            sales: match_state.player_a_sales,
+            rake,
+            timestamp: current_ts,
+        });
+        return Ok(());
+    }
+    
     // Emit payout event
     emit!(StakePayoutEvent {
         match_id: match_state.match_id,
         winner,
         loser,
-        amount: payout_amount,
-        winner_sales,
-        loser_sales,
+        amount: winner_amount,
+        winner_sales: standings.winner_sales,
+        loser_sales: standings.loser_sales,
+        decided_by,
+        loser_consolation: loser_amount,
+        rake,
         timestamp: current_ts,
     });
     
@@ -121,32 +340,58 @@ pub fn finalize_match(ctx: Context<FinalizeMatch>) -> Result<()> {
 #[derive(Accounts)]
 pub struct FinalizeMatch<'info> {
     // ========== Game State ==========
-    // Boxed to avoid stack overflow (MatchState is large)
     
     #[account(
         mut,
         seeds = [
             b"match",
-            match_state.match_id_hash.as_ref(),
-            match_state.player_a.as_ref(),
-            match_state.player_b.as_ref()
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
         ],
-        bump = match_state.bump
+        bump = match_state.load()?.bump
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
     
     #[account(
         mut,
         seeds = [b"stake", stake_state.match_id_hash.as_ref()],
         bump = stake_state.bump,
-        constraint = stake_state.status == MatchStatus::Active @ DroogError::MatchNotActive,
+        constraint = stake_state.allows(LifecycleAction::Finalize) @ DroogError::MatchNotActive,
     )]
     pub stake_state: Box<Account<'info, MatchStakeState>>,
     
+    /// Optional grow state for the harvest/sales cross-check
+    #[account(
+        seeds = [b"grow", match_state.load()?.match_id_hash.as_ref()],
+        bump = grow_state.bump,
+        constraint = grow_state.match_id_hash == match_state.load()?.match_id_hash @ DroogError::MatchIdMismatch
+    )]
+    pub grow_state: Option<Box<Account<'info, MatchGrowState>>>,
+    
+    /// Optional delivery state for the low-engagement rotation count
+    #[account(
+        seeds = [b"delivery", match_state.load()?.match_id_hash.as_ref()],
+        bump = delivery_state.bump,
+        constraint = delivery_state.match_id_hash == match_state.load()?.match_id_hash @ DroogError::MatchIdMismatch
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+    
+    /// Settlement history PDA, created here (rent paid by the caller)
+    #[account(
+        init,
+        payer = player,
+        space = SettlementRecord::SIZE,
+        seeds = [SETTLEMENT_RECORD_SEED, match_state.load()?.match_id_hash.as_ref()],
+        bump
+    )]
+    pub settlement_record: Box<Account<'info, SettlementRecord>>,
+    
     // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
     
     /// $PACKS token mint
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
     
     /// Escrow token account
     #[account(
@@ -154,29 +399,59 @@ pub struct FinalizeMatch<'info> {
         seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
         bump = stake_state.escrow_bump,
     )]
-    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow authority PDA (signs for payout transfer)
     /// CHECK: This is a PDA used only as signing authority
     #[account(
-        seeds = [b"escrow_auth", stake_state.match_id_hash.as_ref()],
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
         bump
     )]
-    pub escrow_authority: UncheckedAccount<'info>,
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
     
     /// Winner's token account (receives payout)
-    /// Constraint: must belong to either player_a or player_b
+    /// Constraint: must belong to either player_a or player_b, and hold the stake mint
     #[account(
         mut,
         constraint = (
-            winner_token_account.owner == match_state.player_a ||
-            winner_token_account.owner == match_state.player_b
+            winner_token_account.owner == match_state.load()?.player_a ||
+            winner_token_account.owner == match_state.load()?.player_b
+        ) @ DroogError::InvalidPlayer,
+        constraint = mint.as_ref().is_none_or(|mint| winner_token_account.mint == mint.key())
+            @ DroogError::MintMismatch
+    )]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Loser's token account (Player B's in a draw); required only when the
+    /// loser is paid something (their half of a draw, or reputation consolation)
+    #[account(
+        mut,
+        constraint = (
+            loser_token_account.owner == match_state.load()?.player_a ||
+            loser_token_account.owner == match_state.load()?.player_b
         ) @ DroogError::InvalidPlayer
     )]
-    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub loser_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Optional payout override (e.g. a non-ATA account the winner controls)
+    /// Must be owned by the winner; receives the payout instead of winner_token_account
+    #[account(mut)]
+    pub payout_destination: Option<InterfaceAccount<'info, TokenAccount>>,
     
-    // ========== Player (Caller) ==========
+    /// Treasury's token account for the rake (owned by ProgramConfig's
+    /// treasury); required unless the rake is zero
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
+    /// Caller's token account for the finalizer reward; required only when a
+    /// participant finalizes with a nonzero reward (may be the payout account)
+    #[account(mut)]
+    pub finalizer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    // ========== Caller (participant, or anyone after the grace window) ==========
+    
+    /// Mutable to pay for settlement_record
+    #[account(mut)]
     pub player: Signer<'info>,
     
     // ========== Programs ==========
@@ -184,6 +459,11 @@ pub struct FinalizeMatch<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 #[event]
@@ -194,4 +474,19 @@ pub struct MatchFinalizedEvent {
     pub player_b_sales: u32,
     pub player_a_reputation: i32,
     pub player_b_reputation: i32,
+    /// True if either player's sales exceed their harvests (accounting desync)
+    pub inventory_discrepancy: bool,
+    /// Integrated smell per player over the match (smell-seconds, 0 without grow_state)
+    pub player_a_smell_exposure: u64,
+    pub player_b_smell_exposure: u64,
+    /// Rule that picked the winner (sales margin or tiebreak), or Draw if the pot was split
+    pub decided_by: DecidedBy,
+    /// Near-zero sales, finalized right at the end, no rotations (anti-collusion signal)
+    pub low_engagement: bool,
+    /// Reputation awarded for distinct customers served (included in the reputations above)
+    pub player_a_diversity_bonus: i32,
+    pub player_b_diversity_bonus: i32,
+    /// Caller of finalize_match and the reward carved from the pot for them
+    pub finalizer: Pubkey,
+    pub finalizer_reward: u64,
 }
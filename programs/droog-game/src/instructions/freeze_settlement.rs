@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::state::{MatchStakeState, SettlementFrozenEvent};
+use crate::errors::DroogError;
+
+/// Freeze a delayed settlement during its challenge window
+/// 
+/// Either player may freeze (e.g. to dispute the result). A frozen
+/// settlement can't be executed; the pot stays in escrow until the dispute
+/// admin either releases it (resolve_dispute) or refunds both players
+/// (reverse_finalization). If the admin does neither within
+/// DISPUTE_RESOLUTION_WINDOW_SECONDS, execute_settlement pays the recorded
+/// winner as if it had never been frozen.
+/// 
+/// Not blocked by the kill-switch (see PAUSE_EXEMPT_INSTRUCTIONS): the
+/// challenge window keeps running while the program is paused, and a player
+/// must not lose the chance to dispute because of it.
+pub fn freeze_settlement(ctx: Context<FreezeSettlement>) -> Result<()> {
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    stake_state.freeze_settlement(current_ts)?;
+    
+    emit!(SettlementFrozenEvent {
+        match_id: stake_state.match_id,
+        frozen_by: ctx.accounts.player.key(),
+        winner: stake_state.pending_settlement.winner,
+        amount: stake_state.pending_settlement.amount,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FreezeSettlement<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = (
+            player.key() == stake_state.player_a ||
+            player.key() == stake_state.player_b
+        ) @ DroogError::InvalidPlayer
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    pub player: Signer<'info>,
+}
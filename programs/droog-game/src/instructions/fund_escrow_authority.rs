@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{MatchStakeState, ESCROW_AUTH_SEED, EscrowAuthorityFundedEvent, ProgramConfig, PROGRAM_CONFIG_SEED};
+
+/// Top up the escrow authority PDA to the rent-exempt minimum
+/// 
+/// The escrow authority is signing-only today (it owns the escrow token
+/// account and signs burn/refund/payout CPIs) and needs no lamports to sign.
+/// This provides a funding path for any future feature that requires it to
+/// hold SOL, e.g. to pay for an account it authorizes.
+/// 
+/// Permissionless: anyone may pay. Idempotent: no-op once rent-exempt.
+pub fn fund_escrow_authority(ctx: Context<FundEscrowAuthority>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let current_lamports = ctx.accounts.escrow_authority.lamports();
+    
+    // Already funded - nothing to do
+    if current_lamports >= rent_exempt_minimum {
+        return Ok(());
+    }
+    
+    let amount = rent_exempt_minimum - current_lamports;
+    
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.payer.to_account_info(),
+        to: ctx.accounts.escrow_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer(cpi_ctx, amount)?;
+    
+    emit!(EscrowAuthorityFundedEvent {
+        match_id: ctx.accounts.stake_state.match_id,
+        escrow_authority: ctx.accounts.escrow_authority.key(),
+        payer: ctx.accounts.payer.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundEscrowAuthority<'info> {
+    #[account(
+        seeds = [b"stake", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+    )]
+    pub stake_state: Account<'info, MatchStakeState>,
+    
+    /// Escrow authority PDA (receives lamports)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        mut,
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{MatchState, REFRESH_POOL_SEED, ProgramConfig, PROGRAM_CONFIG_SEED};
+
+/// Deposit SOL into a match's keeper incentive pool
+/// 
+/// refresh_delivery_slots pays KEEPER_REFRESH_REWARD_LAMPORTS from this pool
+/// to non-participants who perform a due rotation, so rotations keep happening
+/// in keeper-driven deployments. Permissionless: anyone may fund.
+pub fn fund_refresh_pool(ctx: Context<FundRefreshPool>, amount: u64) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    
+    let transfer_accounts = Transfer {
+        from: ctx.accounts.payer.to_account_info(),
+        to: ctx.accounts.refresh_pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer(cpi_ctx, amount)?;
+    
+    emit!(RefreshPoolFundedEvent {
+        match_id: ctx.accounts.match_state.load()?.match_id,
+        refresh_pool: ctx.accounts.refresh_pool.key(),
+        payer: ctx.accounts.payer.key(),
+        amount,
+        pool_balance: ctx.accounts.refresh_pool.lamports(),
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundRefreshPool<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Keeper incentive pool PDA (receives lamports)
+    /// CHECK: System-owned PDA holding only lamports
+    #[account(
+        mut,
+        seeds = [REFRESH_POOL_SEED, match_state.load()?.match_id_hash.as_ref()],
+        bump
+    )]
+    pub refresh_pool: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Event emitted when the keeper incentive pool is topped up
+#[event]
+pub struct RefreshPoolFundedEvent {
+    pub match_id: u64,
+    pub refresh_pool: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub pool_balance: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::deterministic::DELIVERY_ROTATION_INTERVAL;
+use crate::state::{
+    GameTunables, Inventory, MatchState, BURN_PERCENTAGE, CANCEL_TIMEOUT_SECONDS, ENDGAME_LOCK_SECONDS,
+    GROWTH_TIMES, MATCH_DURATION_SECONDS, RAKE_BPS, SMELL_RAID_THRESHOLD, SMELL_RATES, STAKE_AMOUNT,
+};
+
+/// Emit the program's default constants (read-only)
+/// 
+/// Clients fetch these once at startup instead of hardcoding them, so a
+/// constant change never leaves a client quietly out of sync. These are the
+/// compiled-in defaults: a match snapshots its own GameTunables at init, so
+/// live matches should read `tunables` from their MatchState instead.
+pub fn get_constants(_ctx: Context<GetConstants>) -> Result<()> {
+    emit!(compute_constants());
+    Ok(())
+}
+
+/// Build the constants event (pure, shared with tests)
+pub fn compute_constants() -> ConstantsEvent {
+    ConstantsEvent {
+        growth_times: GROWTH_TIMES,
+        smell_rates: SMELL_RATES,
+        customer_cooldowns: [1, 2, 3].map(MatchState::get_customer_cooldown),
+        stake_amount: STAKE_AMOUNT,
+        burn_percentage: BURN_PERCENTAGE,
+        rake_bps: RAKE_BPS,
+        inventory_capacity: Inventory::INVENTORY_CAPACITY,
+        delivery_rotation_interval: DELIVERY_ROTATION_INTERVAL,
+        level1_rotation_seconds: GameTunables::DEFAULT.level1_rotation_seconds,
+        level2_rotation_seconds: GameTunables::DEFAULT.level2_rotation_seconds,
+        match_duration_seconds: MATCH_DURATION_SECONDS,
+        endgame_lock_seconds: ENDGAME_LOCK_SECONDS,
+        cancel_timeout_seconds: CANCEL_TIMEOUT_SECONDS,
+        smell_raid_threshold: SMELL_RAID_THRESHOLD,
+    }
+}
+
+#[derive(Accounts)]
+pub struct GetConstants {}
+
+/// Default gameplay and staking constants
+#[event]
+pub struct ConstantsEvent {
+    /// Seconds to grow, by strain level (1-3)
+    pub growth_times: [i64; 3],
+    /// Smell per minute while growing, by strain level (1-3)
+    pub smell_rates: [u16; 3],
+    /// Base customer cooldown in seconds, by layer (1-3), before patience
+    pub customer_cooldowns: [i64; 3],
+    pub stake_amount: u64,
+    pub burn_percentage: u64,
+    pub rake_bps: u16,
+    pub inventory_capacity: u8,
+    pub delivery_rotation_interval: i64,
+    pub level1_rotation_seconds: i64,
+    pub level2_rotation_seconds: i64,
+    pub match_duration_seconds: i64,
+    pub endgame_lock_seconds: i64,
+    pub cancel_timeout_seconds: i64,
+    pub smell_raid_threshold: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_constants_match_module_constants() {
+        let event = compute_constants();
+        assert_eq!(event.growth_times, GROWTH_TIMES);
+        assert_eq!(event.smell_rates, SMELL_RATES);
+        assert_eq!(event.customer_cooldowns, [10, 15, 20]);
+        assert_eq!(event.stake_amount, STAKE_AMOUNT);
+        assert_eq!(event.burn_percentage, BURN_PERCENTAGE);
+        assert_eq!(event.rake_bps, RAKE_BPS);
+        assert_eq!(event.inventory_capacity, Inventory::INVENTORY_CAPACITY);
+        assert_eq!(event.delivery_rotation_interval, DELIVERY_ROTATION_INTERVAL);
+        assert_eq!(event.level1_rotation_seconds, crate::state::DEFAULT_LEVEL1_ROTATION_SECONDS);
+        assert_eq!(event.level2_rotation_seconds, crate::state::DEFAULT_LEVEL2_ROTATION_SECONDS);
+        assert_eq!(event.match_duration_seconds, MATCH_DURATION_SECONDS);
+        assert_eq!(event.endgame_lock_seconds, ENDGAME_LOCK_SECONDS);
+        assert_eq!(event.cancel_timeout_seconds, CANCEL_TIMEOUT_SECONDS);
+        assert_eq!(event.smell_raid_threshold, SMELL_RAID_THRESHOLD);
+        
+        // The defaults agree with the tunables a fresh match snapshots
+        assert_eq!(event.growth_times, GameTunables::DEFAULT.growth_times);
+        assert_eq!(event.smell_rates, GameTunables::DEFAULT.smell_rates);
+    }
+}
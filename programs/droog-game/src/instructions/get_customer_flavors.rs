@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::MatchState;
+
+/// Emit the display flavor of all 23 customers (read-only)
+/// 
+/// Flavors are derived from the match_id alone, so both clients render the
+/// same names/sprites for every customer without a shared off-chain config.
+pub fn get_customer_flavors(ctx: Context<GetCustomerFlavors>) -> Result<()> {
+    let match_state = ctx.accounts.match_state.load()?;
+    emit!(CustomerFlavorsEvent {
+        match_id: match_state.match_id,
+        flavors: match_state.customer_flavors(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetCustomerFlavors<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+}
+
+/// Display flavor per customer index, each in [0, CUSTOMER_FLAVOR_COUNT)
+#[event]
+pub struct CustomerFlavorsEvent {
+    pub match_id: u64,
+    pub flavors: [u8; 23],
+}
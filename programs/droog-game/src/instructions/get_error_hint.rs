@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use crate::errors::{DroogError, HintAction, HintSeverity};
+
+/// Emit client-facing guidance for a DroogError code (read-only)
+/// 
+/// `code` is the raw Anchor error code from a failed transaction
+/// (ERROR_CODE_OFFSET + variant index). The mapping itself lives next to the
+/// enum in errors.rs (DroogError::hint), so clients render the same severity,
+/// suggested action and retryability for every error. Codes that are not a
+/// DroogError fail with UnknownErrorCode.
+pub fn get_error_hint(_ctx: Context<GetErrorHint>, code: u32) -> Result<()> {
+    emit!(compute_error_hint(code)?);
+    Ok(())
+}
+
+/// Build the hint event for an error code (pure, shared with tests)
+pub fn compute_error_hint(code: u32) -> Result<ErrorHintEvent> {
+    let error = DroogError::from_code(code).ok_or(DroogError::UnknownErrorCode)?;
+    let hint = error.hint();
+    Ok(ErrorHintEvent {
+        code,
+        severity: hint.severity,
+        action: hint.action,
+        retryable: hint.retryable,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetErrorHint {}
+
+/// Structured guidance for one error code
+#[event]
+pub struct ErrorHintEvent {
+    pub code: u32,
+    pub severity: HintSeverity,
+    /// What the client should suggest to the player
+    pub action: HintAction,
+    /// The same transaction may succeed if resent later
+    pub retryable: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::error::ERROR_CODE_OFFSET;
+    
+    #[test]
+    fn test_every_error_has_a_hint() {
+        // ALL lists every declared variant, in code order
+        let declared = include_str!("../errors.rs").matches("#[msg(").count();
+        assert_eq!(DroogError::ALL.len(), declared);
+        for (i, &error) in DroogError::ALL.iter().enumerate() {
+            let code = ERROR_CODE_OFFSET + i as u32;
+            assert_eq!(u32::from(error), code);
+            assert_eq!(DroogError::from_code(code).map(u32::from), Some(code));
+            
+            // DroogError::hint has no wildcard arm, so each variant is mapped
+            let event = compute_error_hint(code).unwrap();
+            assert_eq!(event.retryable, event.action == HintAction::WaitAndRetry);
+        }
+        
+        // Spot checks
+        let hint = DroogError::CustomerOnCooldown.hint();
+        assert_eq!((hint.severity, hint.action, hint.retryable), (HintSeverity::Info, HintAction::WaitAndRetry, true));
+        let hint = DroogError::InsufficientStakeBalance.hint();
+        assert_eq!((hint.action, hint.retryable), (HintAction::TopUpBalance, false));
+        assert_eq!(DroogError::CalculationOverflow.hint().severity, HintSeverity::Critical);
+        
+        // Codes outside the enum
+        for code in [0, ERROR_CODE_OFFSET - 1, ERROR_CODE_OFFSET + DroogError::ALL.len() as u32, u32::MAX] {
+            assert_eq!(compute_error_hint(code).map(|_| ()).unwrap_err(), DroogError::UnknownErrorCode.into());
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
+use crate::state::{MatchDeliveryState, MatchGrowState, MatchStakeState, MatchState};
+use crate::errors::DroogError;
+
+/// Domain tag and version of the checksum layout (bump the version if the field set changes)
+pub const MATCH_CHECKSUM_DOMAIN: &[u8] = b"droog:checksum:v2";
+
+/// Emit a 32-byte digest of the match's key account fields (read-only)
+/// 
+/// For integrity monitoring: a service watching many matches can compare
+/// checksums between expected transitions to spot unexpected state changes.
+/// grow_state and delivery_state are optional (delivery-less matches have no
+/// delivery state); their absence is part of the digest.
+pub fn get_match_checksum(ctx: Context<GetMatchChecksum>) -> Result<()> {
+    let clock = Clock::get()?;
+    let grow_state = ctx.accounts.grow_state.as_deref().map(|g| &**g);
+    let delivery_state = ctx.accounts.delivery_state.as_deref().map(|d| &**d);
+    let match_state = ctx.accounts.match_state.load()?;
+    
+    emit!(MatchChecksumEvent {
+        match_id: match_state.match_id,
+        checksum: compute_match_checksum(
+            &match_state,
+            &ctx.accounts.stake_state,
+            grow_state,
+            delivery_state,
+        ),
+        includes_grow_state: grow_state.is_some(),
+        includes_delivery_state: delivery_state.is_some(),
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// sha256 over MATCH_CHECKSUM_DOMAIN followed by, in this order:
+/// 
+/// 1. match: match_id, match_id_hash, player_a, player_b, start_ts, end_ts,
+///    customers, player_a_sales, player_b_sales, player_a_reputation,
+///    player_b_reputation, is_finalized, replay_seq, early_end_eligible
+/// 2. stake: status, player_a_escrowed, player_b_escrowed, stake_amount,
+///    pending_settlement
+/// 3. grow: presence byte (0/1), then player_a_slots, player_b_slots,
+///    player_a_inventory, player_b_inventory, player_a_harvests, player_b_harvests
+/// 4. delivery: presence byte (0/1), then last_update_ts, available_customers,
+///    active_count, rush_customer, rotation_count
+/// 
+/// Every field is encoded as its Borsh (account) serialization, so integers
+/// are little-endian and structs/enums follow their declaration order.
+pub fn compute_match_checksum(
+    match_state: &MatchState,
+    stake_state: &MatchStakeState,
+    grow_state: Option<&MatchGrowState>,
+    delivery_state: Option<&MatchDeliveryState>,
+) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(1_024);
+    
+    put(&mut bytes, &match_state.match_id);
+    put(&mut bytes, &match_state.match_id_hash);
+    put(&mut bytes, &match_state.player_a);
+    put(&mut bytes, &match_state.player_b);
+    put(&mut bytes, &match_state.start_ts);
+    put(&mut bytes, &match_state.end_ts);
+    put(&mut bytes, &match_state.customers);
+    put(&mut bytes, &match_state.player_a_sales);
+    put(&mut bytes, &match_state.player_b_sales);
+    put(&mut bytes, &match_state.player_a_reputation);
+    put(&mut bytes, &match_state.player_b_reputation);
+    put(&mut bytes, &match_state.is_finalized());
+    put(&mut bytes, &match_state.replay_seq);
+    put(&mut bytes, &match_state.early_end_eligible());
+    
+    put(&mut bytes, &stake_state.status);
+    put(&mut bytes, &stake_state.player_a_escrowed);
+    put(&mut bytes, &stake_state.player_b_escrowed);
+    put(&mut bytes, &stake_state.stake_amount);
+    put(&mut bytes, &stake_state.pending_settlement);
+    
+    put(&mut bytes, &grow_state.is_some());
+    if let Some(grow) = grow_state {
+        put(&mut bytes, &grow.player_a_slots);
+        put(&mut bytes, &grow.player_b_slots);
+        put(&mut bytes, &grow.player_a_inventory);
+        put(&mut bytes, &grow.player_b_inventory);
+        put(&mut bytes, &grow.player_a_harvests);
+        put(&mut bytes, &grow.player_b_harvests);
+    }
+    
+    put(&mut bytes, &delivery_state.is_some());
+    if let Some(delivery) = delivery_state {
+        put(&mut bytes, &delivery.last_update_ts);
+        put(&mut bytes, &delivery.available_customers);
+        put(&mut bytes, &delivery.active_count);
+        put(&mut bytes, &delivery.rush_customer);
+        put(&mut bytes, &delivery.rotation_count);
+    }
+    
+    hashv(&[MATCH_CHECKSUM_DOMAIN, &bytes]).to_bytes()
+}
+
+/// Append a field's Borsh encoding (writing to a Vec cannot fail)
+fn put<T: AnchorSerialize>(bytes: &mut Vec<u8>, value: &T) {
+    value.serialize(bytes).expect("writing to a Vec is infallible");
+}
+
+#[derive(Accounts)]
+pub struct GetMatchChecksum<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        seeds = [b"grow", match_state.load()?.match_id_hash.as_ref()],
+        bump = grow_state.bump,
+        constraint = grow_state.match_id_hash == match_state.load()?.match_id_hash @ DroogError::MatchIdMismatch
+    )]
+    pub grow_state: Option<Box<Account<'info, MatchGrowState>>>,
+    
+    #[account(
+        seeds = [b"delivery", match_state.load()?.match_id_hash.as_ref()],
+        bump = delivery_state.bump,
+        constraint = delivery_state.match_id_hash == match_state.load()?.match_id_hash @ DroogError::MatchIdMismatch
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+}
+
+/// Integrity digest of a match's accounts (see compute_match_checksum)
+#[event]
+pub struct MatchChecksumEvent {
+    pub match_id: u64,
+    pub checksum: [u8; 32],
+    /// Whether grow/delivery state were supplied (and so hashed)
+    pub includes_grow_state: bool,
+    pub includes_delivery_state: bool,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::delivery_state::tests::test_delivery_state;
+    use crate::state::grow_state::tests::test_grow_state;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::stake_state::tests::test_stake;
+    use crate::state::MatchStatus;
+    
+    #[test]
+    fn test_checksum_tracks_state_changes() {
+        let mut m = test_match();
+        let mut stake = test_stake();
+        let mut grow = test_grow_state();
+        let mut delivery = test_delivery_state();
+        let base = compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery));
+        
+        // Stable: recomputing over unchanged state gives the same digest
+        assert_eq!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+        
+        // Each account's mutations show up
+        m.player_b_sales += 1;
+        assert_ne!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+        m.player_b_sales -= 1;
+        assert_eq!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+        
+        stake.status = MatchStatus::Active;
+        assert_ne!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+        stake.status = test_stake().status;
+        
+        grow.player_a_inventory.increment(1, 1_000);
+        assert_ne!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+        grow = test_grow_state();
+        
+        delivery.rotation_count = 1;
+        assert_ne!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+        delivery.rotation_count = 0;
+        
+        // Omitting an optional account changes the digest
+        assert_ne!(base, compute_match_checksum(&m, &stake, Some(&grow), None));
+        assert_ne!(base, compute_match_checksum(&m, &stake, None, Some(&delivery)));
+        
+        // Fields outside the set (e.g. bump) don't
+        m.bump = 1;
+        assert_eq!(base, compute_match_checksum(&m, &stake, Some(&grow), Some(&delivery)));
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use crate::state::{MatchGrowState, MatchState};
+use crate::errors::DroogError;
+
+/// Emit an analytical snapshot of the match for spectator UIs
+/// 
+/// Read-only: combines match state (sales, reputation, timing) with grow
+/// state (inventory on hand = potential future sales) into one event, so a
+/// UI can render "A leads but B has 5 items ready with 3 minutes left".
+pub fn get_match_insight(ctx: Context<GetMatchInsight>) -> Result<()> {
+    let clock = Clock::get()?;
+    let insight = compute_match_insight(
+        &*ctx.accounts.match_state.load()?,
+        &ctx.accounts.grow_state,
+        clock.unix_timestamp,
+    );
+    emit!(insight);
+    Ok(())
+}
+
+/// Build the insight snapshot (pure, shared with tests)
+pub fn compute_match_insight(
+    match_state: &MatchState,
+    grow_state: &MatchGrowState,
+    current_ts: i64,
+) -> MatchInsightEvent {
+    let a_sales = match_state.player_a_sales;
+    let b_sales = match_state.player_b_sales;
+    let a_inventory = grow_state.player_a_inventory.total();
+    let b_inventory = grow_state.player_b_inventory.total();
+    
+    // Projected winner uses the same rule as finalize_match (reputation breaks ties)
+    let a_leads = a_sales >= b_sales;
+    let projected_winner = match_state.determine_winner().winner;
+    
+    // Can the trailing player still catch up by selling what they hold?
+    let (leader_sales, trailer_potential) = if a_leads {
+        (a_sales, b_sales.saturating_add(b_inventory as u32))
+    } else {
+        (b_sales, a_sales.saturating_add(a_inventory as u32))
+    };
+    
+    MatchInsightEvent {
+        match_id: match_state.match_id,
+        projected_winner,
+        sales_gap: a_sales as i64 - b_sales as i64,
+        reputation_gap: match_state.player_a_reputation as i64 - match_state.player_b_reputation as i64,
+        player_a_inventory: a_inventory,
+        player_b_inventory: b_inventory,
+        comeback_possible: trailer_potential >= leader_sales && a_sales != b_sales,
+        time_remaining: match_state.end_ts.saturating_sub(current_ts).max(0),
+        timestamp: current_ts,
+    }
+}
+
+#[derive(Accounts)]
+pub struct GetMatchInsight<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
+    #[account(
+        seeds = [b"grow", match_state.load()?.match_id_hash.as_ref()],
+        bump = grow_state.bump,
+        constraint = grow_state.belongs_to(&*match_state.load()?) @ DroogError::MatchIdMismatch
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+}
+
+/// Aggregated match insight for spectator UIs
+#[event]
+pub struct MatchInsightEvent {
+    pub match_id: u64,
+    /// Who would win if the match were finalized now
+    pub projected_winner: Pubkey,
+    /// player_a_sales - player_b_sales
+    pub sales_gap: i64,
+    /// player_a_reputation - player_b_reputation
+    pub reputation_gap: i64,
+    /// Items on hand (potential future sales)
+    pub player_a_inventory: u8,
+    pub player_b_inventory: u8,
+    /// Trailing player could match the leader by selling everything they hold
+    pub comeback_possible: bool,
+    /// Seconds until end_ts (0 once ended)
+    pub time_remaining: i64,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::grow_state::tests::test_grow_state;
+    
+    #[test]
+    fn test_insight_mid_match() {
+        let mut m = test_match();
+        let mut g = test_grow_state();
+        
+        // A leads 7-4 on sales, B has 5 items on hand with 3 minutes left
+        m.player_a_sales = 7;
+        m.player_b_sales = 4;
+        m.player_a_reputation = 10;
+        m.player_b_reputation = 12;
+        g.player_a_inventory.increment(1, 1_300);
+        for _ in 0..5 {
+            g.player_b_inventory.increment(2, 1_300);
+        }
+        
+        let insight = compute_match_insight(&m, &g, m.end_ts - 180);
+        assert_eq!(insight.projected_winner, m.player_a);
+        assert_eq!(insight.sales_gap, 3);
+        assert_eq!(insight.reputation_gap, -2);
+        assert_eq!(insight.player_a_inventory, 1);
+        assert_eq!(insight.player_b_inventory, 5);
+        assert!(insight.comeback_possible);
+        assert_eq!(insight.time_remaining, 180);
+        
+        // After the end, time remaining floors at zero
+        let insight = compute_match_insight(&m, &g, m.end_ts + 10);
+        assert_eq!(insight.time_remaining, 0);
+    }
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use crate::state::{MatchGrowState, MatchState, SLOTS_PER_PLAYER};
+use crate::errors::DroogError;
+
+/// Emit, for each of the caller's Growing slots, when it becomes Ready and
+/// whether that is before end_ts
+/// 
+/// Read-only planning aid: the in-flight counterpart of will_be_ready_in_time,
+/// using the same growth-time math (ready at planted_at + growth time, in time
+/// if ready_ts <= end_ts).
+pub fn get_projected_ready(ctx: Context<GetProjectedReady>) -> Result<()> {
+    let grow_state = &ctx.accounts.grow_state;
+    let player = ctx.accounts.player.key();
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    let clock = Clock::get()?;
+    emit!(compute_projected_ready(
+        grow_state,
+        is_player_a,
+        ctx.accounts.match_state.load()?.end_ts,
+        clock.unix_timestamp,
+    ));
+    Ok(())
+}
+
+/// Build the projection for one player's slots (pure, shared with tests)
+pub fn compute_projected_ready(
+    grow_state: &MatchGrowState,
+    is_player_a: bool,
+    end_ts: i64,
+    current_ts: i64,
+) -> ProjectedReadyEvent {
+    let slots = if is_player_a {
+        &grow_state.player_a_slots
+    } else {
+        &grow_state.player_b_slots
+    };
+    
+    let mut ready_ts = [0i64; SLOTS_PER_PLAYER];
+    let mut ready_in_time = [false; SLOTS_PER_PLAYER];
+    for (index, slot) in slots.iter().enumerate() {
+        if let Some(ts) = slot.projected_ready_ts(&grow_state.tunables) {
+            ready_ts[index] = ts;
+            ready_in_time[index] = ts <= end_ts;
+        }
+    }
+    
+    ProjectedReadyEvent {
+        match_id: grow_state.match_id,
+        player: if is_player_a { grow_state.player_a } else { grow_state.player_b },
+        ready_ts,
+        ready_in_time,
+        end_ts,
+        timestamp: current_ts,
+    }
+}
+
+#[derive(Accounts)]
+pub struct GetProjectedReady<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Boxed to avoid stack overflow
+    #[account(
+        seeds = [b"grow", match_state.load()?.match_id_hash.as_ref()],
+        bump = grow_state.bump,
+        constraint = grow_state.belongs_to(&*match_state.load()?) @ DroogError::MatchIdMismatch
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The player whose slots are projected
+    pub player: Signer<'info>,
+}
+
+/// Projected ready times for one player's grow slots
+#[event]
+pub struct ProjectedReadyEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    /// Ready timestamp per slot (0 = not Growing)
+    pub ready_ts: [i64; SLOTS_PER_PLAYER],
+    /// Slot's plant becomes Ready at or before end_ts (false if not Growing)
+    pub ready_in_time: [bool; SLOTS_PER_PLAYER],
+    pub end_ts: i64,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::grow_state::tests::test_grow_state;
+    use crate::state::{GameTunables, GrowSlot, PlantState, GROWTH_TIMES};
+    
+    fn growing(strain_level: u8, planted_at: i64) -> GrowSlot {
+        GrowSlot {
+            plant_state: PlantState::Growing { strain_level, planted_at },
+            strain_level,
+            ..GrowSlot::default()
+        }
+    }
+    
+    #[test]
+    fn test_projection_matches_growth_times() {
+        let mut state = test_grow_state();
+        let end_ts = 1_600;
+        let now = 1_560;
+        
+        state.player_b_slots[0] = growing(1, now);      // ready at 1_570
+        state.player_b_slots[1] = growing(3, now - 20); // ready exactly at end_ts
+        state.player_b_slots[2] = growing(3, now);      // ready 20s too late
+        state.player_b_slots[3] = GrowSlot {
+            plant_state: PlantState::Ready { strain_level: 2, ready_at: now },
+            strain_level: 2,
+            ..GrowSlot::default()
+        };
+        
+        let projection = compute_projected_ready(&state, false, end_ts, now);
+        assert_eq!(projection.player, state.player_b);
+        assert_eq!(projection.ready_ts, [now + GROWTH_TIMES[0], end_ts, now + GROWTH_TIMES[2], 0, 0, 0]);
+        assert_eq!(projection.ready_in_time, [true, true, false, false, false, false]);
+        
+        // Agrees with the planting-time check for the same plants
+        assert_eq!(
+            projection.ready_in_time[2],
+            MatchGrowState::will_be_ready_in_time(&GameTunables::DEFAULT, now, end_ts, 3)
+        );
+        
+        // Player A's empty slots project nothing
+        let projection = compute_projected_ready(&state, true, end_ts, now);
+        assert_eq!(projection.ready_ts, [0; SLOTS_PER_PLAYER]);
+        assert_eq!(projection.ready_in_time, [false; SLOTS_PER_PLAYER]);
+    }
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::MatchState;
+use crate::state::{MatchState, ProgramConfig, PROGRAM_CONFIG_SEED};
 use crate::errors::DroogError;
 
 // Strain growth times in seconds
@@ -31,18 +31,22 @@ pub fn harvest(
     planted_at: i64,
     last_harvested_at: Option<i64>,
 ) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
+    let match_state = ctx.accounts.match_state.load()?;
     
     // Prevent state changes after finalization
-    require!(!ctx.accounts.match_state.is_finalized, DroogError::MatchAlreadyFinalized);
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
     
     // Validate strain_id is valid (0-6)
     require!(strain_id < 7, DroogError::StrainNotActive);
     
     // Validate match is active
-    require!(current_ts >= ctx.accounts.match_state.start_ts, DroogError::MatchNotStarted);
-    require!(current_ts < ctx.accounts.match_state.end_ts, DroogError::MatchEnded);
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
     
     // Check if growth time has elapsed
     let growth_time = STRAIN_GROWTH_TIMES[strain_id as usize];
@@ -59,14 +63,14 @@ pub fn harvest(
     // Validate strain is currently active (on-chain rotation validation)
     // Uses half-open intervals [start, end) to prevent boundary overlap
     require!(
-        ctx.accounts.match_state.is_strain_active(strain_id, current_ts),
+        match_state.is_strain_active(strain_id, current_ts),
         DroogError::StrainNotActive
     );
     
     // Emit harvest event (client will track inventory off-chain)
     emit!(HarvestEvent {
         player: ctx.accounts.player.key(),
-        match_id: ctx.accounts.match_state.match_id,
+        match_id: match_state.match_id,
         strain_id,
         harvested_at: current_ts,
     });
@@ -76,18 +80,24 @@ pub fn harvest(
 
 #[derive(Accounts)]
 pub struct Harvest<'info> {
+    /// Canonical match PDA, same seeds as init_match (see pda::derive_match_pda)
     #[account(
         seeds = [
             b"match",
-            match_state.match_id_hash.as_ref(),
-            match_state.player_a.as_ref(),
-            match_state.player_b.as_ref()
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
         ],
-        bump = match_state.bump
+        bump = match_state.load()?.bump
     )]
-    pub match_state: Account<'info, MatchState>,
+    pub match_state: AccountLoader<'info, MatchState>,
     
     pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 #[event]
@@ -97,3 +107,34 @@ pub struct HarvestEvent {
     pub strain_id: u8,
     pub harvested_at: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pda::derive_match_pda;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::LayerConfig;
+    
+    #[test]
+    fn test_harvest_resolves_init_match_pda() {
+        // Account as init_match creates it: canonical address and bump
+        let (player_a, player_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (player_a, player_b) = if player_a < player_b { (player_a, player_b) } else { (player_b, player_a) };
+        let match_id_hash = [42u8; 32];
+        let (address, bump) = derive_match_pda(&match_id_hash, &player_a, &player_b);
+        let mut m = test_match();
+        m.initialize(7, match_id_hash, player_a, player_b, 1_000, LayerConfig::DEFAULT, bump).unwrap();
+        
+        // Harvest's constraint re-derives from the stored fields and bump
+        let resolved = Pubkey::create_program_address(
+            &[b"match", m.match_id_hash.as_ref(), m.player_a.as_ref(), m.player_b.as_ref(), &[m.bump]],
+            &crate::ID,
+        )
+        .unwrap();
+        assert_eq!(resolved, address);
+        
+        // The old id-only seeds never reach the real account
+        let legacy = Pubkey::find_program_address(&[b"match", m.match_id.to_le_bytes().as_ref()], &crate::ID).0;
+        assert_ne!(legacy, address);
+    }
+}
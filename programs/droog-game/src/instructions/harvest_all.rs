@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, REPLAY_ACTION_HARVEST, HARVEST_COMBO_THRESHOLD,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+use super::harvest_strain::{emit_capacity_transition, emit_spoilage, HarvestComboEvent};
+
+/// Harvest every ready plant in the caller's grow slots at once
+/// 
+/// Saves one transaction per slot when several plants ripen together:
+/// 1. Same match/stake/timing validations as harvest_strain
+/// 2. Spoils expired stacks (may free space)
+/// 3. Harvests Ready plants in slot order, skipping slots that aren't ready
+/// 4. Stops cleanly when inventory fills (remaining plants stay ripe)
+/// 
+/// Fails with SlotEmpty if no plant is ready. Each harvest extends the
+/// player's combo and gets its own replay frame, exactly as harvest_strain.
+pub fn harvest_all(ctx: Context<HarvestAll>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active (harvesting allowed until match ends)
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
+    let match_id = grow_state.match_id;
+    let capacity = grow_state.inventory_capacity(is_player_a, current_ts);
+    
+    // Lazily spoil stale stacks (may free space)
+    let inventory = if is_player_a {
+        &mut grow_state.player_a_inventory
+    } else {
+        &mut grow_state.player_b_inventory
+    };
+    let was_full = !inventory.has_space(capacity);
+    let spoiled = inventory.spoil_expired(current_ts);
+    
+    let batch = grow_state.harvest_ready_slots(is_player_a, current_ts)?;
+    
+    let inventory = if is_player_a {
+        &grow_state.player_a_inventory
+    } else {
+        &grow_state.player_b_inventory
+    };
+    let total_inventory = inventory.total();
+    let inventory_has_space = inventory.has_space(capacity);
+    
+    emit_spoilage(match_id, player, spoiled, current_ts);
+    emit_capacity_transition(
+        match_id,
+        player,
+        was_full,
+        !inventory_has_space,
+        total_inventory,
+        current_ts,
+    );
+    
+    emit!(BatchHarvestEvent {
+        match_id,
+        player,
+        harvested_slots: batch.plants.iter().map(|p| p.slot_index).collect(),
+        level1_added: batch.level_counts[0],
+        level2_added: batch.level_counts[1],
+        level3_added: batch.level_counts[2],
+        total_inventory,
+        timestamp: current_ts,
+    });
+    
+    for plant in &batch.plants {
+        if plant.combo_bonus {
+            emit!(HarvestComboEvent {
+                match_id,
+                player,
+                strain_level: plant.strain_level,
+                combo: HARVEST_COMBO_THRESHOLD,
+                new_inventory_count: inventory.get(plant.strain_level),
+                total_inventory,
+                timestamp: current_ts,
+            });
+        }
+        
+        // Let the opponent's client react to big moves
+        if let Some(event) = match_state.significant_harvest_event(player, plant.strain_level, current_ts) {
+            emit!(event);
+        }
+        
+        emit!(match_state.next_replay_frame(
+            REPLAY_ACTION_HARVEST,
+            player,
+            plant.slot_index,
+            plant.strain_level,
+            plant.variant_id,
+            current_ts,
+        ));
+    }
+    
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct HarvestAll<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation and replay seq)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player harvesting
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted by harvest_all
+#[event]
+pub struct BatchHarvestEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    /// Slot indices harvested, in order
+    pub harvested_slots: Vec<u8>,
+    /// Items added per level (combo bonuses included)
+    pub level1_added: u8,
+    pub level2_added: u8,
+    pub level3_added: u8,
+    pub total_inventory: u8,
+    pub timestamp: i64,
+}
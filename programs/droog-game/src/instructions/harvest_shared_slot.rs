@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState,
+    REPLAY_ACTION_HARVEST_SHARED, HARVEST_COMBO_THRESHOLD,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+use super::harvest_strain::{emit_capacity_transition, emit_spoilage, HarvestComboEvent};
+
+/// Harvest the player's Ready plant from a shared-land slot
+/// 
+/// Works like harvest_strain (inventory space, spoilage, combo) but only the
+/// slot's claimant may harvest. Harvesting releases the slot back to Neutral.
+pub fn harvest_shared_slot(
+    ctx: Context<HarvestSharedSlot>,
+    slot_index: u8,
+) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    require!(match_state.shared_land(), DroogError::SharedLandDisabled);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active (harvesting allowed until match ends)
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    let match_id = grow_state.match_id;
+    let capacity = grow_state.inventory_capacity(is_player_a, current_ts);
+    
+    // Lazily spoil stale stacks (may free space)
+    let inventory = if is_player_a {
+        &mut grow_state.player_a_inventory
+    } else {
+        &mut grow_state.player_b_inventory
+    };
+    let was_full = !inventory.has_space(capacity);
+    let spoiled = inventory.spoil_expired(current_ts);
+    
+    let (strain_level, variant_id) = grow_state.harvest_shared_slot(is_player_a, slot_index, current_ts)?;
+    
+    // Quick consecutive harvests build a combo that pays out a bonus item
+    let combo_bonus = grow_state.apply_harvest_combo(is_player_a, strain_level, current_ts);
+    let inventory = if is_player_a {
+        &grow_state.player_a_inventory
+    } else {
+        &grow_state.player_b_inventory
+    };
+    let new_inventory_count = inventory.get(strain_level);
+    let total_inventory = inventory.total();
+    let is_full = !inventory.has_space(capacity);
+    
+    emit_spoilage(match_id, player, spoiled, current_ts);
+    emit_capacity_transition(match_id, player, was_full, is_full, total_inventory, current_ts);
+    
+    emit!(SharedSlotHarvestedEvent {
+        match_id,
+        player,
+        slot_index,
+        strain_level,
+        variant_id,
+        harvested_ts: current_ts,
+        new_inventory_count,
+        total_inventory,
+    });
+    
+    if combo_bonus {
+        emit!(HarvestComboEvent {
+            match_id,
+            player,
+            strain_level,
+            combo: HARVEST_COMBO_THRESHOLD,
+            new_inventory_count,
+            total_inventory,
+            timestamp: current_ts,
+        });
+    }
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_HARVEST_SHARED,
+        player,
+        slot_index,
+        strain_level,
+        variant_id,
+        current_ts,
+    ));
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct HarvestSharedSlot<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation and replay seq)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player harvesting (must hold the slot's claim)
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted when a claimant harvests a shared-land slot (the slot is Neutral again)
+#[event]
+pub struct SharedSlotHarvestedEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub slot_index: u8,
+    pub strain_level: u8,
+    pub variant_id: u8,
+    pub harvested_ts: i64,
+    pub new_inventory_count: u8,
+    pub total_inventory: u8,
+}
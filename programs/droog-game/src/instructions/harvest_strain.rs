@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{MatchGrowState, MatchState, PlantState, SLOTS_PER_PLAYER};
+use crate::state::{
+    Inventory, MatchGrowState, MatchState, MatchStakeState,
+    REPLAY_ACTION_HARVEST, HARVEST_COMBO_THRESHOLD,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
 use crate::errors::DroogError;
 
 /// Harvest a ready plant from a grow slot
@@ -10,129 +14,71 @@ use crate::errors::DroogError;
 /// 3. Validates the plant hasn't been harvested already
 /// 4. Marks the slot as harvested
 /// 5. Increments the player's inventory
+/// 6. Extends the player's harvest combo (bonus item at HARVEST_COMBO_THRESHOLD)
 pub fn harvest_strain(
     ctx: Context<HarvestStrain>,
     slot_index: u8,
 ) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
     
     let grow_state = &mut ctx.accounts.grow_state;
-    let match_state = &ctx.accounts.match_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
     let player = ctx.accounts.player.key();
     
     // Prevent state changes after finalization
-    require!(!match_state.is_finalized, DroogError::MatchAlreadyFinalized);
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
     
     // Validate match is active (harvesting allowed until match ends)
     require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
     require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
     
-    // Validate slot index
-    require!(
-        (slot_index as usize) < SLOTS_PER_PLAYER,
-        DroogError::InvalidSlotIndex
-    );
+    // Resolve the signer's side; every slot and inventory access below goes
+    // through it, so slot_index can never reach the opponent's land
+    let side = grow_state.side_of(&player)?;
+    let is_player_a = side.is_a();
     
-    // Determine which player's slots and inventory to use
-    let is_player_a = player == grow_state.player_a;
-    let is_player_b = player == grow_state.player_b;
-    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
     
     // Cache match_id before mutable borrows
     let match_id = grow_state.match_id;
     
-    // Process harvest for the appropriate player
-    let (strain_level, variant_id, new_inventory_count, total_inventory) = if is_player_a {
-        // First, get mutable access to slot only
-        let slot = &mut grow_state.player_a_slots[slot_index as usize];
-        
-        // Lazy evaluation: advance plant state if growth time has elapsed
-        // Growth is derived from timestamps, not stored timers
-        slot.advance_if_ready(current_ts);
-        
-        // Validate plant is ready for harvest and extract values
-        let (strain_level, variant_id) = match slot.plant_state {
-            PlantState::Ready { strain_level } => {
-                (strain_level, slot.variant_id)
-            }
-            PlantState::Empty => {
-                return Err(DroogError::SlotEmpty.into());
-            }
-            PlantState::Growing { .. } => {
-                return Err(DroogError::GrowthTimeNotElapsed.into());
-            }
-        };
-        
-        // Drop mutable borrow of slot before accessing inventory
-        drop(slot);
-        
-        // Validate inventory has space (hard capacity limit)
-        // Harvesting requires inventory space - if full, harvest must fail
-        require!(
-            grow_state.player_a_inventory.has_space(),
-            DroogError::InventoryFull
-        );
-        
-        // Harvest the plant: add to inventory
-        // Plants are ephemeral - destroyed on harvest
-        grow_state.player_a_inventory.increment(strain_level);
-        let new_inventory_count = grow_state.player_a_inventory.get(strain_level);
-        let total_inventory = grow_state.player_a_inventory.total();
-        
-        // Now update slot state (free the land)
-        // Slots are persistent land - immediately freed for replanting
-        let slot = &mut grow_state.player_a_slots[slot_index as usize];
-        slot.plant_state = PlantState::Empty;
-        slot.last_harvested_ts = current_ts; // Track harvest time for variant lookup
-        
-        (strain_level, variant_id, new_inventory_count, total_inventory)
+    // Lazily spoil stale stacks first (may free space)
+    let capacity = grow_state.inventory_capacity(is_player_a, current_ts);
+    let was_full = !grow_state.inventory(side).has_space(capacity);
+    let spoiled = if is_player_a {
+        grow_state.player_a_inventory.spoil_expired(current_ts)
     } else {
-        // First, get mutable access to slot only
-        let slot = &mut grow_state.player_b_slots[slot_index as usize];
-        
-        // Lazy evaluation: advance plant state if growth time has elapsed
-        // Growth is derived from timestamps, not stored timers
-        slot.advance_if_ready(current_ts);
-        
-        // Validate plant is ready for harvest and extract values
-        let (strain_level, variant_id) = match slot.plant_state {
-            PlantState::Ready { strain_level } => {
-                (strain_level, slot.variant_id)
-            }
-            PlantState::Empty => {
-                return Err(DroogError::SlotEmpty.into());
-            }
-            PlantState::Growing { .. } => {
-                return Err(DroogError::GrowthTimeNotElapsed.into());
-            }
-        };
-        
-        // Drop mutable borrow of slot before accessing inventory
-        drop(slot);
-        
-        // Validate inventory has space (hard capacity limit)
-        // Harvesting requires inventory space - if full, harvest must fail
-        require!(
-            grow_state.player_b_inventory.has_space(),
-            DroogError::InventoryFull
-        );
-        
-        // Harvest the plant: add to inventory
-        // Plants are ephemeral - destroyed on harvest
-        grow_state.player_b_inventory.increment(strain_level);
-        let new_inventory_count = grow_state.player_b_inventory.get(strain_level);
-        let total_inventory = grow_state.player_b_inventory.total();
-        
-        // Now update slot state (free the land)
-        // Slots are persistent land - immediately freed for replanting
-        let slot = &mut grow_state.player_b_slots[slot_index as usize];
-        slot.plant_state = PlantState::Empty;
-        slot.last_harvested_ts = current_ts; // Track harvest time for variant lookup
-        
-        (strain_level, variant_id, new_inventory_count, total_inventory)
+        grow_state.player_b_inventory.spoil_expired(current_ts)
     };
     
+    // Validates the slot index, readiness and inventory space, then frees
+    // the slot and credits the inventory
+    let (strain_level, variant_id) = grow_state.harvest_slot(side, slot_index, current_ts)?;
+    
+    // Quick consecutive harvests build a combo that pays out a bonus item
+    let combo_bonus = grow_state.apply_harvest_combo(is_player_a, strain_level, current_ts);
+    let new_inventory_count = grow_state.inventory(side).get(strain_level);
+    let total_inventory = grow_state.inventory(side).total();
+    
+    emit_spoilage(match_id, player, spoiled, current_ts);
+    let inventory_has_space = grow_state.inventory(side).has_space(capacity);
+    emit_capacity_transition(
+        match_id,
+        player,
+        was_full,
+        !inventory_has_space,
+        total_inventory,
+        current_ts,
+    );
+    
     // Emit harvest event (using cached values)
     emit!(HarvestStrainEvent {
         match_id,
@@ -145,35 +91,112 @@ pub fn harvest_strain(
         total_inventory,
     });
     
+    if combo_bonus {
+        emit!(HarvestComboEvent {
+            match_id,
+            player,
+            strain_level,
+            combo: HARVEST_COMBO_THRESHOLD,
+            new_inventory_count,
+            total_inventory,
+            timestamp: current_ts,
+        });
+    }
+    
+    // Let the opponent's client react to big moves
+    if let Some(event) = match_state.significant_harvest_event(player, strain_level, current_ts) {
+        emit!(event);
+    }
+    
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_HARVEST,
+        player,
+        slot_index,
+        strain_level,
+        variant_id,
+        current_ts,
+    ));
+    
     Ok(())
 }
 
+/// Emit an InventorySpoiledEvent if anything spoiled
+pub(crate) fn emit_spoilage(match_id: u64, player: Pubkey, spoiled: [u8; 3], current_ts: i64) {
+    if spoiled.iter().any(|&c| c > 0) {
+        emit!(InventorySpoiledEvent {
+            match_id,
+            player,
+            level1_spoiled: spoiled[0],
+            level2_spoiled: spoiled[1],
+            level3_spoiled: spoiled[2],
+            timestamp: current_ts,
+        });
+    }
+}
+
+/// Emit an InventoryCapacityEvent only when the full/not-full boundary is crossed
+pub(crate) fn emit_capacity_transition(
+    match_id: u64,
+    player: Pubkey,
+    was_full: bool,
+    is_full: bool,
+    total_inventory: u8,
+    current_ts: i64,
+) {
+    if let Some(became_full) = Inventory::capacity_transition(was_full, is_full) {
+        emit!(InventoryCapacityEvent {
+            match_id,
+            player,
+            became_full,
+            total_inventory,
+            timestamp: current_ts,
+        });
+    }
+}
+
 #[derive(Accounts)]
 pub struct HarvestStrain<'info> {
     /// The grow state PDA
-    /// Boxed to avoid stack overflow (account is ~359 bytes)
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
     #[account(
         mut,
-        seeds = [b"grow", grow_state.match_id.to_le_bytes().as_ref()],
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
         bump = grow_state.bump
     )]
     pub grow_state: Box<Account<'info, MatchGrowState>>,
     
-    /// The corresponding match state (for timing validation)
-    /// Boxed to avoid stack overflow (account is large with 23 customers)
+    /// The corresponding match state (for timing validation and replay seq)
     #[account(
+        mut,
         seeds = [
             b"match",
             grow_state.match_id_hash.as_ref(),
             grow_state.player_a.as_ref(),
             grow_state.player_b.as_ref()
         ],
-        bump = match_state.bump
+        bump = match_state.load()?.bump
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
     
     /// The player harvesting the plant
     pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 #[event]
@@ -187,3 +210,40 @@ pub struct HarvestStrainEvent {
     pub new_inventory_count: u8,
     pub total_inventory: u8,
 }
+
+/// Emitted when a harvest completes a combo and grants a bonus item
+/// (new_inventory_count/total_inventory include the bonus)
+#[event]
+pub struct HarvestComboEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    /// Strain level of the bonus item
+    pub strain_level: u8,
+    /// Consecutive quick harvests that earned the bonus
+    pub combo: u8,
+    pub new_inventory_count: u8,
+    pub total_inventory: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted when a player's inventory becomes full (harvest) or frees space (sell/spoilage)
+#[event]
+pub struct InventoryCapacityEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    /// true: now full, false: space available again
+    pub became_full: bool,
+    pub total_inventory: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted when held inventory spoils (stack older than SPOILAGE_SECONDS)
+#[event]
+pub struct InventorySpoiledEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub level1_spoiled: u8,
+    pub level2_spoiled: u8,
+    pub level3_spoiled: u8,
+    pub timestamp: i64,
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{MatchDeliveryState, MatchState, MAX_DELIVERY_SPOTS};
+use crate::state::{MatchDeliveryState, MatchState, MAX_DELIVERY_SPOTS, ProgramConfig, PROGRAM_CONFIG_SEED};
 use crate::errors::DroogError;
 
 /// Initialize the delivery state PDA for a match
@@ -14,36 +14,43 @@ use crate::errors::DroogError;
 /// Authority: Solana ONLY
 /// - Client cannot influence initial selection
 /// - All randomness is deterministic and replayable
+/// 
+/// Idempotent: retrying after the account was already set up for this match
+/// succeeds as a no-op (existing spots are left untouched).
 pub fn init_delivery_state(
     ctx: Context<InitDeliveryState>, 
-    _match_id_hash: [u8; 32], // Used in seeds constraint
+    match_id_hash: [u8; 32],
     match_id: u64
 ) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let delivery_state = &mut ctx.accounts.delivery_state;
-    let match_state = &ctx.accounts.match_state;
+    let match_state = ctx.accounts.match_state.load()?;
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
     
     // Validate match_id matches the referenced MatchState
     require!(match_state.match_id == match_id, DroogError::MatchIdMismatch);
+    require!(!match_state.delivery_less(), DroogError::DeliveryDisabled);
     
-    // Initialize delivery state
-    delivery_state.match_id = match_id;
-    delivery_state.bump = ctx.bumps.delivery_state;
+    // Safe retry: never re-run selection on an existing account
+    if delivery_state.check_reinit(&match_id_hash, match_id)? {
+        return Ok(());
+    }
     
-    // Compute initial delivery spots using deterministic seed
-    let seed = MatchDeliveryState::compute_delivery_seed(match_id, current_ts);
-    let (spots, count) = MatchDeliveryState::select_delivery_spots(seed);
-    
-    delivery_state.available_customers = spots;
-    delivery_state.active_count = count;
-    delivery_state.last_update_ts = current_ts;
+    // Initialize delivery state and compute the initial spots
+    // (the seeds tie match_id_hash to match_state's)
+    delivery_state.initialize(&match_state, ctx.bumps.delivery_state, current_ts);
     
     // Emit initialization event
     emit!(DeliveryStateInitializedEvent {
         match_id,
-        initial_spots: spots,
-        active_count: count,
+        initial_spots: delivery_state.available_customers,
+        active_count: delivery_state.active_count,
+        demanded_variants: delivery_state.demanded_variants,
+        rush_customer: delivery_state.rush_customer,
+        rush_expires_ts: delivery_state.rush_expires_ts,
         rotation_bucket: MatchDeliveryState::get_rotation_bucket(current_ts),
         timestamp: current_ts,
     });
@@ -56,27 +63,26 @@ pub fn init_delivery_state(
 pub struct InitDeliveryState<'info> {
     /// The delivery state PDA to initialize
     #[account(
-        init,
+        init_if_needed,
         payer = payer,
         space = MatchDeliveryState::SIZE,
-        seeds = [b"delivery", match_id.to_le_bytes().as_ref()],
+        seeds = [b"delivery", match_id_hash.as_ref()],
         bump
     )]
     pub delivery_state: Account<'info, MatchDeliveryState>,
     
     /// The corresponding match state (must exist)
-    /// Boxed to avoid stack overflow (account is large with 23 customers)
     #[account(
         seeds = [
             b"match",
             match_id_hash.as_ref(),
-            match_state.player_a.as_ref(),
-            match_state.player_b.as_ref()
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
         ],
-        bump = match_state.bump,
-        constraint = match_state.match_id == match_id @ DroogError::MatchIdMismatch
+        bump = match_state.load()?.bump,
+        constraint = match_state.load()?.match_id == match_id @ DroogError::MatchIdMismatch
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
     
     /// Payer for account creation (should be one of the players)
     #[account(mut)]
@@ -84,6 +90,11 @@ pub struct InitDeliveryState<'info> {
     
     /// System program for account creation
     pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 /// Event emitted when delivery state is initialized
@@ -95,6 +106,12 @@ pub struct DeliveryStateInitializedEvent {
     pub initial_spots: [u8; MAX_DELIVERY_SPOTS],
     /// Number of active spots
     pub active_count: u8,
+    /// Variant demanded per spot (255 = any)
+    pub demanded_variants: [u8; MAX_DELIVERY_SPOTS],
+    /// Rush customer for this bucket (255 = none)
+    pub rush_customer: u8,
+    /// Rush customer expiry timestamp
+    pub rush_expires_ts: i64,
     /// Rotation bucket number for client sync
     pub rotation_bucket: u64,
     /// Initialization timestamp
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{MatchGrowState, MatchState, GrowSlot, Inventory, SLOTS_PER_PLAYER};
+use crate::state::{MatchGrowState, MatchState, ProgramConfig, PROGRAM_CONFIG_SEED};
 use crate::errors::DroogError;
 
 /// Initialize the grow state PDA for a match
@@ -9,31 +9,30 @@ use crate::errors::DroogError;
 /// 1. Keep account sizes manageable
 /// 2. Allow independent iteration on grow mechanics
 /// 3. Enable parallel fetching of match vs grow state
+/// 
+/// Idempotent: retrying after the account was already set up for this match
+/// succeeds as a no-op (slots and inventory are never reset).
 pub fn init_grow_state(
     ctx: Context<InitGrowState>, 
     match_id_hash: [u8; 32],
     match_id: u64
 ) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let grow_state = &mut ctx.accounts.grow_state;
-    let match_state = &ctx.accounts.match_state;
+    let match_state = ctx.accounts.match_state.load()?;
     
     // Validate match_id matches the referenced MatchState
     require!(match_state.match_id == match_id, DroogError::MatchIdMismatch);
     
-    // Initialize grow state
-    grow_state.match_id = match_id;
-    grow_state.match_id_hash = match_id_hash; // Store hash for PDA derivation in other instructions
-    grow_state.player_a = match_state.player_a;
-    grow_state.player_b = match_state.player_b;
-    grow_state.bump = ctx.bumps.grow_state;
+    // Safe retry: never wipe an existing grow state
+    if grow_state.check_reinit(&match_id_hash, match_id)? {
+        return Ok(());
+    }
     
-    // Initialize empty grow slots for both players
-    grow_state.player_a_slots = [GrowSlot::default(); SLOTS_PER_PLAYER];
-    grow_state.player_b_slots = [GrowSlot::default(); SLOTS_PER_PLAYER];
-    
-    // Initialize empty inventories
-    grow_state.player_a_inventory = Inventory::default();
-    grow_state.player_b_inventory = Inventory::default();
+    // Initialize grow state (the seeds tie match_id_hash to match_state's)
+    grow_state.initialize(&match_state, ctx.bumps.grow_state);
     
     // Emit initialization event
     emit!(GrowStateInitializedEvent {
@@ -49,29 +48,28 @@ pub fn init_grow_state(
 #[instruction(match_id_hash: [u8; 32], match_id: u64)]
 pub struct InitGrowState<'info> {
     /// The grow state PDA to initialize
-    /// Boxed to avoid stack overflow (account is ~359 bytes)
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
     #[account(
-        init,
+        init_if_needed,
         payer = payer,
         space = MatchGrowState::SIZE,
-        seeds = [b"grow", match_id.to_le_bytes().as_ref()],
+        seeds = [b"grow", match_id_hash.as_ref()],
         bump
     )]
     pub grow_state: Box<Account<'info, MatchGrowState>>,
     
     /// The corresponding match state (must exist)
-    /// Boxed to avoid stack overflow (account is large with 23 customers)
     #[account(
         seeds = [
             b"match",
             match_id_hash.as_ref(),
-            match_state.player_a.as_ref(),
-            match_state.player_b.as_ref()
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
         ],
-        bump = match_state.bump,
-        constraint = match_state.match_id == match_id @ DroogError::MatchIdMismatch
+        bump = match_state.load()?.bump,
+        constraint = match_state.load()?.match_id == match_id @ DroogError::MatchIdMismatch
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
     
     /// Payer for account creation (should be one of the players)
     #[account(mut)]
@@ -79,6 +77,11 @@ pub struct InitGrowState<'info> {
     
     /// System program for account creation
     pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 #[event]
@@ -1,13 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{
-    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
-};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::{
-    MatchState, CustomerState, MatchStakeState, MatchStatus,
-    STAKE_AMOUNT, MatchStakeInitializedEvent,
+    MatchState, MatchStakeState, ESCROW_AUTH_SEED, MatchStakeInitializedEvent,
+    LayerConfig, MercyRule, MatchDeliveryState, DEFAULT_EXTRA_DELIVERY_SPOTS,
+    ProgramConfig, PROGRAM_CONFIG_SEED, StrainUnlock, PotSplit,
+    GameConfig, GameTunables, GAME_CONFIG_SEED,
 };
 use crate::errors::DroogError;
+use crate::utils::escrow_player_a_stake;
 
 /// Initialize a match with Player A's stake
 /// 
@@ -16,108 +17,254 @@ use crate::errors::DroogError;
 /// - Match status = Pending (waiting for Player B)
 /// - Player A can cancel and get refund if Player B never joins
 /// 
+/// Handicap: player_a_rep_start / player_b_rep_start seed the reputation
+/// fields (clamped to REP bounds) so a stronger player can spot the weaker one.
+/// 
+/// layer_config: per-layer customer counts for the map shape (None = 3/8/12).
+/// 
+/// extra_delivery_spots: delivery spots beyond the 3 per-layer minimums,
+/// 0..=MAX_EXTRA_DELIVERY_SPOTS (None = 2, the classic 5-spot board).
+/// 
+/// delivery_less: skip the delivery system; sales are gated by cooldowns only.
+/// 
+/// rep_ledger: keep per-player reputation ledgers (recent deltas + reasons).
+/// 
+/// delayed_settlement: finalize records the payout and execute_settlement
+/// transfers it after a challenge window (for high-stakes matches).
+/// 
+/// mercy_rule: sales margin/cap that lets a blowout finalize early (None = off).
+/// 
+/// start_grace: no planting or selling for START_GRACE_SECONDS after start_ts,
+/// so the faster-connecting player gets no head start.
+/// 
+/// hide_live_scores: fog of war - sales emit no player-attributed score events
+/// during play (the account state itself remains public); finalize reveals all.
+/// 
+/// stake_amount: per-player stake in raw token units (nonzero, at most
+/// MAX_STAKE_AMOUNT; ignored for practice). Player B must match it to join.
+/// 
+/// max_sales_per_bucket: per-player sales cap per 60s rotation bucket, so
+/// inventory is sold steadily rather than dumped at the end (None or 0 = off).
+/// 
+/// strain_unlock: minimum reputation to plant Level 2 / Level 3, so players
+/// work up from Level 1 during the match (None = every level open).
+/// 
+/// player_b_stake_amount: handicap stake Player B escrows instead of matching
+/// stake_amount (same bounds; None = symmetric, ignored for practice).
+/// 
+/// pot_split: pre-agreed share of the pot for each outcome (decisive/narrow
+/// win for either player, draw) instead of winner-take-all (None = off; not
+/// allowed with practice or delayed settlement).
+/// 
+/// shared_land: adds SHARED_SLOT_COUNT neutral grow slots either player can
+/// claim by planting (plant_shared_slot); a claim holds until harvested.
+/// 
+/// practice: no stake at all - nothing is escrowed, burned, or paid out, and
+/// the token accounts (mint, player_a_token_account, escrow) may be omitted.
+/// 
 /// Authority: Solana ONLY
 /// - All token transfers are program-controlled
 /// - Client cannot influence escrow amounts
+#[allow(clippy::too_many_arguments)]
 pub fn init_match(
     ctx: Context<InitMatch>, 
     match_id_hash: [u8; 32],
     match_id: Option<u64>,
-    start_ts: i64
+    start_ts: i64,
+    player_a_rep_start: i32,
+    player_b_rep_start: i32,
+    delayed_settlement: bool,
+    layer_config: Option<LayerConfig>,
+    delivery_less: bool,
+    rep_ledger: bool,
+    practice: bool,
+    mercy_rule: Option<MercyRule>,
+    extra_delivery_spots: Option<u8>,
+    start_grace: bool,
+    hide_live_scores: bool,
+    stake_amount: u64,
+    max_sales_per_bucket: Option<u8>,
+    strain_unlock: Option<StrainUnlock>,
+    player_b_stake_amount: Option<u64>,
+    pot_split: Option<PotSplit>,
+    shared_land: bool,
 ) -> Result<()> {
-    let match_state = &mut ctx.accounts.match_state;
-    let stake_state = &mut ctx.accounts.stake_state;
-    let clock = Clock::get()?;
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
     
-    // Validate match hasn't started yet or just started
-    require!(start_ts <= clock.unix_timestamp + 60, DroogError::MatchNotStarted);
-    
-    // Validate player has sufficient balance
-    require!(
-        ctx.accounts.player_a_token_account.amount >= STAKE_AMOUNT,
-        DroogError::InsufficientStakeBalance
-    );
-    
-    // Derive match_id from hash if not provided (use first 8 bytes as u64)
-    let derived_match_id = match_id.unwrap_or_else(|| {
-        u64::from_le_bytes([
-            match_id_hash[0], match_id_hash[1], match_id_hash[2], match_id_hash[3],
-            match_id_hash[4], match_id_hash[5], match_id_hash[6], match_id_hash[7],
-        ])
-    });
+    let clock = Clock::get()?;
+    let player_a = ctx.accounts.player_a.key();
+    let player_b = ctx.accounts.player_b.key();
     
-    // ========== Initialize Match State ==========
-    match_state.match_id = derived_match_id;
-    match_state.match_id_hash = match_id_hash;
-    match_state.start_ts = start_ts;
-    match_state.end_ts = start_ts + (10 * 60); // 10 minutes (fast-paced)
-    match_state.player_a = ctx.accounts.player_a.key();
-    match_state.player_b = ctx.accounts.player_b.key();
-    match_state.player_a_sales = 0;
-    match_state.player_b_sales = 0;
-    match_state.player_a_reputation = 0;
-    match_state.player_b_reputation = 0;
-    match_state.is_finalized = false;
-    match_state.bump = ctx.bumps.match_state;
-    
-    // Initialize customers with deterministic layer assignments
-    for i in 0..23 {
-        match_state.customers[i] = CustomerState {
-            layer: if i < 12 { 1 } else if i < 20 { 2 } else { 3 },
-            last_served_ts: 0,
-            total_serves: 0,
-            last_served_by: None,
-        };
+    NewMatchArgs {
+        match_id_hash,
+        match_id,
+        start_ts,
+        player_a,
+        player_b,
+        player_a_rep_start,
+        player_b_rep_start,
+        delayed_settlement,
+        layer_config,
+        delivery_less,
+        rep_ledger,
+        practice,
+        mercy_rule,
+        extra_delivery_spots,
+        start_grace,
+        hide_live_scores,
+        stake_amount,
+        max_sales_per_bucket,
+        strain_unlock,
+        player_b_stake_amount,
+        pot_split,
+        shared_land,
+        tunables: GameConfig::load_tunables(&ctx.accounts.game_config)?,
     }
-    
-    // ========== Initialize Stake State ==========
-    stake_state.match_id = derived_match_id;
-    stake_state.match_id_hash = match_id_hash;
-    stake_state.player_a = ctx.accounts.player_a.key();
-    stake_state.player_b = ctx.accounts.player_b.key();
-    stake_state.status = MatchStatus::Pending;
-    stake_state.player_a_escrowed = STAKE_AMOUNT;
-    stake_state.player_b_escrowed = 0; // Not yet joined
-    stake_state.created_at = clock.unix_timestamp;
-    stake_state.bump = ctx.bumps.stake_state;
-    stake_state.escrow_bump = ctx.bumps.escrow_token_account;
+    .initialize(
+        &mut *ctx.accounts.match_state.load_init()?,
+        &mut ctx.accounts.stake_state,
+        ctx.bumps.match_state,
+        ctx.bumps.stake_state,
+        ctx.bumps.escrow_token_account.unwrap_or_default(),
+        clock.unix_timestamp,
+    )?;
+    let stake_state = &ctx.accounts.stake_state;
+    let stake_amount = stake_state.stake_amount;
     
     // ========== Transfer Player A's Stake to Escrow (NO BURN) ==========
     // Option C: 100% goes to escrow, burn happens only when Player B joins
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.player_a_token_account.to_account_info(),
-        to: ctx.accounts.escrow_token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        authority: ctx.accounts.player_a.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-    );
-    transfer_checked(cpi_ctx, STAKE_AMOUNT, ctx.accounts.mint.decimals)?;
+    escrow_player_a_stake(
+        stake_state.practice,
+        stake_amount,
+        ctx.accounts.mint.as_ref(),
+        ctx.accounts.player_a_token_account.as_ref(),
+        ctx.accounts.escrow_token_account.as_ref(),
+        ctx.accounts.escrow_authority.as_ref(),
+        &ctx.accounts.player_a,
+        &ctx.accounts.token_program,
+    )?;
     
     // Emit event
     emit!(MatchStakeInitializedEvent {
-        match_id: derived_match_id,
-        player_a: ctx.accounts.player_a.key(),
-        player_b: ctx.accounts.player_b.key(),
-        amount_escrowed: STAKE_AMOUNT,
+        match_id: stake_state.match_id,
+        player_a,
+        player_b,
+        amount_escrowed: stake_state.player_a_escrowed,
+        player_b_stake_amount: stake_state.player_b_stake_amount,
         timestamp: clock.unix_timestamp,
     });
     
     Ok(())
 }
 
+/// init_match's rules and players (shared with setup_match)
+pub(crate) struct NewMatchArgs {
+    pub match_id_hash: [u8; 32],
+    pub match_id: Option<u64>,
+    pub start_ts: i64,
+    pub player_a: Pubkey,
+    pub player_b: Pubkey,
+    pub player_a_rep_start: i32,
+    pub player_b_rep_start: i32,
+    pub delayed_settlement: bool,
+    pub layer_config: Option<LayerConfig>,
+    pub delivery_less: bool,
+    pub rep_ledger: bool,
+    pub practice: bool,
+    pub mercy_rule: Option<MercyRule>,
+    pub extra_delivery_spots: Option<u8>,
+    pub start_grace: bool,
+    pub hide_live_scores: bool,
+    pub stake_amount: u64,
+    pub max_sales_per_bucket: Option<u8>,
+    pub strain_unlock: Option<StrainUnlock>,
+    pub player_b_stake_amount: Option<u64>,
+    pub pot_split: Option<PotSplit>,
+    pub shared_land: bool,
+    /// Snapshot of the GameConfig at creation
+    pub tunables: GameTunables,
+}
+
+impl NewMatchArgs {
+    /// Validate the rules and set up freshly created match and stake accounts
+    /// (Pending, Player A's stake recorded as escrowed; the caller transfers it)
+    pub(crate) fn initialize(
+        self,
+        match_state: &mut MatchState,
+        stake_state: &mut MatchStakeState,
+        match_bump: u8,
+        stake_bump: u8,
+        escrow_bump: u8,
+        current_ts: i64,
+    ) -> Result<()> {
+        // Validate match hasn't started yet or just started
+        require!(self.start_ts <= current_ts + 60, DroogError::MatchNotStarted);
+        
+        // Validate the map layout covers the full roster
+        let layer_config = self.layer_config.unwrap_or_default();
+        layer_config.validate()?;
+        let extra_delivery_spots = self.extra_delivery_spots.unwrap_or(DEFAULT_EXTRA_DELIVERY_SPOTS);
+        MatchDeliveryState::validate_extra_spots(extra_delivery_spots)?;
+        let strain_unlock = self.strain_unlock.unwrap_or_default();
+        strain_unlock.validate()?;
+        
+        // Practice matches have nothing to settle
+        let practice = self.practice;
+        MatchStakeState::validate_stake_mode(practice, self.delayed_settlement)?;
+        let stake_amount = if practice { 0 } else { self.stake_amount };
+        let player_b_stake_amount = self.player_b_stake_amount.filter(|_| !practice).unwrap_or(stake_amount);
+        if !practice {
+            MatchStakeState::validate_stake_amount(stake_amount)?;
+            MatchStakeState::validate_stake_amount(player_b_stake_amount)?;
+        }
+        let pot_split = self.pot_split.unwrap_or_default();
+        MatchStakeState::validate_pot_split(&pot_split, practice, self.delayed_settlement)?;
+        
+        // Derive match_id from the full hash if not provided
+        let derived_match_id = self.match_id.unwrap_or_else(|| MatchState::derive_match_id(&self.match_id_hash));
+        
+        // ========== Initialize Match State ==========
+        match_state.initialize(
+            derived_match_id,
+            self.match_id_hash,
+            self.player_a,
+            self.player_b,
+            self.start_ts,
+            layer_config,
+            match_bump,
+        )?;
+        match_state.rep_ledger_enabled = u8::from(self.rep_ledger);
+        match_state.apply_starting_reputation(self.player_a_rep_start, self.player_b_rep_start);
+        match_state.delivery_less = u8::from(self.delivery_less);
+        match_state.extra_delivery_spots = extra_delivery_spots;
+        match_state.start_grace = u8::from(self.start_grace);
+        match_state.hide_live_scores = u8::from(self.hide_live_scores);
+        match_state.max_sales_per_bucket = self.max_sales_per_bucket.unwrap_or(0);
+        match_state.strain_unlock = strain_unlock;
+        match_state.shared_land = u8::from(self.shared_land);
+        match_state.tunables = self.tunables;
+        
+        // ========== Initialize Stake State ==========
+        stake_state.initialize(match_state, stake_amount, current_ts, stake_bump, escrow_bump);
+        stake_state.delayed_settlement = self.delayed_settlement;
+        stake_state.practice = practice;
+        stake_state.mercy_rule = self.mercy_rule.unwrap_or_default();
+        stake_state.player_b_stake_amount = player_b_stake_amount;
+        stake_state.pot_split = pot_split;
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 #[instruction(match_id_hash: [u8; 32])]
 pub struct InitMatch<'info> {
     // ========== Game State PDAs ==========
-    // Boxed to avoid stack overflow (MatchState is large with 23 customers)
     
     #[account(
         init,
         payer = player_a,
-        space = MatchState::SIZE,
+        space = 8 + MatchState::SIZE,
         seeds = [
             b"match",
             match_id_hash.as_ref(),
@@ -127,7 +274,7 @@ pub struct InitMatch<'info> {
         bump,
         constraint = player_a.key() < player_b.key() @ DroogError::InvalidPlayerOrder
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
     
     #[account(
         init,
@@ -139,10 +286,11 @@ pub struct InitMatch<'info> {
     pub stake_state: Box<Account<'info, MatchStakeState>>,
     
     // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
     
     /// $PACKS token mint
     #[account(mut)]
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
     
     /// Player A's $PACKS token account
     #[account(
@@ -150,7 +298,7 @@ pub struct InitMatch<'info> {
         associated_token::mint = mint,
         associated_token::authority = player_a,
     )]
-    pub player_a_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub player_a_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow token account (PDA-controlled)
     /// Seeds: ["escrow", match_id_hash]
@@ -162,16 +310,17 @@ pub struct InitMatch<'info> {
         seeds = [b"escrow", match_id_hash.as_ref()],
         bump
     )]
-    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow authority PDA (signs for escrow transfers)
     /// Seeds: ["escrow_auth", match_id_hash]
+    /// Signing-only: holds no lamports unless topped up via fund_escrow_authority
     /// CHECK: This is a PDA used only as signing authority for escrow
     #[account(
-        seeds = [b"escrow_auth", match_id_hash.as_ref()],
+        seeds = [ESCROW_AUTH_SEED, match_id_hash.as_ref()],
         bump
     )]
-    pub escrow_authority: UncheckedAccount<'info>,
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
     
     // ========== Players ==========
     
@@ -187,4 +336,14 @@ pub struct InitMatch<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+    
+    /// Balance tunables to snapshot (no GameConfig account = compiled-in defaults)
+    /// CHECK: address fixed by seeds; contents read by GameConfig::load_tunables
+    #[account(seeds = [GAME_CONFIG_SEED], bump)]
+    pub game_config: UncheckedAccount<'info>,
 }
@@ -5,14 +5,18 @@ use anchor_spl::token_interface::{
 };
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::{
-    MatchStakeState, MatchStatus, STAKE_AMOUNT, MatchActivatedEvent,
+    MatchStakeState, MatchStatus, LifecycleAction, ESCROW_AUTH_SEED, MatchActivatedEvent,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
 };
 use crate::errors::DroogError;
 
 /// Player B joins the match and stakes their tokens
 /// 
 /// Option C Semantics (Critical):
-/// - Player B escrows 100% of stake to escrow
+/// - Player B escrows 100% of stake to escrow, matching Player A's current
+///   stake_amount (chosen at init, or raised by Player A via add_stake), or
+///   the handicap stake agreed at init
+/// - Burn is 10% of the combined escrow, whatever each side put in
 /// - Burn occurs ONLY here (10% of total escrowed)
 /// - Match status transitions to Active ATOMICALLY with burn
 /// - This is the point of no return - bilateral commitment complete
@@ -22,20 +26,26 @@ use crate::errors::DroogError;
 /// - Player B must match the expected player_b from stake_state
 /// - Burn is calculated from combined escrow, executed once
 /// 
+/// Practice matches: joining is a plain opt-in - no transfer, no burn, and
+/// the token accounts may be omitted.
+/// 
+/// `expected_stake_amount` is the stake Player B agreed to match (0 for
+/// practice); the join fails if Player A raised it in the meantime.
+/// 
 /// Authority: Solana ONLY
 /// - Burns are irreversible once this instruction succeeds
 /// - Client cannot influence burn amount or timing
-pub fn join_match_with_stake(ctx: Context<JoinMatchWithStake>) -> Result<()> {
+pub fn join_match_with_stake(ctx: Context<JoinMatchWithStake>, expected_stake_amount: u64) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let stake_state = &mut ctx.accounts.stake_state;
     let clock = Clock::get()?;
     
     // ========== Invariant Checks ==========
     
     // Must be in Pending status
-    require!(
-        stake_state.status == MatchStatus::Pending,
-        DroogError::MatchNotPending
-    );
+    stake_state.check_lifecycle(LifecycleAction::Join)?;
     
     // Player B must not have staked yet
     require!(
@@ -43,27 +53,57 @@ pub fn join_match_with_stake(ctx: Context<JoinMatchWithStake>) -> Result<()> {
         DroogError::AlreadyStaked
     );
     
+    // Player B matches Player A's (possibly raised) stake, or their handicap
+    // stake - but only the amount they signed up for
+    let stake_amount = stake_state.check_join_stake(expected_stake_amount)?;
+    
+    // ========== Practice: Opt-In Only ==========
+    if stake_state.practice {
+        stake_state.status = MatchStatus::Active;
+        
+        emit!(MatchActivatedEvent {
+            match_id: stake_state.match_id,
+            player_a: stake_state.player_a,
+            player_b: stake_state.player_b,
+            total_escrowed: 0,
+            amount_burned: 0,
+            final_pot: 0,
+            timestamp: clock.unix_timestamp,
+        });
+        
+        return Ok(());
+    }
+    
+    let (Some(mint), Some(player_b_token_account), Some(escrow_token_account), Some(escrow_authority)) = (
+        ctx.accounts.mint.as_ref(),
+        ctx.accounts.player_b_token_account.as_ref(),
+        ctx.accounts.escrow_token_account.as_ref(),
+        ctx.accounts.escrow_authority.as_ref(),
+    ) else {
+        return err!(DroogError::StakeAccountsRequired);
+    };
+    
     // Validate player has sufficient balance
     require!(
-        ctx.accounts.player_b_token_account.amount >= STAKE_AMOUNT,
+        player_b_token_account.amount >= stake_amount,
         DroogError::InsufficientStakeBalance
     );
     
     // ========== Transfer Player B's Stake to Escrow ==========
     let transfer_accounts = TransferChecked {
-        from: ctx.accounts.player_b_token_account.to_account_info(),
-        to: ctx.accounts.escrow_token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
+        from: player_b_token_account.to_account_info(),
+        to: escrow_token_account.to_account_info(),
+        mint: mint.to_account_info(),
         authority: ctx.accounts.player_b.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         transfer_accounts,
     );
-    transfer_checked(cpi_ctx, STAKE_AMOUNT, ctx.accounts.mint.decimals)?;
+    transfer_checked(cpi_ctx, stake_amount, mint.decimals)?;
     
     // Update stake state with Player B's contribution
-    stake_state.player_b_escrowed = STAKE_AMOUNT;
+    stake_state.player_b_escrowed = stake_amount;
     
     // ========== Execute Burn (Option C Critical Section) ==========
     // Burn occurs ONLY after both players have escrowed
@@ -78,17 +118,17 @@ pub fn join_match_with_stake(ctx: Context<JoinMatchWithStake>) -> Result<()> {
     // Burn from escrow using PDA authority
     if burn_amount > 0 {
         let match_id_hash = stake_state.match_id_hash;
-        let escrow_auth_bump = ctx.bumps.escrow_authority;
+        let escrow_auth_bump = ctx.bumps.escrow_authority.unwrap_or_default();
         let signer_seeds: &[&[&[u8]]] = &[&[
-            b"escrow_auth",
+            ESCROW_AUTH_SEED,
             match_id_hash.as_ref(),
             &[escrow_auth_bump],
         ]];
         
         let burn_accounts = Burn {
-            mint: ctx.accounts.mint.to_account_info(),
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            authority: ctx.accounts.escrow_authority.to_account_info(),
+            mint: mint.to_account_info(),
+            from: escrow_token_account.to_account_info(),
+            authority: escrow_authority.to_account_info(),
         };
         let burn_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
@@ -128,15 +168,16 @@ pub struct JoinMatchWithStake<'info> {
         seeds = [b"stake", stake_state.match_id_hash.as_ref()],
         bump = stake_state.bump,
         constraint = stake_state.player_b == player_b.key() @ DroogError::InvalidPlayer,
-        constraint = stake_state.status == MatchStatus::Pending @ DroogError::MatchNotPending,
+        constraint = stake_state.allows(LifecycleAction::Join) @ DroogError::MatchNotPending,
     )]
     pub stake_state: Account<'info, MatchStakeState>,
     
     // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
     
     /// $PACKS token mint
     #[account(mut)]
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
     
     /// Player B's $PACKS token account
     #[account(
@@ -144,7 +185,7 @@ pub struct JoinMatchWithStake<'info> {
         associated_token::mint = mint,
         associated_token::authority = player_b,
     )]
-    pub player_b_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub player_b_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow token account (already initialized by init_match)
     #[account(
@@ -152,15 +193,15 @@ pub struct JoinMatchWithStake<'info> {
         seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
         bump = stake_state.escrow_bump,
     )]
-    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     
     /// Escrow authority PDA (signs for escrow burns)
     /// CHECK: This is a PDA used only as signing authority
     #[account(
-        seeds = [b"escrow_auth", stake_state.match_id_hash.as_ref()],
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
         bump
     )]
-    pub escrow_authority: UncheckedAccount<'info>,
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
     
     // ========== Players ==========
     
@@ -172,4 +213,9 @@ pub struct JoinMatchWithStake<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
@@ -1,23 +1,99 @@
+pub mod accept_double_or_nothing;
+pub mod add_stake;
 pub mod cancel_match;
+pub mod cancel_match_mutual;
+pub mod clear_slot;
+pub mod close_match_accounts;
+pub mod decline_double_or_nothing;
+pub mod execute_settlement;
+pub mod fertilize_slot;
 pub mod finalize_match;
+pub mod freeze_settlement;
+pub mod fund_escrow_authority;
+pub mod fund_refresh_pool;
+pub mod get_constants;
+pub mod get_customer_flavors;
+pub mod get_error_hint;
+pub mod get_match_checksum;
+pub mod get_match_insight;
+pub mod get_projected_ready;
 pub mod harvest;
+pub mod harvest_all;
+pub mod harvest_shared_slot;
 pub mod harvest_strain;
 pub mod init_delivery_state;
 pub mod init_grow_state;
 pub mod init_match;
 pub mod join_match_stake;
+pub mod offer_double_or_nothing;
+pub mod plant_many;
+pub mod plant_shared_slot;
 pub mod plant_strain;
+pub mod preview_payout;
+pub mod reap_abandoned;
+pub mod rematch;
 pub mod refresh_delivery_slots;
+pub mod replace_plant;
+pub mod resolve_dispute;
+pub mod reverse_finalization;
+pub mod seed_opening_plants;
 pub mod sell_to_customer;
+pub mod set_game_config;
+pub mod set_program_paused;
+pub mod set_treasury;
+pub mod setup_match;
+pub mod surrender_match;
+pub mod trigger_raid;
+pub mod upgrade_inventory;
+pub mod use_expansion;
+pub mod view_smell;
 
+pub use accept_double_or_nothing::*;
+pub use add_stake::*;
 pub use cancel_match::*;
+pub use cancel_match_mutual::*;
+pub use clear_slot::*;
+pub use close_match_accounts::*;
+pub use decline_double_or_nothing::*;
+pub use execute_settlement::*;
+pub use fertilize_slot::*;
 pub use finalize_match::*;
+pub use freeze_settlement::*;
+pub use fund_escrow_authority::*;
+pub use fund_refresh_pool::*;
+pub use get_constants::*;
+pub use get_customer_flavors::*;
+pub use get_error_hint::*;
+pub use get_match_checksum::*;
+pub use get_match_insight::*;
+pub use get_projected_ready::*;
 pub use harvest::*;
+pub use harvest_all::*;
+pub use harvest_shared_slot::*;
 pub use harvest_strain::*;
 pub use init_delivery_state::*;
 pub use init_grow_state::*;
 pub use init_match::*;
 pub use join_match_stake::*;
+pub use offer_double_or_nothing::*;
+pub use plant_many::*;
+pub use plant_shared_slot::*;
 pub use plant_strain::*;
+pub use preview_payout::*;
+pub use reap_abandoned::*;
+pub use rematch::*;
 pub use refresh_delivery_slots::*;
+pub use replace_plant::*;
+pub use resolve_dispute::*;
+pub use reverse_finalization::*;
+pub use seed_opening_plants::*;
 pub use sell_to_customer::*;
+pub use set_game_config::*;
+pub use set_program_paused::*;
+pub use set_treasury::*;
+pub use setup_match::*;
+pub use surrender_match::*;
+pub use trigger_raid::*;
+pub use upgrade_inventory::*;
+pub use use_expansion::*;
+pub use view_smell::*;
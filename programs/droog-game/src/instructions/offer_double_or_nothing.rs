@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    DoubleOrNothingOffer, DoubleOrNothingOfferedEvent, LifecycleAction, MatchStakeState,
+    DOUBLE_OR_NOTHING_SEED, ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Offer the loser a double-or-nothing continuation of a settling match
+/// 
+/// Only for delayed settlements: the pot must still be in escrow (Settling,
+/// not frozen) and the caller must be the recorded winner. Opens the offer
+/// PDA; the loser answers with accept_double_or_nothing or
+/// decline_double_or_nothing. The offer lapses if execute_settlement pays the
+/// winner first, and a match can be offered only once.
+pub fn offer_double_or_nothing(ctx: Context<OfferDoubleOrNothing>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let stake_state = &ctx.accounts.stake_state;
+    let offer = &mut ctx.accounts.offer;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    offer.open(stake_state, &ctx.accounts.winner.key(), current_ts, ctx.bumps.offer)?;
+    
+    emit!(DoubleOrNothingOfferedEvent {
+        match_id: stake_state.match_id,
+        winner: offer.winner,
+        loser: offer.loser,
+        pot: offer.pot,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OfferDoubleOrNothing<'info> {
+    /// Settling match's stake state (holds the recorded winner and pot)
+    #[account(
+        seeds = [b"stake", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.allows(LifecycleAction::DoubleOrNothing) @ DroogError::SettlementNotPending,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        init,
+        payer = winner,
+        space = DoubleOrNothingOffer::SIZE,
+        seeds = [DOUBLE_OR_NOTHING_SEED, stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, DoubleOrNothingOffer>,
+    
+    /// Recorded winner (makes the offer and pays rent)
+    #[account(
+        mut,
+        constraint = winner.key() == stake_state.pending_settlement.winner @ DroogError::InvalidPlayer
+    )]
+    pub winner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use crate::state::{MatchGrowState, MatchState, MatchStakeState, REPLAY_ACTION_PLANT, ProgramConfig, PROGRAM_CONFIG_SEED};
+use crate::errors::DroogError;
+
+/// One entry of a plant_many batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PlantOrder {
+    pub slot_index: u8,
+    pub strain_level: u8,
+}
+
+/// Plant up to MAX_PLANT_BATCH (6) slots in one transaction
+/// 
+/// For players on poor connections: same checks as plant_strain, applied to
+/// every entry before any slot changes, so one bad entry (occupied or
+/// repeated slot, endgame lock, won't be ready in time) reverts the batch.
+/// Each plant's variant comes from compute_variant_id with its own slot index,
+/// exactly as if it had been planted on its own in this slot.
+pub fn plant_many(ctx: Context<PlantMany>, plants: Vec<PlantOrder>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    let current_slot = clock.slot;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    match_state.check_start_grace(current_ts)?;
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Higher strain levels may be locked behind reputation
+    for order in &plants {
+        match_state.check_strain_unlocked(is_player_a, order.strain_level)?;
+    }
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
+    let match_id = grow_state.match_id;
+    let reputation = match_state.reputation_of(is_player_a);
+    let planted: Vec<(u8, u8, u8)> = plants
+        .iter()
+        .map(|order| {
+            let variant_id = MatchGrowState::compute_variant_id(
+                grow_state.algo_version,
+                match_id,
+                &player,
+                order.slot_index,
+                current_slot,
+                reputation,
+            );
+            (order.slot_index, order.strain_level, variant_id)
+        })
+        .collect();
+    
+    grow_state.plant_batch(is_player_a, &planted, current_ts, match_state.end_ts)?;
+    
+    emit!(BatchPlantEvent {
+        match_id,
+        player,
+        slot_indices: planted.iter().map(|p| p.0).collect(),
+        strain_levels: planted.iter().map(|p| p.1).collect(),
+        variant_ids: planted.iter().map(|p| p.2).collect(),
+        planted_ts: current_ts,
+    });
+    
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    for &(slot_index, strain_level, variant_id) in &planted {
+        emit!(match_state.next_replay_frame(
+            REPLAY_ACTION_PLANT,
+            player,
+            slot_index,
+            strain_level,
+            variant_id,
+            current_ts,
+        ));
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PlantMany<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation and replay seq)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player planting
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted by plant_many (parallel vectors, one entry per planted slot)
+#[event]
+pub struct BatchPlantEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub slot_indices: Vec<u8>,
+    pub strain_levels: Vec<u8>,
+    pub variant_ids: Vec<u8>,
+    pub planted_ts: i64,
+}
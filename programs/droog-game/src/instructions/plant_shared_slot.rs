@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, SLOTS_PER_PLAYER,
+    REPLAY_ACTION_PLANT_SHARED,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Plant a strain in a shared-land slot, claiming it
+/// 
+/// Same checks as plant_strain, plus:
+/// - The match was created with shared_land
+/// - The slot is Neutral (a plant left to rot forfeits its claim)
+/// 
+/// The claim blocks the opponent until the claimant harvests.
+/// Shared plants don't add to the planter's smell.
+pub fn plant_shared_slot(
+    ctx: Context<PlantSharedSlot>,
+    slot_index: u8,
+    strain_level: u8,
+) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    let current_slot = clock.slot;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    require!(match_state.shared_land(), DroogError::SharedLandDisabled);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    match_state.check_start_grace(current_ts)?;
+    
+    // Validate endgame lock, strain level, and that the plant will be ready in time
+    MatchGrowState::validate_new_plant(&grow_state.tunables, current_ts, match_state.end_ts, strain_level)?;
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Higher strain levels may be locked behind reputation
+    match_state.check_strain_unlocked(is_player_a, strain_level)?;
+    
+    // Shared slots are numbered after the player's own for variant entropy
+    let match_id = grow_state.match_id;
+    let variant_id = MatchGrowState::compute_variant_id(
+        grow_state.algo_version,
+        match_id,
+        &player,
+        (SLOTS_PER_PLAYER as u8).saturating_add(slot_index),
+        current_slot,
+        match_state.reputation_of(is_player_a),
+    );
+    
+    grow_state.claim_shared_slot(is_player_a, slot_index, strain_level, variant_id, current_ts)?;
+    
+    emit!(SharedSlotClaimedEvent {
+        match_id,
+        player,
+        slot_index,
+        strain_level,
+        variant_id,
+        planted_ts: current_ts,
+    });
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_PLANT_SHARED,
+        player,
+        slot_index,
+        strain_level,
+        variant_id,
+        current_ts,
+    ));
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PlantSharedSlot<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation and replay seq)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player claiming the slot
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted when a player claims a shared-land slot by planting in it
+#[event]
+pub struct SharedSlotClaimedEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub slot_index: u8,
+    pub strain_level: u8,
+    pub variant_id: u8,
+    pub planted_ts: i64,
+}
@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{MatchGrowState, MatchState, PlantState, SLOTS_PER_PLAYER};
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, SlotTransition, SLOTS_PER_PLAYER,
+    REPLAY_ACTION_PLANT,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
 use crate::errors::DroogError;
 
 /// Plant a strain in a grow slot
@@ -8,7 +12,8 @@ use crate::errors::DroogError;
 /// 1. Validates the player has authority
 /// 2. Validates the match is active and not in endgame lock
 /// 3. Validates the slot is available
-/// 4. Validates the plant will be ready before match ends
+/// 4. Validates the plant will be ready before match ends and the player's
+///    reputation has unlocked its strain level
 /// 5. Computes deterministic variant_id
 /// 6. Locks the slot with immutable plant data
 pub fn plant_strain(
@@ -16,32 +21,30 @@ pub fn plant_strain(
     slot_index: u8,
     strain_level: u8,
 ) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
     let current_slot = clock.slot;
     
     let grow_state = &mut ctx.accounts.grow_state;
-    let match_state = &ctx.accounts.match_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
     let player = ctx.accounts.player.key();
     
     // Prevent state changes after finalization
-    require!(!match_state.is_finalized, DroogError::MatchAlreadyFinalized);
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
     
     // Validate match is active
     require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
     require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    match_state.check_start_grace(current_ts)?;
     
-    // Validate endgame lock (no planting in final 5 minutes)
-    require!(
-        MatchGrowState::can_plant(current_ts, match_state.end_ts),
-        DroogError::EndgamePlantingLocked
-    );
-    
-    // Validate strain level
-    require!(
-        strain_level >= 1 && strain_level <= 3,
-        DroogError::InvalidStrainLevel
-    );
+    // Validate endgame lock, strain level, and that the plant will be ready in time
+    MatchGrowState::validate_new_plant(&grow_state.tunables, current_ts, match_state.end_ts, strain_level)?;
     
     // Validate slot index
     require!(
@@ -49,49 +52,42 @@ pub fn plant_strain(
         DroogError::InvalidSlotIndex
     );
     
-    // Validate plant will be ready before match ends
-    require!(
-        MatchGrowState::will_be_ready_in_time(current_ts, match_state.end_ts, strain_level),
-        DroogError::PlantWontBeReady
-    );
-    
     // Determine which player's slots to use
     let is_player_a = player == grow_state.player_a;
     let is_player_b = player == grow_state.player_b;
     require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
     
+    // Higher strain levels may be locked behind reputation
+    match_state.check_strain_unlocked(is_player_a, strain_level)?;
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
     // Cache match_id and compute variant_id before mutable borrows
     // Use slot number instead of timestamp for better entropy
+    // (v2 weights the variant by the planter's current reputation)
     let match_id = grow_state.match_id;
     let variant_id = MatchGrowState::compute_variant_id(
+        grow_state.algo_version,
         match_id,
         &player,
         slot_index,
         current_slot,
+        match_state.reputation_of(is_player_a),
     );
+    // Fixed for the plant's life once rolled (0 unless the match jitters growth)
+    let growth_jitter = grow_state.next_growth_jitter(is_player_a, slot_index, strain_level);
     
+    let tunables = grow_state.tunables;
     let slots = if is_player_a {
         &mut grow_state.player_a_slots
     } else {
         &mut grow_state.player_b_slots
     };
     
-    let slot = &mut slots[slot_index as usize];
-    
-    // Validate slot is available (must be Empty)
-    require!(
-        MatchGrowState::is_slot_available(slot),
-        DroogError::SlotOccupied
-    );
-    
-    // Plant the strain - slot becomes Growing
+    // Plant the strain - slot must be Empty and becomes Growing
     // Plants are ephemeral, slots are persistent land
-    slot.plant_state = PlantState::Growing {
-        strain_level,
-        planted_at: current_ts,
-    };
-    slot.strain_level = strain_level;
-    slot.variant_id = variant_id;
+    slots[slot_index as usize].transition(&tunables, SlotTransition::Plant { strain_level, variant_id, growth_jitter }, current_ts)?;
     
     // Emit plant event (using cached match_id)
     emit!(PlantStrainEvent {
@@ -103,35 +99,61 @@ pub fn plant_strain(
         planted_ts: current_ts,
     });
     
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_PLANT,
+        player,
+        slot_index,
+        strain_level,
+        variant_id,
+        current_ts,
+    ));
+    
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct PlantStrain<'info> {
     /// The grow state PDA
-    /// Boxed to avoid stack overflow (account is ~359 bytes)
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
     #[account(
         mut,
-        seeds = [b"grow", grow_state.match_id.to_le_bytes().as_ref()],
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
         bump = grow_state.bump
     )]
     pub grow_state: Box<Account<'info, MatchGrowState>>,
     
-    /// The corresponding match state (for timing validation)
-    /// Boxed to avoid stack overflow (account is large with 23 customers)
+    /// The corresponding match state (for timing validation and replay seq)
     #[account(
+        mut,
         seeds = [
             b"match",
             grow_state.match_id_hash.as_ref(),
             grow_state.player_a.as_ref(),
             grow_state.player_b.as_ref()
         ],
-        bump = match_state.bump
+        bump = match_state.load()?.bump
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
     
     /// The player planting the strain
     pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 #[event]
@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{DecidedBy, MatchState, MatchStakeState, ProgramConfig, PROGRAM_CONFIG_SEED};
+
+/// Emit the projected settlement if the match were finalized now
+/// 
+/// Read-only: uses the same standings (diversity bonus included) and payout
+/// math as finalize_match, so both players can see exactly what is at stake
+/// before settlement. The escrow account may be omitted for practice matches
+/// (payout is always 0).
+/// 
+/// The treasury rake (once a treasury is configured) and finalizer reward
+/// come off the top; the winner receives
+/// the rest less the loser's reputation consolation (loser_amount), except in
+/// a draw, where winner_amount/loser_amount are Player A's and Player B's halves.
+/// With a pot split the amounts are the players' agreed shares for the outcome.
+pub fn preview_payout(ctx: Context<PreviewPayout>) -> Result<()> {
+    let clock = Clock::get()?;
+    let preview = compute_payout_preview(
+        &*ctx.accounts.match_state.load()?,
+        &ctx.accounts.stake_state,
+        ctx.accounts.escrow_token_account.as_ref().map(|e| e.amount),
+        ProgramConfig::treasury(&ctx.accounts.program_config)?,
+        clock.unix_timestamp,
+    )?;
+    emit!(preview);
+    Ok(())
+}
+
+/// Build the payout preview (pure, shared with tests)
+pub fn compute_payout_preview(
+    match_state: &MatchState,
+    stake_state: &MatchStakeState,
+    escrow_balance: Option<u64>,
+    treasury: Option<Pubkey>,
+    current_ts: i64,
+) -> Result<PayoutPreviewEvent> {
+    // Finalize awards the diversity bonus before picking the winner
+    let (player_a_reputation, player_b_reputation) = match_state.projected_reputation();
+    let standings = match_state.standings_with_reputation(player_a_reputation, player_b_reputation);
+    let payout_amount = stake_state.payout_amount(escrow_balance)?;
+    let decided_by = if stake_state.settles_as_draw(&standings) {
+        DecidedBy::Draw
+    } else {
+        standings.decided_by
+    };
+    // Treasury rake first; then assumes a participant finalizes (the reward
+    // is the same for either)
+    let treasury_rake = MatchStakeState::treasury_rake(payout_amount, treasury)?;
+    let payout_amount = payout_amount - treasury_rake;
+    let finalizer_reward = stake_state.finalizer_reward(
+        payout_amount,
+        match_state.tunables.finalizer_reward_bps,
+        &standings.winner,
+    );
+    let loser_reputation = if standings.loser == match_state.player_a {
+        player_a_reputation
+    } else {
+        player_b_reputation
+    };
+    let (winner_amount, loser_amount) =
+        stake_state.settlement_split(&standings, payout_amount - finalizer_reward, loser_reputation);
+    
+    Ok(PayoutPreviewEvent {
+        match_id: match_state.match_id,
+        projected_winner: standings.winner,
+        projected_loser: standings.loser,
+        winner_sales: standings.winner_sales,
+        loser_sales: standings.loser_sales,
+        decided_by,
+        escrow_balance: escrow_balance.unwrap_or(0),
+        winner_amount,
+        loser_amount,
+        finalizer_reward,
+        treasury_rake,
+        practice: stake_state.practice,
+        delayed_settlement: stake_state.delayed_settlement,
+        can_finalize_now: !match_state.is_finalized() && match_state.can_finalize_at(current_ts),
+        timestamp: current_ts,
+    })
+}
+
+#[derive(Accounts)]
+pub struct PreviewPayout<'info> {
+    #[account(
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// Escrow token account (optional only for practice matches)
+    #[account(
+        seeds = [b"escrow", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Program config (names the treasury; no Config account = no rake)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::treasury
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Projected settlement breakdown
+#[event]
+pub struct PayoutPreviewEvent {
+    pub match_id: u64,
+    /// Who would win if the match were finalized now (reputation breaks sales
+    /// ties; Player A in a draw)
+    pub projected_winner: Pubkey,
+    pub projected_loser: Pubkey,
+    pub winner_sales: u32,
+    pub loser_sales: u32,
+    /// Rule that picked projected_winner
+    pub decided_by: DecidedBy,
+    /// Current escrow balance (post-burn; 0 for practice matches)
+    pub escrow_balance: u64,
+    /// Amount the winner would receive
+    pub winner_amount: u64,
+    /// Amount the loser would receive (reputation consolation, or their half of a draw)
+    pub loser_amount: u64,
+    /// Reward for the participant who finalizes (comes off the top of the pot)
+    pub finalizer_reward: u64,
+    /// Treasury rake taken off the pot first (0 while no treasury is configured)
+    pub treasury_rake: u64,
+    pub practice: bool,
+    /// Payout would be held for the challenge window rather than paid at finalize
+    pub delayed_settlement: bool,
+    /// finalize_match would currently be accepted on timing
+    pub can_finalize_now: bool,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::DroogError;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::stake_state::tests::test_stake;
+    use crate::state::{MatchStatus, PendingSettlement, STAKE_AMOUNT};
+    
+    /// Preview with a treasury configured (the rake applies)
+    fn compute_payout_preview_at(
+        match_state: &MatchState,
+        stake_state: &MatchStakeState,
+        escrow_balance: Option<u64>,
+        current_ts: i64,
+    ) -> Result<PayoutPreviewEvent> {
+        compute_payout_preview(match_state, stake_state, escrow_balance, Some(Pubkey::new_unique()), current_ts)
+    }
+    
+    #[test]
+    fn test_preview_matches_finalize() {
+        let mut m = test_match();
+        let escrow = MatchStakeState::calculate_burn_amount(2 * STAKE_AMOUNT);
+        let escrow = 2 * STAKE_AMOUNT - escrow;
+        
+        // B leads: preview winner and amount equal what finalize records
+        m.player_a_sales = 3;
+        m.player_b_sales = 5;
+        let mut stake = MatchStakeState {
+            status: MatchStatus::Active,
+            delayed_settlement: true,
+            ..test_stake()
+        };
+        let preview = compute_payout_preview_at(&m, &stake, Some(escrow), m.end_ts).unwrap();
+        assert!(preview.can_finalize_now);
+        assert_eq!(preview.projected_winner, m.player_b);
+        assert_eq!((preview.winner_sales, preview.loser_sales), (5, 3));
+        
+        // Delayed and immediate settlements split the pot the same way, and
+        // execute pays the winner what remains of the escrow after the shares
+        let immediate = compute_payout_preview_at(&m, &test_stake(), Some(escrow), m.end_ts).unwrap();
+        assert_eq!(
+            (preview.winner_amount, preview.loser_amount, preview.finalizer_reward),
+            (immediate.winner_amount, immediate.loser_amount, immediate.finalizer_reward)
+        );
+        stake.record_pending_settlement(
+            PendingSettlement {
+                winner: m.determine_winner().winner,
+                amount: preview.winner_amount,
+                rake: preview.treasury_rake,
+                loser_amount: preview.loser_amount,
+                ..Default::default()
+            },
+            m.end_ts,
+        );
+        assert_eq!(stake.pending_settlement.winner, preview.projected_winner);
+        assert_eq!(stake.pending_settlement.winner_payout(escrow).unwrap(), preview.winner_amount);
+        assert_eq!(preview.winner_amount + preview.treasury_rake, escrow);
+        
+        // Tie splits the pot after the rake, same as finalize
+        m.player_a_sales = 5;
+        let preview = compute_payout_preview_at(&m, &test_stake(), Some(escrow), m.end_ts).unwrap();
+        assert_eq!(preview.decided_by, DecidedBy::Draw);
+        assert!(preview.treasury_rake > 0);
+        assert_eq!(preview.winner_amount + preview.loser_amount + preview.treasury_rake, escrow);
+        assert_eq!(
+            (preview.winner_amount, preview.loser_amount),
+            MatchStakeState::draw_split(escrow - preview.treasury_rake, &m.player_a, &m.player_b)
+        );
+        assert!(!preview.delayed_settlement);
+        
+        // Delayed settlements split a tie too (paid at execute)
+        let delayed = MatchStakeState { delayed_settlement: true, ..test_stake() };
+        let preview = compute_payout_preview_at(&m, &delayed, Some(escrow), m.end_ts).unwrap();
+        assert_eq!(preview.decided_by, DecidedBy::Draw);
+        assert_eq!(
+            (preview.winner_amount, preview.loser_amount),
+            MatchStakeState::draw_split(escrow - preview.treasury_rake, &m.player_a, &m.player_b)
+        );
+        
+        // Practice: nothing paid, no escrow needed
+        let practice = MatchStakeState { practice: true, ..test_stake() };
+        let preview = compute_payout_preview_at(&m, &practice, None, m.end_ts).unwrap();
+        assert_eq!((preview.escrow_balance, preview.winner_amount), (0, 0));
+        
+        // Staked match without the escrow fails like finalize does
+        assert_eq!(
+            compute_payout_preview_at(&m, &test_stake(), None, m.end_ts).map(|_| ()).unwrap_err(),
+            DroogError::StakeAccountsRequired.into()
+        );
+        
+        // Before the end the preview still projects, but finalize would be rejected
+        m.player_b_sales = 6;
+        let preview = compute_payout_preview_at(&m, &test_stake(), Some(escrow), m.end_ts - 1).unwrap();
+        assert!(!preview.can_finalize_now);
+        assert_eq!(preview.winner_amount + preview.treasury_rake, escrow);
+        
+        // No treasury configured: nothing is raked
+        let unraked = compute_payout_preview(&m, &test_stake(), Some(escrow), None, m.end_ts).unwrap();
+        assert_eq!((unraked.treasury_rake, unraked.winner_amount), (0, escrow));
+        
+        // A loser with reputation gets their consolation carved from the raked pot
+        m.player_a_reputation = 20;
+        let preview = compute_payout_preview_at(&m, &test_stake(), Some(escrow), m.end_ts).unwrap();
+        let raked = escrow - preview.treasury_rake;
+        assert_eq!(preview.loser_amount, MatchStakeState::loser_consolation(raked, 20));
+        assert_eq!(preview.winner_amount + preview.loser_amount, raked);
+    }
+}
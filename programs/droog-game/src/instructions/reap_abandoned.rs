@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount,
+    transfer_checked, close_account,
+};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{
+    MatchState, MatchStakeState, MatchGrowState, MatchDeliveryState, LifecycleAction, ESCROW_AUTH_SEED, MatchReapedEvent,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Reap an abandoned Pending match and refund Player A
+/// 
+/// cancel_match requires Player A to act. If Player A also disappears,
+/// the escrow would sit forever. After ABANDONED_MATCH_HORIZON_SECONDS,
+/// anyone may trigger the refund.
+/// 
+/// Non-custodial:
+/// - Funds go ONLY to Player A's ATA (created if needed, paid by the reaper)
+/// - Rent from the closed match, stake, escrow, grow and delivery accounts
+///   returns to Player A
+/// 
+/// grow_state and delivery_state are optional (init_grow_state and
+/// init_delivery_state may never have run) but are closed when supplied.
+/// Practice matches are reaped the same way; they have no escrow, so the
+/// token accounts may be omitted.
+pub fn reap_abandoned(ctx: Context<ReapAbandoned>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let stake_state = &ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    // ========== Invariant Checks ==========
+    
+    stake_state.check_lifecycle(LifecycleAction::Reap)?;
+    
+    require!(
+        stake_state.can_reap(current_ts),
+        DroogError::ReapTooEarly
+    );
+    
+    // ========== Refund Player A ==========
+    // Escrow balance is authoritative - drain it fully so it can be closed
+    // Practice matches have no escrow: nothing to refund or close
+    
+    let mut refund_amount = 0;
+    
+    if !stake_state.practice {
+        let (Some(mint), Some(player_a_token_account), Some(escrow_token_account), Some(escrow_authority)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.player_a_token_account.as_ref(),
+            ctx.accounts.escrow_token_account.as_ref(),
+            ctx.accounts.escrow_authority.as_ref(),
+        ) else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        refund_amount = escrow_token_account.amount;
+        
+        let match_id_hash = stake_state.match_id_hash;
+        let escrow_auth_bump = ctx.bumps.escrow_authority.unwrap_or_default();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            ESCROW_AUTH_SEED,
+            match_id_hash.as_ref(),
+            &[escrow_auth_bump],
+        ]];
+        
+        if refund_amount > 0 {
+            let transfer_accounts = TransferChecked {
+                from: escrow_token_account.to_account_info(),
+                to: player_a_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                authority: escrow_authority.to_account_info(),
+            };
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+            transfer_checked(transfer_ctx, refund_amount, mint.decimals)?;
+        }
+        
+        // ========== Close Escrow ==========
+        let close_accounts = CloseAccount {
+            account: escrow_token_account.to_account_info(),
+            destination: ctx.accounts.player_a.to_account_info(),
+            authority: escrow_authority.to_account_info(),
+        };
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        );
+        close_account(close_ctx)?;
+    }
+    
+    // match_state, stake_state and any grow/delivery state are closed to
+    // Player A by Anchor (close = player_a)
+    
+    emit!(MatchReapedEvent {
+        match_id: stake_state.match_id,
+        player_a: stake_state.player_a,
+        reaper: ctx.accounts.reaper.key(),
+        amount_refunded: refund_amount,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReapAbandoned<'info> {
+    // ========== Game State ==========
+    
+    #[account(
+        mut,
+        close = player_a,
+        seeds = [
+            b"match",
+            stake_state.match_id_hash.as_ref(),
+            stake_state.player_a.as_ref(),
+            stake_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        mut,
+        close = player_a,
+        seeds = [b"stake", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.allows(LifecycleAction::Reap) @ DroogError::MatchNotPending,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        mut,
+        close = player_a,
+        seeds = [b"grow", stake_state.match_id_hash.as_ref()],
+        bump = grow_state.bump,
+    )]
+    pub grow_state: Option<Box<Account<'info, MatchGrowState>>>,
+    
+    #[account(
+        mut,
+        close = player_a,
+        seeds = [b"delivery", stake_state.match_id_hash.as_ref()],
+        bump = delivery_state.bump,
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+    
+    // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
+    
+    /// $PACKS token mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// Player A's $PACKS token account (receives refund, created if needed)
+    #[account(
+        init_if_needed,
+        payer = reaper,
+        associated_token::mint = mint,
+        associated_token::authority = player_a,
+        associated_token::token_program = token_program,
+    )]
+    pub player_a_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow token account (drained and closed)
+    #[account(
+        mut,
+        seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow authority PDA (signs for refund and close)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
+    
+    // ========== Players ==========
+    
+    /// Player A's wallet (receives refund and reclaimed rent)
+    /// CHECK: Validated against stake_state.player_a
+    #[account(
+        mut,
+        constraint = player_a.key() == stake_state.player_a @ DroogError::InvalidPlayer
+    )]
+    pub player_a: UncheckedAccount<'info>,
+    
+    /// Anyone may reap (pays for Player A's ATA if it doesn't exist)
+    #[account(mut)]
+    pub reaper: Signer<'info>,
+    
+    // ========== Programs ==========
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -1,16 +1,23 @@
 use anchor_lang::prelude::*;
-use crate::state::{MatchDeliveryState, MatchState, MAX_DELIVERY_SPOTS};
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::{
+    LifecycleAction, MatchDeliveryState, MatchStakeState, MatchState, MAX_DELIVERY_SPOTS,
+    REFRESH_POOL_SEED, REPLAY_ACTION_REFRESH,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
 use crate::errors::DroogError;
 
 /// Refresh delivery slots after the 60-second rotation interval
 /// 
 /// This instruction can be called by anyone (permissionless) but only succeeds if:
-/// 1. At least 60 seconds have passed since last refresh
-/// 2. The match is still active (not ended, not finalized)
+/// 1. At least 60 seconds have passed since last refresh, and the current
+///    rotation bucket hasn't been processed yet
+/// 2. The match is still active (not ended, not finalized) and its stake is Live
 /// 
 /// The new delivery spots are selected deterministically from:
 /// - match_id
 /// - current timestamp (bucketed to 60s intervals)
+/// - the match's algo_version (fixed at init for replayability)
 /// 
 /// This ensures all clients can independently compute the expected spots
 /// for verification and rendering.
@@ -18,35 +25,87 @@ use crate::errors::DroogError;
 /// Authority: Solana ONLY
 /// - Client calls this to trigger rotation but cannot influence selection
 /// - Selection is purely deterministic from on-chain state
+/// 
+/// Participants who trigger the refresh are credited on MatchState
+/// (with a small reputation bonus when done promptly at the boundary).
+/// 
+/// Incentive: the first refresher of a rotation bucket claims it. A participant
+/// gets the reputation bonus above; a keeper gets KEEPER_REFRESH_REWARD_LAMPORTS
+/// from the match's pre-funded refresh pool, if one is passed and funded.
 pub fn refresh_delivery_slots(ctx: Context<RefreshDeliverySlots>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let delivery_state = &mut ctx.accounts.delivery_state;
-    let match_state = &ctx.accounts.match_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let caller = ctx.accounts.caller.key();
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
     
     // Validate match is active
-    require!(!match_state.is_finalized, DroogError::MatchAlreadyFinalized);
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
     require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
     require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
     
-    // Validate 60 seconds have passed since last refresh
-    require!(
-        delivery_state.needs_refresh(current_ts),
-        DroogError::DeliveryRotationTooSoon
-    );
+    // Rotations only run once both players have committed
+    ctx.accounts.stake_state.check_lifecycle(LifecycleAction::RefreshDelivery)?;
+    
+    // Validate 60 seconds have passed since last refresh and this bucket
+    // hasn't been processed already
+    delivery_state.check_refresh(current_ts)?;
+    
+    // First refresher of this bucket claims the incentive
+    let rotation_bucket = MatchDeliveryState::get_rotation_bucket(current_ts);
+    let incentive = match_state.claim_refresh_incentive(rotation_bucket);
+    
+    // Credit the caller if they're a participant
+    let prompt = delivery_state.is_prompt_refresh(current_ts);
+    let credited_participant = match_state.credit_refresh(caller, prompt && incentive);
+    
+    // Keepers are paid from the refresh pool instead
+    let mut keeper_reward = 0;
+    if incentive && !credited_participant {
+        if let (Some(pool), Some(system_program)) =
+            (&ctx.accounts.refresh_pool, &ctx.accounts.system_program)
+        {
+            let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+            keeper_reward = MatchDeliveryState::keeper_reward_payable(pool.lamports(), rent_exempt_minimum);
+            
+            if keeper_reward > 0 {
+                let match_id_hash = match_state.match_id_hash;
+                let pool_bump = ctx.bumps.refresh_pool.unwrap_or_default();
+                let signer_seeds: &[&[&[u8]]] = &[&[
+                    REFRESH_POOL_SEED,
+                    match_id_hash.as_ref(),
+                    &[pool_bump],
+                ]];
+                let transfer_accounts = Transfer {
+                    from: pool.to_account_info(),
+                    to: ctx.accounts.caller.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    system_program.to_account_info(),
+                    transfer_accounts,
+                    signer_seeds,
+                );
+                transfer(cpi_ctx, keeper_reward)?;
+            }
+        }
+    }
     
     // Cache previous state for event
     let previous_spots = delivery_state.available_customers;
     let previous_count = delivery_state.active_count;
     
-    // Compute new delivery spots using deterministic seed
-    let seed = MatchDeliveryState::compute_delivery_seed(delivery_state.match_id, current_ts);
-    let (new_spots, new_count) = MatchDeliveryState::select_delivery_spots(seed);
-    
-    // Update state
-    delivery_state.available_customers = new_spots;
-    delivery_state.active_count = new_count;
-    delivery_state.last_update_ts = current_ts;
+    // Compute new delivery spots for the current bucket (deterministic seed);
+    // after a multi-interval gap this skips straight past the missed buckets
+    let missed_rotations = delivery_state.rotate(current_ts);
+    delivery_state.rotation_count = delivery_state.rotation_count.saturating_add(1);
+    let new_spots = delivery_state.available_customers;
+    let new_count = delivery_state.active_count;
+    let new_demanded_variants = delivery_state.demanded_variants;
+    let rush_customer = delivery_state.rush_customer;
+    let rush_expires_ts = delivery_state.rush_expires_ts;
     
     // Emit rotation event for auditability and client sync
     emit!(DeliveryRotationEvent {
@@ -55,11 +114,27 @@ pub fn refresh_delivery_slots(ctx: Context<RefreshDeliverySlots>) -> Result<()>
         previous_count,
         new_spots,
         new_count,
-        rotation_bucket: MatchDeliveryState::get_rotation_bucket(current_ts),
+        new_demanded_variants,
+        rush_customer,
+        rush_expires_ts,
+        rotation_bucket,
+        missed_rotations,
         timestamp: current_ts,
         remaining_match_time: match_state.end_ts - current_ts,
+        triggered_by: caller,
+        credited_participant,
+        keeper_reward,
     });
     
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_REFRESH,
+        caller,
+        new_count,
+        rush_customer,
+        0,
+        current_ts,
+    ));
+    
     Ok(())
 }
 
@@ -68,25 +143,54 @@ pub struct RefreshDeliverySlots<'info> {
     /// The delivery state PDA to update
     #[account(
         mut,
-        seeds = [b"delivery", delivery_state.match_id.to_le_bytes().as_ref()],
+        seeds = [b"delivery", delivery_state.match_id_hash.as_ref()],
         bump = delivery_state.bump
     )]
     pub delivery_state: Account<'info, MatchDeliveryState>,
     
-    /// The corresponding match state (for validation)
-    /// CHECK: Only used for validation, not mutated
-    /// Boxed to avoid stack overflow (account is large with 23 customers)
+    /// The corresponding match state (for validation and refresh credit)
     #[account(
+        mut,
         seeds = [
             b"match",
-            match_state.match_id_hash.as_ref(),
-            match_state.player_a.as_ref(),
-            match_state.player_b.as_ref()
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
         ],
-        bump = match_state.bump,
-        constraint = match_state.match_id == delivery_state.match_id @ DroogError::MatchIdMismatch
+        bump = match_state.load()?.bump,
+        constraint = match_state.load()?.match_id == delivery_state.match_id @ DroogError::MatchIdMismatch,
+        constraint = match_state.load()?.match_id_hash == delivery_state.match_id_hash @ DroogError::MatchIdMismatch
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (rotations require stake status == Active)
+    #[account(
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// Whoever triggers the rotation (participant or keeper)
+    /// Mutable so a keeper can receive the refresh reward
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    /// Optional keeper incentive pool (funded via fund_refresh_pool)
+    /// CHECK: System-owned PDA holding only lamports
+    #[account(
+        mut,
+        seeds = [REFRESH_POOL_SEED, match_state.load()?.match_id_hash.as_ref()],
+        bump
+    )]
+    pub refresh_pool: Option<UncheckedAccount<'info>>,
+    
+    /// Required only when refresh_pool is passed
+    pub system_program: Option<Program<'info, System>>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 /// Event emitted when delivery slots rotate
@@ -107,12 +211,26 @@ pub struct DeliveryRotationEvent {
     pub new_spots: [u8; MAX_DELIVERY_SPOTS],
     /// New active count
     pub new_count: u8,
+    /// Variant demanded per new spot (255 = any)
+    pub new_demanded_variants: [u8; MAX_DELIVERY_SPOTS],
+    /// Rush customer for the new bucket (255 = none)
+    pub rush_customer: u8,
+    /// Rush customer expiry timestamp
+    pub rush_expires_ts: i64,
     /// Current rotation bucket (ts / 60)
     pub rotation_bucket: u64,
+    /// Buckets nobody refreshed since the previous rotation (their boards never went live)
+    pub missed_rotations: u64,
     /// Rotation timestamp
     pub timestamp: i64,
     /// Remaining time in match (for pacing analytics)
     pub remaining_match_time: i64,
+    /// Signer who triggered the rotation
+    pub triggered_by: Pubkey,
+    /// Whether the caller was a participant and received refresh credit
+    pub credited_participant: bool,
+    /// Lamports paid to a keeper caller from the refresh pool (0 if none)
+    pub keeper_reward: u64,
 }
 
 #[cfg(test)]
@@ -122,9 +240,10 @@ mod tests {
     #[test]
     fn test_rotation_bucket_consistency() {
         // Verify that rotation bucket is consistent for same 60s window
+        // (1000 and 1019 are both in [960, 1020))
         let ts1 = 1000;
-        let ts2 = 1059;
-        let ts3 = 1060;
+        let ts2 = 1019;
+        let ts3 = 1020;
         
         assert_eq!(
             MatchDeliveryState::get_rotation_bucket(ts1),
@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{
+    MatchState, MatchStakeState, MatchGrowState, MatchDeliveryState, ESCROW_AUTH_SEED,
+    MatchStakeInitializedEvent, RematchCreatedEvent, ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+use crate::utils::escrow_player_a_stake;
+use super::init_delivery_state::DeliveryStateInitializedEvent;
+use super::init_grow_state::GrowStateInitializedEvent;
+
+/// Start a new match for a settled match's player pair in one call
+/// 
+/// The source match must be Finalized or Cancelled. Creates the match, stake,
+/// grow and (unless delivery-less) delivery accounts under a fresh
+/// match_id_hash, for the same player_a/player_b (no re-ordering needed),
+/// and re-escrows Player A's stake like init_match. Carried over from the
+/// source: stake amount, practice/delayed settlement, mercy rule, map layout,
+/// delivery and pacing options, and the starting reputations (handicap).
+/// The new match starts now; Player B joins with join_match_with_stake.
+pub fn rematch(ctx: Context<Rematch>, match_id_hash: [u8; 32]) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let source_match = ctx.accounts.source_match_state.load()?;
+    let source_stake = &ctx.accounts.source_stake_state;
+    source_stake.check_rematch_source()?;
+    
+    // Delivery state exists exactly when the rules use deliveries
+    if source_match.delivery_less() {
+        require!(ctx.accounts.delivery_state.is_none(), DroogError::DeliveryDisabled);
+    } else {
+        require!(ctx.accounts.delivery_state.is_some(), DroogError::DeliveryStateNotInitialized);
+    }
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    let match_id = MatchState::derive_match_id(&match_id_hash);
+    
+    // ========== Match State ==========
+    let mut match_state = ctx.accounts.match_state.load_init()?;
+    match_state.initialize(
+        match_id,
+        match_id_hash,
+        source_match.player_a,
+        source_match.player_b,
+        current_ts,
+        source_match.layer_config,
+        ctx.bumps.match_state,
+    )?;
+    match_state.copy_rules_from(&source_match);
+    
+    // ========== Stake State ==========
+    let stake_amount = source_stake.stake_amount;
+    let stake_state = &mut ctx.accounts.stake_state;
+    stake_state.initialize(
+        &match_state,
+        stake_amount,
+        current_ts,
+        ctx.bumps.stake_state,
+        ctx.bumps.escrow_token_account.unwrap_or_default(),
+    );
+    stake_state.delayed_settlement = source_stake.delayed_settlement;
+    stake_state.practice = source_stake.practice;
+    stake_state.mercy_rule = source_stake.mercy_rule;
+    stake_state.player_b_stake_amount = source_stake.player_b_stake_amount;
+    stake_state.pot_split = source_stake.pot_split;
+    
+    // ========== Grow / Delivery State ==========
+    ctx.accounts.grow_state.initialize(&match_state, ctx.bumps.grow_state);
+    if let Some(delivery_state) = ctx.accounts.delivery_state.as_mut() {
+        delivery_state.initialize(&match_state, ctx.bumps.delivery_state.unwrap_or_default(), current_ts);
+        emit!(DeliveryStateInitializedEvent {
+            match_id,
+            initial_spots: delivery_state.available_customers,
+            active_count: delivery_state.active_count,
+            demanded_variants: delivery_state.demanded_variants,
+            rush_customer: delivery_state.rush_customer,
+            rush_expires_ts: delivery_state.rush_expires_ts,
+            rotation_bucket: MatchDeliveryState::get_rotation_bucket(current_ts),
+            timestamp: current_ts,
+        });
+    }
+    
+    // ========== Re-escrow Player A's Stake (NO BURN) ==========
+    escrow_player_a_stake(
+        stake_state.practice,
+        stake_amount,
+        ctx.accounts.mint.as_ref(),
+        ctx.accounts.player_a_token_account.as_ref(),
+        ctx.accounts.escrow_token_account.as_ref(),
+        ctx.accounts.escrow_authority.as_ref(),
+        &ctx.accounts.player_a,
+        &ctx.accounts.token_program,
+    )?;
+    
+    emit!(GrowStateInitializedEvent {
+        match_id,
+        player_a: match_state.player_a,
+        player_b: match_state.player_b,
+    });
+    
+    emit!(MatchStakeInitializedEvent {
+        match_id,
+        player_a: match_state.player_a,
+        player_b: match_state.player_b,
+        amount_escrowed: stake_state.player_a_escrowed,
+        player_b_stake_amount: stake_state.player_b_stake_amount,
+        timestamp: current_ts,
+    });
+    
+    emit!(RematchCreatedEvent {
+        previous_match_id: source_match.match_id,
+        match_id,
+        player_a: match_state.player_a,
+        player_b: match_state.player_b,
+        stake_amount,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(match_id_hash: [u8; 32])]
+pub struct Rematch<'info> {
+    // ========== Source Match ==========
+    
+    /// The settled match being replayed
+    #[account(
+        seeds = [
+            b"match",
+            source_match_state.load()?.match_id_hash.as_ref(),
+            source_match_state.load()?.player_a.as_ref(),
+            source_match_state.load()?.player_b.as_ref()
+        ],
+        bump = source_match_state.load()?.bump
+    )]
+    pub source_match_state: AccountLoader<'info, MatchState>,
+    
+    /// Source stake state (status must be Finalized or Cancelled)
+    #[account(
+        seeds = [b"stake", source_match_state.load()?.match_id_hash.as_ref()],
+        bump = source_stake_state.bump
+    )]
+    pub source_stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    // ========== New Match PDAs ==========
+    
+    #[account(
+        init,
+        payer = player_a,
+        space = 8 + MatchState::SIZE,
+        seeds = [
+            b"match",
+            match_id_hash.as_ref(),
+            source_match_state.load()?.player_a.as_ref(),
+            source_match_state.load()?.player_b.as_ref()
+        ],
+        bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        init,
+        payer = player_a,
+        space = MatchStakeState::SIZE,
+        seeds = [b"stake", match_id_hash.as_ref()],
+        bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        init,
+        payer = player_a,
+        space = MatchGrowState::SIZE,
+        seeds = [b"grow", match_id_hash.as_ref()],
+        bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// Required unless the source match is delivery-less
+    #[account(
+        init,
+        payer = player_a,
+        space = MatchDeliveryState::SIZE,
+        seeds = [b"delivery", match_id_hash.as_ref()],
+        bump
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+    
+    // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
+    
+    /// $PACKS token mint
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// Player A's $PACKS token account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = player_a,
+    )]
+    pub player_a_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow token account (PDA-controlled)
+    /// Seeds: ["escrow", match_id_hash]
+    #[account(
+        init,
+        payer = player_a,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        seeds = [b"escrow", match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow authority PDA (signs for escrow transfers)
+    /// CHECK: This is a PDA used only as signing authority for escrow
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
+    
+    // ========== Players ==========
+    
+    /// Player A of the source match (escrows again and pays rent)
+    #[account(
+        mut,
+        constraint = player_a.key() == source_match_state.load()?.player_a @ DroogError::InvalidPlayer
+    )]
+    pub player_a: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+    
+    // ========== Programs ==========
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, SLOTS_PER_PLAYER, REPLAY_ACTION_REPLACE,
+    REP_REASON_REPLACE,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Reputation cost for discarding a growing plant
+pub const REPLACE_PLANT_REP_COST: i32 = 1;
+
+/// Atomically swap a growing plant for a different strain
+/// 
+/// This instruction:
+/// 1. Validates the player, match timing, and stake lifecycle
+/// 2. Applies all plant validations to the new strain (endgame lock, will-be-ready)
+/// 3. Clears the Growing plant and plants the new strain (planted_at = now)
+/// 4. Charges REPLACE_PLANT_REP_COST reputation for the waste
+/// 
+/// Unlike harvest (requires Ready), this allows mid-growth replacement.
+pub fn replace_plant(
+    ctx: Context<ReplacePlant>,
+    slot_index: u8,
+    new_strain_level: u8,
+) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    let current_slot = clock.slot;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    match_state.check_start_grace(current_ts)?;
+    
+    // Same validations as a fresh plant
+    MatchGrowState::validate_new_plant(&grow_state.tunables, current_ts, match_state.end_ts, new_strain_level)?;
+    
+    // Validate slot index
+    require!(
+        (slot_index as usize) < SLOTS_PER_PLAYER,
+        DroogError::InvalidSlotIndex
+    );
+    
+    // Determine which player's slots to use
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Higher strain levels may be locked behind reputation
+    match_state.check_strain_unlocked(is_player_a, new_strain_level)?;
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
+    // Variant is weighted by reputation before the replacement cost is paid
+    let match_id = grow_state.match_id;
+    let variant_id = MatchGrowState::compute_variant_id(
+        grow_state.algo_version,
+        match_id,
+        &player,
+        slot_index,
+        current_slot,
+        match_state.reputation_of(is_player_a),
+    );
+    let growth_jitter = grow_state.next_growth_jitter(is_player_a, slot_index, new_strain_level);
+    
+    let tunables = grow_state.tunables;
+    let slots = if is_player_a {
+        &mut grow_state.player_a_slots
+    } else {
+        &mut grow_state.player_b_slots
+    };
+    
+    let old_strain_level = slots[slot_index as usize]
+        .replace_growing(&tunables, new_strain_level, variant_id, growth_jitter, current_ts)?;
+    
+    // Charge the waste (clamped like every other reputation change)
+    match_state.adjust_reputation(is_player_a, -REPLACE_PLANT_REP_COST, REP_REASON_REPLACE);
+    
+    emit!(PlantReplacedEvent {
+        match_id,
+        player,
+        slot_index,
+        old_strain_level,
+        new_strain_level,
+        variant_id,
+        reputation_cost: REPLACE_PLANT_REP_COST,
+        replaced_ts: current_ts,
+    });
+    
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_REPLACE,
+        player,
+        slot_index,
+        new_strain_level,
+        variant_id,
+        current_ts,
+    ));
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReplacePlant<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (timing validation and reputation cost)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player replacing the plant
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct PlantReplacedEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub slot_index: u8,
+    pub old_strain_level: u8,
+    pub new_strain_level: u8,
+    pub variant_id: u8,
+    pub reputation_cost: i32,
+    pub replaced_ts: i64,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::{MatchState, MatchStakeState, LifecycleAction, DISPUTE_ADMIN, DisputeResolvedEvent};
+use crate::errors::DroogError;
+
+/// Resolve a frozen delayed settlement in favour of one player
+/// 
+/// Requirements:
+/// 1. Signer is DISPUTE_ADMIN
+/// 2. Stake status == Settling and a player froze the settlement
+/// 3. winner is Player A or Player B
+/// 
+/// Nothing moves here: the settlement is unfrozen with `winner` as its
+/// recipient and becomes executable at once, so execute_settlement pays the
+/// pot as usual. Overturning re-splits the shares for the new loser, and a
+/// drawn settlement can't be resolved to one player. Use reverse_finalization
+/// instead to refund both players.
+pub fn resolve_dispute(ctx: Context<ResolveDispute>, winner: Pubkey) -> Result<()> {
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let recorded_winner = stake_state.pending_settlement.winner;
+    stake_state.resolve_dispute(winner, &match_state, current_ts)?;
+    match_state.revise_result(Some(winner), stake_state.pending_settlement.amount);
+    
+    emit!(DisputeResolvedEvent {
+        match_id: stake_state.match_id,
+        admin: ctx.accounts.admin.key(),
+        recorded_winner,
+        winner,
+        amount: stake_state.pending_settlement.amount,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// Match the settlement is for (sales and reputation for the re-split;
+    /// its result snapshot names the resolved winner)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.allows(LifecycleAction::ResolveDispute) @ DroogError::SettlementNotPending,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        constraint = admin.key() == DISPUTE_ADMIN @ DroogError::NotDisputeAdmin
+    )]
+    pub admin: Signer<'info>,
+}
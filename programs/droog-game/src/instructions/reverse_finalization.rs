@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use crate::state::{
+    MatchState, MatchStakeState, LifecycleAction, ESCROW_AUTH_SEED, DISPUTE_ADMIN, FinalizationReversedEvent,
+    SettlementRecord, SETTLEMENT_RECORD_SEED,
+};
+use crate::errors::DroogError;
+
+/// Void a delayed settlement and refund both players
+/// 
+/// Requirements:
+/// 1. Signer is DISPUTE_ADMIN
+/// 2. Stake status == Settling (immediate settlements are irreversible)
+/// 3. Challenge window still open, or the settlement was frozen by a player
+/// 
+/// The escrow is refunded in proportion to what each player escrowed
+/// (rounding remainder to Player A). The burn that
+/// happened at activation is not restored. Status becomes Disputed, the
+/// SettlementRecord is rewritten with the refunds, and MatchState no longer
+/// reports a winner.
+pub fn reverse_finalization(ctx: Context<ReverseFinalization>) -> Result<()> {
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    stake_state.reverse_finalization(current_ts)?;
+    
+    // Escrow balance is authoritative
+    let (player_a_refund, player_b_refund) =
+        stake_state.dispute_refund_split(ctx.accounts.escrow_token_account.amount);
+    
+    let match_id_hash = stake_state.match_id_hash;
+    let escrow_auth_bump = ctx.bumps.escrow_authority;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ESCROW_AUTH_SEED,
+        match_id_hash.as_ref(),
+        &[escrow_auth_bump],
+    ]];
+    
+    for (destination, amount) in [
+        (&ctx.accounts.player_a_token_account, player_a_refund),
+        (&ctx.accounts.player_b_token_account, player_b_refund),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: destination.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.escrow_authority.to_account_info(),
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_ctx, amount, ctx.accounts.mint.decimals)?;
+    }
+    
+    ctx.accounts.settlement_record.record_reversal(stake_state, player_a_refund, player_b_refund, current_ts);
+    ctx.accounts.match_state.load_mut()?.revise_result(None, 0);
+    
+    emit!(FinalizationReversedEvent {
+        match_id: stake_state.match_id,
+        admin: ctx.accounts.admin.key(),
+        voided_winner: stake_state.pending_settlement.winner,
+        player_a_refund,
+        player_b_refund,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReverseFinalization<'info> {
+    /// Result snapshot cleared: the voided match has no winner
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+        constraint = stake_state.allows(LifecycleAction::ReverseFinalization) @ DroogError::SettlementNotPending,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// Settlement history PDA written by finalize_match
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_RECORD_SEED, stake_state.match_id_hash.as_ref()],
+        bump = settlement_record.bump,
+    )]
+    pub settlement_record: Box<Account<'info, SettlementRecord>>,
+    
+    /// $PACKS token mint
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Escrow token account
+    #[account(
+        mut,
+        seeds = [b"escrow", stake_state.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Escrow authority PDA (signs for refund transfers)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, stake_state.match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+    
+    /// Player A's token account (receives Player A's share of the refund)
+    #[account(
+        mut,
+        constraint = player_a_token_account.owner == stake_state.player_a @ DroogError::InvalidPlayer
+    )]
+    pub player_a_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Player B's token account (receives Player B's share of the refund)
+    #[account(
+        mut,
+        constraint = player_b_token_account.owner == stake_state.player_b @ DroogError::InvalidPlayer
+    )]
+    pub player_b_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        constraint = admin.key() == DISPUTE_ADMIN @ DroogError::NotDisputeAdmin
+    )]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
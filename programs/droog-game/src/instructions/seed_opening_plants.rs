@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, OPENING_PLANT_SLOT, OPENING_PLANT_STRAIN_LEVEL,
+    REPLAY_ACTION_PLANT,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::instructions::PlantStrainEvent;
+use crate::errors::DroogError;
+
+/// Plant both players' opening plants in one instruction
+/// 
+/// Symmetric match-start ritual for modes that give each player a free
+/// opening plant: a Level 1 strain goes into slot 0 for player A and B at the
+/// same timestamp.
+/// 
+/// - Both players must sign
+/// - Same validations as plant_strain (active stake, match running, endgame
+///   lock, strain/timing, empty slot)
+/// - Runs once per match (guarded by grow_state.opening_plants_seeded)
+/// - Exempt from the start grace: it is symmetric, so nobody gets a head start
+/// 
+/// Emits a PlantStrainEvent and a replay frame per player.
+pub fn seed_opening_plants(ctx: Context<SeedOpeningPlants>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    let current_slot = clock.slot;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    MatchGrowState::validate_new_plant(&grow_state.tunables, current_ts, match_state.end_ts, OPENING_PLANT_STRAIN_LEVEL)?;
+    
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(true, current_ts);
+    grow_state.accrue_smell(false, current_ts);
+    
+    let match_id = grow_state.match_id;
+    let variant_for = |player: &Pubkey, is_player_a: bool| {
+        MatchGrowState::compute_variant_id(
+            grow_state.algo_version,
+            match_id,
+            player,
+            OPENING_PLANT_SLOT,
+            current_slot,
+            match_state.reputation_of(is_player_a),
+        )
+    };
+    let variant_a = variant_for(&grow_state.player_a, true);
+    let variant_b = variant_for(&grow_state.player_b, false);
+    
+    grow_state.seed_opening_plants(variant_a, variant_b, current_ts)?;
+    
+    for (player, variant_id) in [(grow_state.player_a, variant_a), (grow_state.player_b, variant_b)] {
+        emit!(PlantStrainEvent {
+            match_id,
+            player,
+            slot_index: OPENING_PLANT_SLOT,
+            strain_level: OPENING_PLANT_STRAIN_LEVEL,
+            variant_id,
+            planted_ts: current_ts,
+        });
+        
+        emit!(match_state.next_replay_frame(
+            REPLAY_ACTION_PLANT,
+            player,
+            OPENING_PLANT_SLOT,
+            OPENING_PLANT_STRAIN_LEVEL,
+            variant_id,
+            current_ts,
+        ));
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SeedOpeningPlants<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (for timing validation and replay seq)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(constraint = player_a.key() == grow_state.player_a @ DroogError::InvalidPlayer)]
+    pub player_a: Signer<'info>,
+    
+    #[account(constraint = player_b.key() == grow_state.player_b @ DroogError::InvalidPlayer)]
+    pub player_b: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
-use crate::state::{MatchState, MatchGrowState, MatchDeliveryState};
+use crate::state::{
+    MatchState, MatchGrowState, MatchDeliveryState, MatchStakeState, RUSH_REP_BONUS,
+    REPLAY_ACTION_SELL,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
 use crate::errors::DroogError;
+use crate::instructions::harvest_strain::{emit_capacity_transition, emit_spoilage};
 
 /// Sell a strain to a customer
 /// 
@@ -8,25 +13,48 @@ use crate::errors::DroogError;
 /// 1. Validates customer availability and strain compatibility (existing)
 /// 2. Burns one item from the player's inventory (new)
 /// 3. Applies variant reputation modifier (new)
-/// 4. Updates player stats (existing)
+/// 4. Applies the customer's variant demand bonus/penalty, if any
+///    and the rush bonus if a rush customer was served inside its window
+/// 5. Steals a share of the opponent's reputation if they served this customer recently
+/// 6. Updates player stats (existing)
+/// 
+/// Fog of war (hide_live_scores at init): state updates as usual, but instead
+/// of SaleEvent only a SaleOccurredEvent (no player, no reputation) is
+/// emitted, the opponent/mercy-rule events are suppressed, and the replay
+/// frame is anonymized. MatchFinalizedEvent reveals the totals at the end.
+/// 
+/// Stale boards: the current board stays authoritative until a refresh
+/// actually replaces it, even past its nominal rotation time (nobody
+/// refreshed, or no refresh can land this late in the match). SaleEvent
+/// records the bucket the served board was selected for.
+/// 
+/// Delivery-less matches (flag at init) skip the delivery availability check,
+/// variant demand, and rush entirely; customers are gated by cooldowns only.
+/// delivery_state may be omitted for them.
 pub fn sell_to_customer(
     ctx: Context<SellToCustomer>,
     customer_index: u8,
     strain_level: u8,
 ) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
     let clock = Clock::get()?;
     let current_ts = clock.unix_timestamp;
-    let match_state = &mut ctx.accounts.match_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
     let grow_state = &mut ctx.accounts.grow_state;
-    let delivery_state = &mut ctx.accounts.delivery_state;
     let player = ctx.accounts.player.key();
     
     // Prevent state changes after finalization
-    require!(!match_state.is_finalized, DroogError::MatchAlreadyFinalized);
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
     
     // Validate match is active
     require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
     require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    match_state.check_start_grace(current_ts)?;
     
     // Validate customer index (0-22)
     require!(customer_index < 23, DroogError::InvalidCustomerIndex);
@@ -42,20 +70,39 @@ pub fn sell_to_customer(
     let is_player_b = player == match_state.player_b;
     require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
     
+    // Integrate smell exposure before this instruction changes the slots
+    grow_state.accrue_smell(is_player_a, current_ts);
+    
+    // Optional per-bucket sales cap (no end-game dumping)
+    match_state.record_sale_for_rate_cap(is_player_a, current_ts)?;
+    
     // ========== DELIVERY AVAILABILITY VALIDATION ==========
     // Solana is the ABSOLUTE AUTHORITY on which customers are available.
     // Client cannot override or bypass this check.
-    require!(
-        delivery_state.is_customer_available(customer_index),
-        DroogError::CustomerNotAvailableForDelivery
-    );
+    // A rush customer is servable only inside its short window.
+    let use_delivery = match_state.check_delivery_access(ctx.accounts.delivery_state.is_some())?;
+    let mut delivery_state = if use_delivery {
+        ctx.accounts.delivery_state.as_mut()
+    } else {
+        None
+    };
+    let is_rush = delivery_state
+        .as_ref()
+        .map(|d| d.is_rush_active(customer_index, current_ts))
+        .unwrap_or(false);
+    if let Some(delivery) = delivery_state.as_ref() {
+        require!(
+            delivery.is_customer_available(customer_index) || is_rush,
+            DroogError::CustomerNotAvailableForDelivery
+        );
+    }
     
     // DERIVE layer from customer_index (authoritative - never stored)
-    let customer_layer = MatchState::layer_from_index(customer_index);
+    let customer_layer = match_state.customer_layer(customer_index);
     
-    // Check customer cooldown BEFORE getting mutable reference
+    // Check the acting player's cooldown BEFORE getting mutable reference
     require!(
-        match_state.is_customer_available(customer_index as usize, current_ts),
+        match_state.is_customer_available(customer_index as usize, is_player_a, current_ts),
         DroogError::CustomerOnCooldown
     );
     
@@ -68,23 +115,23 @@ pub fn sell_to_customer(
     // Cache values from grow_state before mutable borrows
     let match_id = match_state.match_id;
     
-    // Clone slots for read-only access (finding variant)
-    let slots_snapshot = if is_player_a {
-        grow_state.player_a_slots.clone()
-    } else {
-        grow_state.player_b_slots.clone()
-    };
-    
     // Find variant for this sale (most recently harvested matching strain level)
-    let variant_id = MatchGrowState::find_variant_for_sale(&slots_snapshot, strain_level);
+    // The shared borrow of the slots ends here, before the inventory is borrowed mutably
+    let variant_id = grow_state.variant_for_sale(is_player_a, strain_level);
     
     // Get player's inventory from grow state
+    let capacity = grow_state.inventory_capacity(is_player_a, current_ts);
     let inventory = if is_player_a {
         &mut grow_state.player_a_inventory
     } else {
         &mut grow_state.player_b_inventory
     };
     
+    // Lazily spoil stale stacks before checking stock
+    let was_full = !inventory.has_space(capacity);
+    let spoiled = inventory.spoil_expired(current_ts);
+    emit_spoilage(match_id, player, spoiled, current_ts);
+    
     // Validate player has inventory to sell
     require!(
         inventory.has(strain_level),
@@ -95,6 +142,14 @@ pub fn sell_to_customer(
     let burned = inventory.decrement(strain_level);
     require!(burned, DroogError::InsufficientInventory);
     let remaining_inventory = inventory.get(strain_level);
+    emit_capacity_transition(
+        match_id,
+        player,
+        was_full,
+        !inventory.has_space(capacity),
+        inventory.total(),
+        current_ts,
+    );
     
     // Calculate base reputation change using derived layer
     let base_reputation_change = MatchState::get_reputation_change(customer_layer, strain_level);
@@ -104,79 +159,162 @@ pub fn sell_to_customer(
         .map(|v| MatchGrowState::get_variant_rep_bonus(v))
         .unwrap_or(0);
     
-    let total_reputation_change = base_reputation_change.saturating_add(variant_bonus);
+    // Apply variant demand modifier (exact match bonus / wrong variant penalty)
+    let demanded_variant = delivery_state.as_ref().and_then(|d| d.demanded_variant(customer_index));
+    let variant_demand_bonus = MatchDeliveryState::variant_demand_modifier(demanded_variant, variant_id);
+    
+    // Rush customers pay out big when served in time
+    let rush_bonus = if is_rush { RUSH_REP_BONUS } else { 0 };
+    
+    let total_reputation_change = base_reputation_change
+        .saturating_add(variant_bonus)
+        .saturating_add(variant_demand_bonus)
+        .saturating_add(rush_bonus);
+    
+    // Contested serve: take a cut of what the opponent earned here (before overwriting last_served_*)
+    let stolen_reputation = match_state.steal_reputation(customer_index as usize, player, current_ts);
     
     // Get customer and update state
     let customer = &mut match_state.customers[customer_index as usize];
     customer.last_served_ts = current_ts;
     customer.total_serves += 1;
-    customer.last_served_by = Some(player);
+    customer.set_last_served_by(player);
+    customer.last_rep_earned = total_reputation_change;
+    if is_player_a {
+        customer.player_a_last_served_ts = current_ts;
+    } else {
+        customer.player_b_last_served_ts = current_ts;
+    }
     
     // Update player stats
     if is_player_a {
         match_state.player_a_sales += 1;
-        // Clamp reputation to prevent overflow/underflow
-        match_state.player_a_reputation = MatchState::clamp_reputation(
-            match_state.player_a_reputation.saturating_add(total_reputation_change)
-        );
     } else {
         match_state.player_b_sales += 1;
-        // Clamp reputation to prevent overflow/underflow
-        match_state.player_b_reputation = MatchState::clamp_reputation(
-            match_state.player_b_reputation.saturating_add(total_reputation_change)
-        );
+    }
+    match_state.mark_customer_served(is_player_a, customer_index);
+    let momentum = match_state.record_sale_momentum(is_player_a, current_ts);
+    // Clamped, and logged with its modifiers when the ledger is enabled
+    let rep_reasons = MatchState::sale_rep_reasons(variant_bonus, variant_demand_bonus, rush_bonus);
+    match_state.adjust_reputation(is_player_a, total_reputation_change, rep_reasons);
+    
+    // Mercy rule: a blowout can be finalized before end_ts
+    if !match_state.early_end_eligible()
+        && ctx.accounts.stake_state.mercy_rule.is_triggered(match_state.player_a_sales, match_state.player_b_sales)
+    {
+        match_state.early_end_eligible = 1;
+        if match_state.reveals_live_scores() {
+            emit!(MercyRuleTriggeredEvent {
+                match_id,
+                leader: player,
+                player_a_sales: match_state.player_a_sales,
+                player_b_sales: match_state.player_b_sales,
+                timestamp: current_ts,
+            });
+        }
     }
     
-    // Get delivery rotation bucket for event
+    // Get delivery rotation bucket for event, and the bucket of the board
+    // actually served (earlier when it was stale)
     let rotation_bucket = MatchDeliveryState::get_rotation_bucket(current_ts);
+    let board_bucket = delivery_state
+        .as_ref()
+        .map(|d| d.last_processed_bucket)
+        .unwrap_or(rotation_bucket);
     
     // ========== REMOVE CUSTOMER FROM AVAILABILITY ==========
     // Each customer can only be delivered to ONCE per rotation cycle.
     // This creates competition between players for available delivery spots.
     // The customer will become available again on the next rotation refresh.
-    delivery_state.remove_customer(customer_index);
+    if let Some(delivery) = delivery_state.as_mut() {
+        if is_rush {
+            delivery.clear_rush();
+        } else {
+            delivery.remove_customer(customer_index);
+        }
+    }
     
-    // Emit enhanced sale event for auditability
-    emit!(SaleEvent {
-        match_id,
+    if match_state.reveals_live_scores() {
+        // Emit enhanced sale event for auditability
+        emit!(SaleEvent {
+            match_id,
+            customer_index,
+            customer_layer,      // Derived layer for analytics
+            strain_level,
+            variant_id: variant_id.unwrap_or(0),
+            player,
+            base_reputation_delta: base_reputation_change,
+            variant_bonus,
+            variant_demand_bonus,
+            rush_bonus,
+            total_reputation_delta: total_reputation_change,
+            stolen_reputation,
+            timestamp: current_ts,
+            remaining_inventory,
+            rotation_bucket,     // Delivery rotation context for replay
+            board_bucket,
+            momentum,
+        });
+    } else {
+        // Fog of war: announce the sale without attributing it
+        emit!(SaleOccurredEvent {
+            match_id,
+            customer_index,
+            timestamp: current_ts,
+            rotation_bucket,
+        });
+    }
+    
+    // Let the opponent's client react to big moves
+    if let Some(event) = match_state.significant_sale_event(
+        player,
         customer_index,
-        customer_layer,      // Derived layer for analytics
         strain_level,
-        variant_id: variant_id.unwrap_or(0),
-        player,
-        base_reputation_delta: base_reputation_change,
-        variant_bonus,
-        total_reputation_delta: total_reputation_change,
-        timestamp: current_ts,
-        remaining_inventory,
-        rotation_bucket,     // Delivery rotation context for replay
-    });
+        total_reputation_change,
+        current_ts,
+    ) {
+        emit!(event);
+    }
+    
+    // Warn clients when the player's smell climbs into a higher band
+    if let Some(event) = grow_state.update_smell_band(is_player_a, current_ts) {
+        emit!(event);
+    }
+    
+    let replay_actor = match_state.sale_replay_actor(player);
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_SELL,
+        replay_actor,
+        customer_index,
+        strain_level,
+        variant_id.unwrap_or(0),
+        current_ts,
+    ));
     
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct SellToCustomer<'info> {
-    /// Boxed to avoid stack overflow (account is large with 23 customers)
     #[account(
         mut,
         seeds = [
             b"match",
-            match_state.match_id_hash.as_ref(),
-            match_state.player_a.as_ref(),
-            match_state.player_b.as_ref()
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
         ],
-        bump = match_state.bump
+        bump = match_state.load()?.bump
     )]
-    pub match_state: Box<Account<'info, MatchState>>,
+    pub match_state: AccountLoader<'info, MatchState>,
     
     /// The grow state PDA (for inventory management)
-    /// Boxed to avoid stack overflow (account is ~359 bytes)
+    /// Boxed to avoid stack overflow (account is ~783 bytes)
     #[account(
         mut,
-        seeds = [b"grow", grow_state.match_id.to_le_bytes().as_ref()],
+        seeds = [b"grow", match_state.load()?.match_id_hash.as_ref()],
         bump = grow_state.bump,
-        constraint = grow_state.match_id == match_state.match_id @ DroogError::MatchIdMismatch
+        constraint = grow_state.belongs_to(&*match_state.load()?) @ DroogError::MatchIdMismatch
     )]
     pub grow_state: Box<Account<'info, MatchGrowState>>,
     
@@ -185,15 +323,28 @@ pub struct SellToCustomer<'info> {
     /// Client CANNOT influence this - only render indicators based on this state.
     /// NOTE: Mutable because we remove the customer from availability after sale.
     /// Each customer can only be delivered to ONCE per rotation cycle.
+    /// Optional: required unless the match was created delivery-less.
     #[account(
         mut,
-        seeds = [b"delivery", match_state.match_id.to_le_bytes().as_ref()],
+        seeds = [b"delivery", match_state.load()?.match_id_hash.as_ref()],
         bump = delivery_state.bump,
-        constraint = delivery_state.match_id == match_state.match_id @ DroogError::MatchIdMismatch
+        constraint = delivery_state.match_id_hash == match_state.load()?.match_id_hash @ DroogError::MatchIdMismatch
     )]
-    pub delivery_state: Account<'info, MatchDeliveryState>,
+    pub delivery_state: Option<Account<'info, MatchDeliveryState>>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
     
     pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
 }
 
 /// Enhanced sale event for auditability and analytics.
@@ -216,8 +367,14 @@ pub struct SaleEvent {
     pub base_reputation_delta: i32,
     /// Variant reputation bonus/penalty (-1, 0, or +1)
     pub variant_bonus: i32,
-    /// Total reputation change (base + variant)
+    /// Variant demand bonus/penalty (0 if the customer had no demand)
+    pub variant_demand_bonus: i32,
+    /// Rush customer bonus (0 unless a rush customer was served in time)
+    pub rush_bonus: i32,
+    /// Total reputation change (base + variant + demand + rush)
     pub total_reputation_delta: i32,
+    /// Reputation transferred from the opponent on a contested serve (0 if not a steal)
+    pub stolen_reputation: i32,
     /// On-chain timestamp when sale was recorded
     pub timestamp: i64,
     /// Remaining inventory of this strain level after sale
@@ -225,4 +382,31 @@ pub struct SaleEvent {
     /// Delivery rotation bucket (ts / 60) for replay verification
     /// Allows post-match audit to verify customer was legitimately available
     pub rotation_bucket: u64,
+    /// Bucket the served board was selected for: below rotation_bucket when
+    /// the sale hit a stale (overdue, unrefreshed) board; equal to it in
+    /// delivery-less matches. Audits should check availability against this.
+    pub board_bucket: u64,
+    /// Seller's momentum after this sale (decays with inactivity; see MOMENTUM_MAX)
+    pub momentum: i32,
+}
+
+/// Score-free stand-in for SaleEvent in hide_live_scores matches
+/// The board change is public anyway; who sold and what it earned is not
+#[event]
+pub struct SaleOccurredEvent {
+    pub match_id: u64,
+    pub customer_index: u8,
+    pub timestamp: i64,
+    pub rotation_bucket: u64,
+}
+
+/// Emitted once when a sale pushes the match past its mercy rule threshold
+/// Finalize may run from this point on, before end_ts
+#[event]
+pub struct MercyRuleTriggeredEvent {
+    pub match_id: u64,
+    pub leader: Pubkey,
+    pub player_a_sales: u32,
+    pub player_b_sales: u32,
+    pub timestamp: i64,
 }
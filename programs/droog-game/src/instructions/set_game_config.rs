@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::state::{GameConfig, GameConfigUpdatedEvent, GameTunables, GAME_CONFIG_SEED};
+
+/// Set the balance tunables new matches snapshot (config authority only)
+/// 
+/// Creates the GameConfig PDA on first use; only the dispute admin may do
+/// that, becoming its first authority. The current authority may hand over
+/// control via new_authority. Matches already created keep their snapshot.
+pub fn set_game_config(
+    ctx: Context<SetGameConfig>,
+    tunables: GameTunables,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.game_config;
+    let signer = ctx.accounts.authority.key();
+    
+    config.check_authority(signer)?;
+    tunables.validate()?;
+    
+    config.authority = new_authority.unwrap_or(signer);
+    config.tunables = tunables;
+    config.updated_at = clock.unix_timestamp;
+    config.bump = ctx.bumps.game_config;
+    
+    emit!(GameConfigUpdatedEvent {
+        authority: config.authority,
+        tunables,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetGameConfig<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = GameConfig::SIZE,
+        seeds = [GAME_CONFIG_SEED],
+        bump
+    )]
+    pub game_config: Account<'info, GameConfig>,
+    
+    /// The config authority (the dispute admin when creating the config)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProgramConfig, ProgramPauseChangedEvent, DISPUTE_ADMIN, PROGRAM_CONFIG_SEED};
+use crate::errors::DroogError;
+
+/// Set or clear the program-wide kill-switch (dispute admin only)
+/// 
+/// For use during a discovered vulnerability: while paused, mutating
+/// instructions reject with ProgramPaused. Creates the Config PDA on first use.
+pub fn set_program_paused(ctx: Context<SetProgramPaused>, paused: bool) -> Result<()> {
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.program_config;
+    
+    config.paused = paused;
+    config.updated_at = clock.unix_timestamp;
+    config.bump = ctx.bumps.program_config;
+    
+    emit!(ProgramPauseChangedEvent {
+        paused,
+        admin: ctx.accounts.admin.key(),
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetProgramPaused<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ProgramConfig::SIZE,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    #[account(
+        mut,
+        constraint = admin.key() == DISPUTE_ADMIN @ DroogError::NotDisputeAdmin
+    )]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
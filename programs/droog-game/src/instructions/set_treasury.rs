@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProgramConfig, TreasuryChangedEvent, DISPUTE_ADMIN, PROGRAM_CONFIG_SEED};
+use crate::errors::DroogError;
+
+/// Name the owner of the treasury's token account (dispute admin only)
+/// 
+/// Settlements take the treasury rake only once a treasury is named, and pay
+/// it to a token account this key owns. Creates the Config PDA on first use;
+/// Pubkey::default() turns the rake off again.
+pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.program_config;
+    
+    config.treasury = treasury;
+    config.updated_at = clock.unix_timestamp;
+    config.bump = ctx.bumps.program_config;
+    
+    emit!(TreasuryChangedEvent {
+        treasury,
+        admin: ctx.accounts.admin.key(),
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ProgramConfig::SIZE,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    
+    #[account(
+        mut,
+        constraint = admin.key() == DISPUTE_ADMIN @ DroogError::NotDisputeAdmin
+    )]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,409 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{
+    MatchState, MatchStakeState, MatchGrowState, MatchDeliveryState, ESCROW_AUTH_SEED,
+    MatchStakeInitializedEvent, LayerConfig, MercyRule, StrainUnlock, PotSplit,
+    ProgramConfig, PROGRAM_CONFIG_SEED, GameConfig, GAME_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+use crate::utils::escrow_player_a_stake;
+use super::init_delivery_state::DeliveryStateInitializedEvent;
+use super::init_grow_state::GrowStateInitializedEvent;
+use super::init_match::NewMatchArgs;
+
+/// Create a whole match in one atomic call
+/// 
+/// Does what init_match, init_grow_state and init_delivery_state do in
+/// sequence - match, stake, grow and (unless delivery_less) delivery
+/// accounts plus Player A's escrow transfer - so a failure can never leave a
+/// half-created match. Takes exactly init_match's arguments and validations.
+/// The individual instructions remain available.
+#[allow(clippy::too_many_arguments)]
+pub fn setup_match(
+    ctx: Context<SetupMatch>,
+    match_id_hash: [u8; 32],
+    match_id: Option<u64>,
+    start_ts: i64,
+    player_a_rep_start: i32,
+    player_b_rep_start: i32,
+    delayed_settlement: bool,
+    layer_config: Option<LayerConfig>,
+    delivery_less: bool,
+    rep_ledger: bool,
+    practice: bool,
+    mercy_rule: Option<MercyRule>,
+    extra_delivery_spots: Option<u8>,
+    start_grace: bool,
+    hide_live_scores: bool,
+    stake_amount: u64,
+    max_sales_per_bucket: Option<u8>,
+    strain_unlock: Option<StrainUnlock>,
+    player_b_stake_amount: Option<u64>,
+    pot_split: Option<PotSplit>,
+    shared_land: bool,
+) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    let player_a = ctx.accounts.player_a.key();
+    let player_b = ctx.accounts.player_b.key();
+    
+    let args = NewMatchArgs {
+        match_id_hash,
+        match_id,
+        start_ts,
+        player_a,
+        player_b,
+        player_a_rep_start,
+        player_b_rep_start,
+        delayed_settlement,
+        layer_config,
+        delivery_less,
+        rep_ledger,
+        practice,
+        mercy_rule,
+        extra_delivery_spots,
+        start_grace,
+        hide_live_scores,
+        stake_amount,
+        max_sales_per_bucket,
+        strain_unlock,
+        player_b_stake_amount,
+        pot_split,
+        shared_land,
+        tunables: GameConfig::load_tunables(&ctx.accounts.game_config)?,
+    };
+    initialize_match_accounts(
+        args,
+        &mut *ctx.accounts.match_state.load_init()?,
+        &mut ctx.accounts.stake_state,
+        &mut ctx.accounts.grow_state,
+        ctx.accounts.delivery_state.as_deref_mut().map(|d| &mut **d),
+        &ctx.bumps,
+        current_ts,
+    )?;
+    
+    let match_state = ctx.accounts.match_state.load()?;
+    let stake_state = &ctx.accounts.stake_state;
+    let stake_amount = stake_state.stake_amount;
+    
+    // ========== Transfer Player A's Stake to Escrow (NO BURN) ==========
+    // Option C: 100% goes to escrow, burn happens only when Player B joins
+    escrow_player_a_stake(
+        stake_state.practice,
+        stake_amount,
+        ctx.accounts.mint.as_ref(),
+        ctx.accounts.player_a_token_account.as_ref(),
+        ctx.accounts.escrow_token_account.as_ref(),
+        ctx.accounts.escrow_authority.as_ref(),
+        &ctx.accounts.player_a,
+        &ctx.accounts.token_program,
+    )?;
+    
+    // Same events as the three separate instructions
+    emit!(MatchStakeInitializedEvent {
+        match_id: match_state.match_id,
+        player_a,
+        player_b,
+        amount_escrowed: stake_state.player_a_escrowed,
+        player_b_stake_amount: stake_state.player_b_stake_amount,
+        timestamp: current_ts,
+    });
+    
+    emit!(GrowStateInitializedEvent {
+        match_id: match_state.match_id,
+        player_a,
+        player_b,
+    });
+    
+    if let Some(delivery_state) = ctx.accounts.delivery_state.as_ref() {
+        emit!(DeliveryStateInitializedEvent {
+            match_id: match_state.match_id,
+            initial_spots: delivery_state.available_customers,
+            active_count: delivery_state.active_count,
+            demanded_variants: delivery_state.demanded_variants,
+            rush_customer: delivery_state.rush_customer,
+            rush_expires_ts: delivery_state.rush_expires_ts,
+            rotation_bucket: MatchDeliveryState::get_rotation_bucket(current_ts),
+            timestamp: current_ts,
+        });
+    }
+    
+    Ok(())
+}
+
+/// Set up every state account of a new match (setup_match minus the escrow
+/// transfer). The delivery account must be present exactly when the rules
+/// use deliveries.
+pub(crate) fn initialize_match_accounts(
+    args: NewMatchArgs,
+    match_state: &mut MatchState,
+    stake_state: &mut MatchStakeState,
+    grow_state: &mut MatchGrowState,
+    delivery_state: Option<&mut MatchDeliveryState>,
+    bumps: &SetupMatchBumps,
+    current_ts: i64,
+) -> Result<()> {
+    if args.delivery_less {
+        require!(delivery_state.is_none(), DroogError::DeliveryDisabled);
+    } else {
+        require!(delivery_state.is_some(), DroogError::DeliveryStateNotInitialized);
+    }
+    
+    args.initialize(
+        match_state,
+        stake_state,
+        bumps.match_state,
+        bumps.stake_state,
+        bumps.escrow_token_account.unwrap_or_default(),
+        current_ts,
+    )?;
+    grow_state.initialize(match_state, bumps.grow_state);
+    if let Some(delivery_state) = delivery_state {
+        delivery_state.initialize(match_state, bumps.delivery_state.unwrap_or_default(), current_ts);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(match_id_hash: [u8; 32])]
+pub struct SetupMatch<'info> {
+    // ========== Game State PDAs ==========
+    
+    #[account(
+        init,
+        payer = player_a,
+        space = 8 + MatchState::SIZE,
+        seeds = [
+            b"match",
+            match_id_hash.as_ref(),
+            player_a.key().as_ref(),
+            player_b.key().as_ref()
+        ],
+        bump,
+        constraint = player_a.key() < player_b.key() @ DroogError::InvalidPlayerOrder
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        init,
+        payer = player_a,
+        space = MatchStakeState::SIZE,
+        seeds = [b"stake", match_id_hash.as_ref()],
+        bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    #[account(
+        init,
+        payer = player_a,
+        space = MatchGrowState::SIZE,
+        seeds = [b"grow", match_id_hash.as_ref()],
+        bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// Required unless delivery_less
+    #[account(
+        init,
+        payer = player_a,
+        space = MatchDeliveryState::SIZE,
+        seeds = [b"delivery", match_id_hash.as_ref()],
+        bump
+    )]
+    pub delivery_state: Option<Box<Account<'info, MatchDeliveryState>>>,
+    
+    // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
+    
+    /// $PACKS token mint
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// Player A's $PACKS token account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = player_a,
+    )]
+    pub player_a_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow token account (PDA-controlled)
+    /// Seeds: ["escrow", match_id_hash]
+    #[account(
+        init,
+        payer = player_a,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        seeds = [b"escrow", match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow authority PDA (signs for escrow transfers)
+    /// CHECK: This is a PDA used only as signing authority for escrow
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
+    
+    // ========== Players ==========
+    
+    /// Player A (escrows the stake and pays rent for every account)
+    #[account(mut)]
+    pub player_a: Signer<'info>,
+    
+    /// Player B's public key (used for PDA derivation)
+    /// CHECK: Validated via constraint on match_state
+    pub player_b: UncheckedAccount<'info>,
+    
+    // ========== Programs ==========
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+    
+    /// Balance tunables to snapshot (no GameConfig account = compiled-in defaults)
+    /// CHECK: address fixed by seeds; contents read by GameConfig::load_tunables
+    #[account(seeds = [GAME_CONFIG_SEED], bump)]
+    pub game_config: UncheckedAccount<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pda::MatchPdas;
+    use crate::state::delivery_state::tests::test_delivery_state;
+    use crate::state::grow_state::tests::test_grow_state;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::stake_state::tests::test_stake;
+    use crate::state::{GameTunables, MatchStatus, STAKE_AMOUNT};
+    
+    fn args(player_a: Pubkey, player_b: Pubkey, delivery_less: bool) -> NewMatchArgs {
+        NewMatchArgs {
+            match_id_hash: [42u8; 32],
+            match_id: None,
+            start_ts: 1_000,
+            player_a,
+            player_b,
+            player_a_rep_start: 0,
+            player_b_rep_start: 0,
+            delayed_settlement: false,
+            layer_config: None,
+            delivery_less,
+            rep_ledger: false,
+            practice: false,
+            mercy_rule: None,
+            extra_delivery_spots: None,
+            start_grace: false,
+            hide_live_scores: false,
+            stake_amount: STAKE_AMOUNT,
+            max_sales_per_bucket: None,
+            strain_unlock: None,
+            player_b_stake_amount: None,
+            pot_split: None,
+            shared_land: false,
+            tunables: GameTunables {
+                growth_times: [5, 20, 40],
+                ..GameTunables::DEFAULT
+            },
+        }
+    }
+    
+    #[test]
+    fn test_setup_creates_all_match_accounts() {
+        let pdas = MatchPdas::derive(&[42u8; 32], &Pubkey::new_unique(), &Pubkey::new_unique());
+        let bump_of = |key: Pubkey, seeds: &[&[u8]]| {
+            let (address, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+            assert_eq!(address, key);
+            bump
+        };
+        let hash = [42u8; 32];
+        let bumps = SetupMatchBumps {
+            match_state: bump_of(
+                pdas.match_state,
+                &[b"match", &hash, pdas.player_a.as_ref(), pdas.player_b.as_ref()],
+            ),
+            stake_state: bump_of(pdas.stake_state, &[b"stake", &hash]),
+            grow_state: bump_of(pdas.grow_state, &[b"grow", &hash]),
+            delivery_state: Some(bump_of(pdas.delivery_state, &[b"delivery", &hash])),
+            escrow_token_account: Some(bump_of(pdas.escrow_token_account, &[b"escrow", &hash])),
+            ..Default::default()
+        };
+        
+        let (mut match_state, mut stake_state) = (test_match(), test_stake());
+        let (mut grow_state, mut delivery_state) = (test_grow_state(), test_delivery_state());
+        grow_state.match_id_hash = [0u8; 32];
+        delivery_state.match_id_hash = [0u8; 32];
+        initialize_match_accounts(
+            args(pdas.player_a, pdas.player_b, false),
+            &mut match_state,
+            &mut stake_state,
+            &mut grow_state,
+            Some(&mut delivery_state),
+            &bumps,
+            1_000,
+        )
+        .unwrap();
+        
+        // All four accounts belong to the same match, at their canonical PDAs
+        let match_id = MatchState::derive_match_id(&hash);
+        assert_eq!(match_state.match_id, match_id);
+        assert_eq!(
+            crate::pda::derive_match_pda(&match_state.match_id_hash, &match_state.player_a, &match_state.player_b),
+            (pdas.match_state, match_state.bump)
+        );
+        assert_eq!((stake_state.match_id, stake_state.match_id_hash), (match_id, hash));
+        assert_eq!(stake_state.status, MatchStatus::Pending);
+        assert_eq!(stake_state.player_a_escrowed, STAKE_AMOUNT);
+        assert!(grow_state.is_initialized());
+        // Both carry the same tunables snapshot
+        assert_eq!(match_state.tunables.growth_times, [5, 20, 40]);
+        assert_eq!(grow_state.tunables, match_state.tunables);
+        assert_eq!(MatchGrowState::derive_pda(&grow_state.match_id_hash), (pdas.grow_state, grow_state.bump));
+        assert!(delivery_state.is_initialized());
+        assert!(delivery_state.active_count > 0);
+        assert_eq!(
+            MatchDeliveryState::derive_pda(&delivery_state.match_id_hash),
+            (pdas.delivery_state, delivery_state.bump)
+        );
+        
+        // The delivery account must be supplied exactly when deliveries are on
+        let mut grow_state = test_grow_state();
+        assert_eq!(
+            initialize_match_accounts(
+                args(pdas.player_a, pdas.player_b, false),
+                &mut test_match(),
+                &mut test_stake(),
+                &mut grow_state,
+                None,
+                &bumps,
+                1_000,
+            )
+            .unwrap_err(),
+            DroogError::DeliveryStateNotInitialized.into()
+        );
+        assert_eq!(
+            initialize_match_accounts(
+                args(pdas.player_a, pdas.player_b, true),
+                &mut test_match(),
+                &mut test_stake(),
+                &mut grow_state,
+                Some(&mut test_delivery_state()),
+                &bumps,
+                1_000,
+            )
+            .unwrap_err(),
+            DroogError::DeliveryDisabled.into()
+        );
+    }
+}
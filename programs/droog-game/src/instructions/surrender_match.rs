@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use crate::state::{
+    MatchState, MatchStakeState, MatchStatus, LifecycleAction, ESCROW_AUTH_SEED,
+    ProgramConfig, PROGRAM_CONFIG_SEED, SettlementRecord, SETTLEMENT_RECORD_SEED,
+};
+use crate::errors::DroogError;
+
+/// Concede a running match: the opponent immediately receives the full escrow
+/// 
+/// Requirements:
+/// 1. Match not finalized (a second surrender fails with MatchAlreadyFinalized)
+/// 2. Before end_ts (finalize_match handles ended matches)
+/// 3. Signer is player_a or player_b
+/// 4. Stake must be Active (both players committed)
+/// 
+/// The payout is immediate even for delayed-settlement matches: the loser
+/// has conceded, so there is no result to challenge. A pot split does not
+/// apply either - conceding forfeits the whole pot. winner_token_account may
+/// be any token account of the payout mint owned by the winner.
+/// 
+/// The treasury rake is taken first, as at finalize: treasury_token_account
+/// (owned by ProgramConfig's treasury) is required unless it is zero.
+/// 
+/// Practice matches end the same way but move no tokens; the token
+/// accounts may be omitted.
+/// 
+/// Settlement record: the payout is written to a new SettlementRecord PDA
+/// (outcome Surrendered), paid for by the conceding player, as finalize would.
+pub fn surrender_match(ctx: Context<SurrenderMatch>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let stake_state = &mut ctx.accounts.stake_state;
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let surrendering_player = ctx.accounts.player.key();
+    let winner = match_state.surrender_winner(surrendering_player, current_ts)?;
+    stake_state.check_lifecycle(LifecycleAction::Surrender)?;
+    
+    // Same payout rule as finalize: the whole (post-burn) escrow balance,
+    // less the treasury rake
+    let pot = stake_state.payout_amount(
+        ctx.accounts.escrow_token_account.as_ref().map(|e| e.amount),
+    )?;
+    let treasury = ProgramConfig::treasury(&ctx.accounts.program_config)?;
+    let rake = MatchStakeState::treasury_rake(pot, treasury)?;
+    let amount = pot - rake;
+    
+    if !stake_state.practice {
+        let (Some(mint), Some(escrow_token_account), Some(escrow_authority), Some(winner_token_account)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.escrow_token_account.as_ref(),
+            ctx.accounts.escrow_authority.as_ref(),
+            ctx.accounts.winner_token_account.as_ref(),
+        ) else {
+            return err!(DroogError::StakeAccountsRequired);
+        };
+        MatchStakeState::validate_payout_destination(&winner_token_account.owner, &winner)?;
+        let mut payouts = [(winner_token_account, 0), (winner_token_account, amount)];
+        
+        // Treasury rake goes out before the winner is paid
+        if rake > 0 {
+            let Some(treasury_token_account) = ctx.accounts.treasury_token_account.as_ref() else {
+                return err!(DroogError::StakeAccountsRequired);
+            };
+            MatchStakeState::validate_treasury_destination(&treasury_token_account.owner, treasury)?;
+            MatchStakeState::validate_payout_mint(&treasury_token_account.mint, &mint.key())?;
+            payouts[0] = (treasury_token_account, rake);
+        }
+        
+        let match_id_hash = stake_state.match_id_hash;
+        let escrow_auth_bump = ctx.bumps.escrow_authority.unwrap_or_default();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            ESCROW_AUTH_SEED,
+            match_id_hash.as_ref(),
+            &[escrow_auth_bump],
+        ]];
+        
+        for (destination, amount) in payouts {
+            if amount == 0 {
+                continue;
+            }
+            let transfer_accounts = TransferChecked {
+                from: escrow_token_account.to_account_info(),
+                to: destination.to_account_info(),
+                mint: mint.to_account_info(),
+                authority: escrow_authority.to_account_info(),
+            };
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+            transfer_checked(transfer_ctx, amount, mint.decimals)?;
+        }
+    }
+    
+    match_state.record_result(Some(winner), amount, current_ts);
+    stake_state.status = MatchStatus::Finalized;
+    ctx.accounts.settlement_record.record_surrender(
+        stake_state,
+        winner,
+        surrendering_player,
+        amount,
+        rake,
+        current_ts,
+        ctx.bumps.settlement_record,
+    );
+    
+    emit!(MatchSurrenderedEvent {
+        match_id: match_state.match_id,
+        surrendering_player,
+        winner,
+        amount,
+        rake,
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SurrenderMatch<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            match_state.load()?.match_id_hash.as_ref(),
+            match_state.load()?.player_a.as_ref(),
+            match_state.load()?.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.bump,
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// Settlement history PDA, created here (rent paid by the conceding player)
+    #[account(
+        init,
+        payer = player,
+        space = SettlementRecord::SIZE,
+        seeds = [SETTLEMENT_RECORD_SEED, match_state.load()?.match_id_hash.as_ref()],
+        bump
+    )]
+    pub settlement_record: Box<Account<'info, SettlementRecord>>,
+    
+    // ========== Token Accounts ==========
+    // Optional only for practice matches (no stake); required otherwise
+    
+    /// $PACKS token mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    /// Escrow token account
+    #[account(
+        mut,
+        seeds = [b"escrow", match_state.load()?.match_id_hash.as_ref()],
+        bump = stake_state.escrow_bump,
+    )]
+    pub escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Escrow authority PDA (signs for payout transfer)
+    /// CHECK: This is a PDA used only as signing authority
+    #[account(
+        seeds = [ESCROW_AUTH_SEED, match_state.load()?.match_id_hash.as_ref()],
+        bump
+    )]
+    pub escrow_authority: Option<UncheckedAccount<'info>>,
+    
+    /// Opponent's token account (receives the pot; must be owned by the winner)
+    #[account(mut)]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Treasury's token account for the rake (owned by ProgramConfig's
+    /// treasury); required unless the rake is zero
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The player conceding (mutable to pay for settlement_record)
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted when a player concedes and the opponent is paid
+#[event]
+pub struct MatchSurrenderedEvent {
+    pub match_id: u64,
+    pub surrendering_player: Pubkey,
+    pub winner: Pubkey,
+    /// Escrow paid to the winner, after the rake (0 for practice matches)
+    pub amount: u64,
+    /// Treasury rake taken off the pot
+    pub rake: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    MatchGrowState, MatchState, MatchStakeState, RAID_REP_PENALTY, REP_REASON_RAID,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+use super::harvest_strain::emit_capacity_transition;
+
+/// Police raid on a player whose grow op smells too strongly
+/// 
+/// Permissionless (the opponent or any keeper can call it). Succeeds only if:
+/// 1. The match is live (started, not ended, not finalized, stake Active)
+/// 2. The target's smell, recomputed on-chain from their slots at the current
+///    time, is at least SMELL_RAID_THRESHOLD
+/// 3. The target hasn't already been raided in this rotation bucket
+/// 
+/// The target's entire inventory is confiscated and they lose RAID_REP_PENALTY
+/// reputation.
+pub fn trigger_raid(ctx: Context<TriggerRaid>, player: Pubkey) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Raids only happen during the match
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    let capacity = grow_state.inventory_capacity(is_player_a, current_ts);
+    let was_full = if is_player_a {
+        !grow_state.player_a_inventory.has_space(capacity)
+    } else {
+        !grow_state.player_b_inventory.has_space(capacity)
+    };
+    
+    let (smell_level, inventory_cleared) = grow_state.raid(is_player_a, current_ts)?;
+    let reputation_penalty = -match_state.adjust_reputation(is_player_a, -RAID_REP_PENALTY, REP_REASON_RAID);
+    
+    let match_id = grow_state.match_id;
+    emit_capacity_transition(match_id, player, was_full, false, 0, current_ts);
+    
+    emit!(RaidEvent {
+        match_id,
+        player,
+        smell_level,
+        inventory_cleared,
+        reputation_penalty,
+        triggered_by: ctx.accounts.caller.key(),
+        timestamp: current_ts,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TriggerRaid<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (timing validation and reputation)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// Whoever calls in the raid (opponent or keeper)
+    pub caller: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+/// Emitted when a player's inventory is confiscated by trigger_raid
+#[event]
+pub struct RaidEvent {
+    pub match_id: u64,
+    /// The raided player
+    pub player: Pubkey,
+    /// Smell at the time of the raid (>= SMELL_RAID_THRESHOLD)
+    pub smell_level: u16,
+    /// Items confiscated from inventory
+    pub inventory_cleared: u8,
+    /// Reputation actually lost (less than RAID_REP_PENALTY near the floor)
+    pub reputation_penalty: i32,
+    pub triggered_by: Pubkey,
+    pub timestamp: i64,
+}
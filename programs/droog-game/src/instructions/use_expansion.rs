@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    Inventory, MatchGrowState, MatchState, MatchStakeState, REPLAY_ACTION_EXPANSION,
+    REP_REASON_EXPANSION,
+    ProgramConfig, PROGRAM_CONFIG_SEED,
+};
+use crate::errors::DroogError;
+
+/// Buy a temporary inventory expansion with reputation
+/// 
+/// This instruction:
+/// 1. Validates the player, match timing, and stake lifecycle
+/// 2. Requires at least EXPANSION_REP_COST reputation (and no active expansion)
+/// 3. Raises the player's inventory cap by EXPANSION_CAP_BONUS for
+///    EXPANSION_DURATION_SECONDS (on top of any permanent upgrades)
+/// 
+/// Enables burst harvesting ahead of a big delivery window. Items held over
+/// the base cap when the expansion lapses are kept but block new harvests.
+pub fn use_expansion(ctx: Context<UseExpansion>) -> Result<()> {
+    // Program-wide kill-switch
+    ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;
+    
+    let clock = Clock::get()?;
+    let current_ts = clock.unix_timestamp;
+    
+    let grow_state = &mut ctx.accounts.grow_state;
+    let mut match_state = ctx.accounts.match_state.load_mut()?;
+    let player = ctx.accounts.player.key();
+    
+    // Prevent state changes after finalization
+    require!(!match_state.is_finalized(), DroogError::MatchAlreadyFinalized);
+    
+    // Gameplay requires an Active stake (both players committed)
+    ctx.accounts.stake_state.check_gameplay()?;
+    
+    // Validate match is active
+    require!(current_ts >= match_state.start_ts, DroogError::MatchNotStarted);
+    require!(current_ts < match_state.end_ts, DroogError::MatchEnded);
+    
+    let is_player_a = player == grow_state.player_a;
+    let is_player_b = player == grow_state.player_b;
+    require!(is_player_a || is_player_b, DroogError::InvalidPlayer);
+    
+    // Pay with reputation (never dips below zero to buy)
+    let reputation = if is_player_a {
+        match_state.player_a_reputation
+    } else {
+        match_state.player_b_reputation
+    };
+    require!(reputation >= Inventory::EXPANSION_REP_COST, DroogError::InsufficientReputation);
+    
+    let inventory = if is_player_a {
+        &mut grow_state.player_a_inventory
+    } else {
+        &mut grow_state.player_b_inventory
+    };
+    inventory.activate_expansion(current_ts)?;
+    let expires_ts = inventory.temp_cap_expiry;
+    let new_capacity = grow_state.inventory_capacity(is_player_a, current_ts);
+    
+    match_state.adjust_reputation(is_player_a, -Inventory::EXPANSION_REP_COST, REP_REASON_EXPANSION);
+    
+    emit!(ExpansionUsedEvent {
+        match_id: grow_state.match_id,
+        player,
+        new_capacity,
+        expires_ts,
+        reputation_cost: Inventory::EXPANSION_REP_COST,
+        timestamp: current_ts,
+    });
+    
+    emit!(match_state.next_replay_frame(
+        REPLAY_ACTION_EXPANSION,
+        player,
+        Inventory::EXPANSION_CAP_BONUS,
+        new_capacity,
+        0,
+        current_ts,
+    ));
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UseExpansion<'info> {
+    /// The grow state PDA
+    /// Boxed to avoid stack overflow
+    #[account(
+        mut,
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+    
+    /// The corresponding match state (timing validation and reputation cost)
+    #[account(
+        mut,
+        seeds = [
+            b"match",
+            grow_state.match_id_hash.as_ref(),
+            grow_state.player_a.as_ref(),
+            grow_state.player_b.as_ref()
+        ],
+        bump = match_state.load()?.bump
+    )]
+    pub match_state: AccountLoader<'info, MatchState>,
+    
+    /// Stake state (gameplay requires stake status == Active)
+    #[account(
+        seeds = [b"stake", grow_state.match_id_hash.as_ref()],
+        bump = stake_state.bump
+    )]
+    pub stake_state: Box<Account<'info, MatchStakeState>>,
+    
+    /// The player buying the expansion
+    pub player: Signer<'info>,
+    
+    /// Program-wide kill-switch (no Config account = not paused)
+    /// CHECK: address fixed by seeds; contents read by ProgramConfig::check_not_paused
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct ExpansionUsedEvent {
+    pub match_id: u64,
+    pub player: Pubkey,
+    pub new_capacity: u8,
+    pub expires_ts: i64,
+    pub reputation_cost: i32,
+    pub timestamp: i64,
+}
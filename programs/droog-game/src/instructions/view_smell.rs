@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::state::{MatchGrowState, SMELL_RAID_THRESHOLD};
+
+/// Emit both players' current smell (read-only)
+/// 
+/// Uses MatchGrowState::compute_smell on the stored slots at the current
+/// clock, exactly as trigger_raid does, so a client's smell bar never drifts
+/// from the number a raid would be judged on. Only Growing plants contribute:
+/// a plant that has flipped to Ready (or been harvested) adds nothing.
+pub fn view_smell(ctx: Context<ViewSmell>) -> Result<()> {
+    let clock = Clock::get()?;
+    emit!(compute_smell_snapshot(&ctx.accounts.grow_state, clock.unix_timestamp));
+    Ok(())
+}
+
+/// Build the smell snapshot for both players (pure, shared with tests)
+pub fn compute_smell_snapshot(grow_state: &MatchGrowState, current_ts: i64) -> SmellSnapshotEvent {
+    let player_a_smell = MatchGrowState::compute_smell(&grow_state.tunables, &grow_state.player_a_slots, current_ts);
+    let player_b_smell = MatchGrowState::compute_smell(&grow_state.tunables, &grow_state.player_b_slots, current_ts);
+    
+    SmellSnapshotEvent {
+        match_id: grow_state.match_id,
+        player_a_smell,
+        player_b_smell,
+        player_a_raidable: player_a_smell >= SMELL_RAID_THRESHOLD,
+        player_b_raidable: player_b_smell >= SMELL_RAID_THRESHOLD,
+        timestamp: current_ts,
+    }
+}
+
+#[derive(Accounts)]
+pub struct ViewSmell<'info> {
+    /// Boxed to avoid stack overflow
+    #[account(
+        seeds = [b"grow", grow_state.match_id_hash.as_ref()],
+        bump = grow_state.bump
+    )]
+    pub grow_state: Box<Account<'info, MatchGrowState>>,
+}
+
+/// Authoritative smell levels at a point in time
+#[event]
+pub struct SmellSnapshotEvent {
+    pub match_id: u64,
+    pub player_a_smell: u16,
+    pub player_b_smell: u16,
+    /// Smell is at or above SMELL_RAID_THRESHOLD (raid-prone, subject to the
+    /// once-per-rotation limit)
+    pub player_a_raidable: bool,
+    pub player_b_raidable: bool,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::grow_state::tests::test_grow_state;
+    use crate::state::{GameTunables, GrowSlot, PlantState, GROWTH_TIMES};
+    
+    #[test]
+    fn test_snapshot_agrees_with_raid() {
+        let mut state = test_grow_state();
+        let planted_at = 10_000;
+        state.player_a_slots[0] = GrowSlot {
+            plant_state: PlantState::Growing { strain_level: 3, planted_at },
+            strain_level: 3,
+            ..GrowSlot::default()
+        };
+        state.player_b_slots[0] = GrowSlot {
+            plant_state: PlantState::Growing { strain_level: 1, planted_at },
+            strain_level: 1,
+            ..GrowSlot::default()
+        };
+        
+        // Level 3 left Growing for 3 minutes: exactly at the threshold
+        let now = planted_at + 180;
+        let snapshot = compute_smell_snapshot(&state, now);
+        assert_eq!((snapshot.player_a_smell, snapshot.player_b_smell), (SMELL_RAID_THRESHOLD, 3));
+        assert!(snapshot.player_a_raidable);
+        assert!(!snapshot.player_b_raidable);
+        
+        // The raid sees the same number
+        let mut raided = state.clone();
+        assert_eq!(raided.raid(true, now).unwrap().0, snapshot.player_a_smell);
+        
+        // A plant that just ripened still counts while stored as Growing...
+        state.player_a_slots[0].plant_state = PlantState::Growing {
+            strain_level: 3,
+            planted_at: now - GROWTH_TIMES[2],
+        };
+        assert_eq!(compute_smell_snapshot(&state, now).player_a_smell, 4);
+        
+        // ...but once it flips to Ready it stops contributing immediately
+        state.player_a_slots[0].advance_if_ready(&GameTunables::DEFAULT, now);
+        assert_eq!(state.player_a_slots[0].plant_state, PlantState::Ready { strain_level: 3, ready_at: now });
+        let snapshot = compute_smell_snapshot(&state, now);
+        assert_eq!(snapshot.player_a_smell, 0);
+        assert!(!snapshot.player_a_raidable);
+        assert_eq!(snapshot.player_b_smell, 3);
+    }
+}
@@ -1,10 +1,14 @@
 use anchor_lang::prelude::*;
 
+pub mod deterministic;
 pub mod errors;
 pub mod instructions;
+pub mod pda;
 pub mod state;
+pub mod utils;
 
 use instructions::*;
+use state::{GameTunables, LayerConfig, MercyRule, PotSplit, StrainUnlock};
 
 declare_id!("2xzwRYwn1gdVBd5FBrvWV5To6qKR9pn9UfiJnZz8GwC2");
 
@@ -18,13 +22,123 @@ pub mod droog_game {
     /// - Player A escrows 100% of stake (NO BURN)
     /// - Match status = Pending
     /// - Player A can cancel if Player B never joins
+    /// 
+    /// Handicap: starting reputations are clamped to REP bounds
+    /// delayed_settlement: payout waits out a challenge window after finalize
+    /// layer_config: per-layer customer counts (None = default 3/8/12)
+    /// delivery_less: no delivery rotation; sales gated by cooldowns only
+    /// rep_ledger: record every reputation delta on MatchState for auditing
+    /// practice: no stake; token accounts may be omitted and nothing is paid out
+    /// mercy_rule: sales margin/cap for early finalize of blowouts (None = off)
+    /// extra_delivery_spots: delivery board size beyond 3 minimums (None = 2)
+    /// start_grace: opening lock on plant/sell for START_GRACE_SECONDS
+    /// hide_live_scores: fog of war; no attributed sale/score events until finalize
+    /// stake_amount: per-player stake in raw units (nonzero, <= MAX_STAKE_AMOUNT)
+    /// max_sales_per_bucket: per-player sales cap per rotation bucket (None = off)
+    /// strain_unlock: reputation needed to plant Level 2 / Level 3 (None = all open)
+    /// player_b_stake_amount: handicap stake for Player B (None = match stake_amount)
+    /// pot_split: per-outcome share of the pot instead of winner-take-all (None = off)
+    /// shared_land: neutral grow slots either player can claim by planting
+    #[allow(clippy::too_many_arguments)]
     pub fn init_match(
         ctx: Context<InitMatch>, 
         match_id_hash: [u8; 32],
         match_id: Option<u64>,
-        start_ts: i64
+        start_ts: i64,
+        player_a_rep_start: i32,
+        player_b_rep_start: i32,
+        delayed_settlement: bool,
+        layer_config: Option<LayerConfig>,
+        delivery_less: bool,
+        rep_ledger: bool,
+        practice: bool,
+        mercy_rule: Option<MercyRule>,
+        extra_delivery_spots: Option<u8>,
+        start_grace: bool,
+        hide_live_scores: bool,
+        stake_amount: u64,
+        max_sales_per_bucket: Option<u8>,
+        strain_unlock: Option<StrainUnlock>,
+        player_b_stake_amount: Option<u64>,
+        pot_split: Option<PotSplit>,
+        shared_land: bool,
+    ) -> Result<()> {
+        instructions::init_match(
+            ctx,
+            match_id_hash,
+            match_id,
+            start_ts,
+            player_a_rep_start,
+            player_b_rep_start,
+            delayed_settlement,
+            layer_config,
+            delivery_less,
+            rep_ledger,
+            practice,
+            mercy_rule,
+            extra_delivery_spots,
+            start_grace,
+            hide_live_scores,
+            stake_amount,
+            max_sales_per_bucket,
+            strain_unlock,
+            player_b_stake_amount,
+            pot_split,
+            shared_land,
+        )
+    }
+
+    /// Create match, stake, grow and delivery state and escrow Player A's
+    /// stake in one atomic transaction
+    /// Same arguments and validations as init_match; the delivery account is
+    /// omitted for delivery_less matches
+    #[allow(clippy::too_many_arguments)]
+    pub fn setup_match(
+        ctx: Context<SetupMatch>,
+        match_id_hash: [u8; 32],
+        match_id: Option<u64>,
+        start_ts: i64,
+        player_a_rep_start: i32,
+        player_b_rep_start: i32,
+        delayed_settlement: bool,
+        layer_config: Option<LayerConfig>,
+        delivery_less: bool,
+        rep_ledger: bool,
+        practice: bool,
+        mercy_rule: Option<MercyRule>,
+        extra_delivery_spots: Option<u8>,
+        start_grace: bool,
+        hide_live_scores: bool,
+        stake_amount: u64,
+        max_sales_per_bucket: Option<u8>,
+        strain_unlock: Option<StrainUnlock>,
+        player_b_stake_amount: Option<u64>,
+        pot_split: Option<PotSplit>,
+        shared_land: bool,
     ) -> Result<()> {
-        instructions::init_match(ctx, match_id_hash, match_id, start_ts)
+        instructions::setup_match(
+            ctx,
+            match_id_hash,
+            match_id,
+            start_ts,
+            player_a_rep_start,
+            player_b_rep_start,
+            delayed_settlement,
+            layer_config,
+            delivery_less,
+            rep_ledger,
+            practice,
+            mercy_rule,
+            extra_delivery_spots,
+            start_grace,
+            hide_live_scores,
+            stake_amount,
+            max_sales_per_bucket,
+            strain_unlock,
+            player_b_stake_amount,
+            pot_split,
+            shared_land,
+        )
     }
 
     /// Player B joins the match and stakes their tokens
@@ -33,8 +147,15 @@ pub mod droog_game {
     /// - Player B escrows 100% of stake
     /// - Burn occurs ONLY here (10% of total)
     /// - Match becomes Active ATOMICALLY with burn
-    pub fn join_match_with_stake(ctx: Context<JoinMatchWithStake>) -> Result<()> {
-        instructions::join_match_with_stake(ctx)
+    /// - Fails if the stake to match is no longer `expected_stake_amount`
+    pub fn join_match_with_stake(ctx: Context<JoinMatchWithStake>, expected_stake_amount: u64) -> Result<()> {
+        instructions::join_match_with_stake(ctx, expected_stake_amount)
+    }
+
+    /// Raise Player A's stake while Pending (before Player B joins)
+    /// Player B must match the raised stake; the burn at join covers the final total
+    pub fn add_stake(ctx: Context<AddStake>, amount: u64) -> Result<()> {
+        instructions::add_stake(ctx, amount)
     }
 
     /// Cancel a pending match and refund Player A
@@ -47,6 +168,47 @@ pub mod droog_game {
         instructions::cancel_match(ctx)
     }
 
+    /// Cancel a pending match with both players' consent
+    /// - Only callable if status == Pending (Player B has not escrowed)
+    /// - Player A and Player B must both sign; no timeout
+    /// - Player A gets 100% refund (no burn in Pending state)
+    pub fn cancel_match_mutual(ctx: Context<CancelMatchMutual>) -> Result<()> {
+        instructions::cancel_match_mutual(ctx)
+    }
+
+    /// Start a new match for a finalized/cancelled match's player pair
+    /// 
+    /// Creates match, stake, grow and delivery state under a fresh match_id_hash
+    /// with the same rules and stake, and re-escrows Player A's stake (NO BURN)
+    pub fn rematch(ctx: Context<Rematch>, match_id_hash: [u8; 32]) -> Result<()> {
+        instructions::rematch(ctx, match_id_hash)
+    }
+
+    /// Reap an abandoned Pending match and refund Player A
+    /// 
+    /// Permissionless after ABANDONED_MATCH_HORIZON_SECONDS (30 days):
+    /// - Refund goes ONLY to Player A's ATA (created if needed)
+    /// - Match, stake, and escrow accounts are closed to Player A
+    pub fn reap_abandoned(ctx: Context<ReapAbandoned>) -> Result<()> {
+        instructions::reap_abandoned(ctx)
+    }
+
+    /// Close a settled match's stake, grow and delivery accounts
+    /// - Only once status is Finalized, Cancelled or Disputed
+    /// - Either player may call; rent goes to the participant named as recipient
+    /// - MatchState and the SettlementRecord are kept
+    pub fn close_match_accounts(ctx: Context<CloseMatchAccounts>) -> Result<()> {
+        instructions::close_match_accounts(ctx)
+    }
+
+    /// Top up the escrow authority PDA to the rent-exempt minimum
+    /// 
+    /// The escrow authority is signing-only today and holds no lamports.
+    /// Permissionless and idempotent - a funding path for future features.
+    pub fn fund_escrow_authority(ctx: Context<FundEscrowAuthority>) -> Result<()> {
+        instructions::fund_escrow_authority(ctx)
+    }
+
     /// Initialize the grow state PDA for a match
     /// Should be called after init_match
     pub fn init_grow_state(
@@ -67,6 +229,28 @@ pub mod droog_game {
         instructions::plant_strain(ctx, slot_index, strain_level)
     }
 
+    /// Plant up to 6 slots in one transaction (all or nothing)
+    /// Each entry gets plant_strain's validations; one failure reverts the batch
+    pub fn plant_many(ctx: Context<PlantMany>, plants: Vec<PlantOrder>) -> Result<()> {
+        instructions::plant_many(ctx, plants)
+    }
+
+    /// Swap a growing plant for a different strain (restarts growth)
+    /// Same validations as plant_strain, costs a little reputation
+    pub fn replace_plant(
+        ctx: Context<ReplacePlant>,
+        slot_index: u8,
+        new_strain_level: u8,
+    ) -> Result<()> {
+        instructions::replace_plant(ctx, slot_index, new_strain_level)
+    }
+
+    /// Plant a Level 1 strain in slot 0 for both players at once (both sign)
+    /// One-time opening ritual; same validations as plant_strain
+    pub fn seed_opening_plants(ctx: Context<SeedOpeningPlants>) -> Result<()> {
+        instructions::seed_opening_plants(ctx)
+    }
+
     /// Harvest a ready plant from a grow slot
     /// Increments player inventory
     pub fn harvest_strain(
@@ -76,6 +260,40 @@ pub mod droog_game {
         instructions::harvest_strain(ctx, slot_index)
     }
 
+    /// Plant in a shared-land slot, claiming it until harvested
+    /// Same validations as plant_strain; the opponent is blocked from the slot
+    pub fn plant_shared_slot(
+        ctx: Context<PlantSharedSlot>,
+        slot_index: u8,
+        strain_level: u8,
+    ) -> Result<()> {
+        instructions::plant_shared_slot(ctx, slot_index, strain_level)
+    }
+
+    /// Harvest the caller's ready plant from a shared-land slot
+    /// Releases the slot back to neutral
+    pub fn harvest_shared_slot(ctx: Context<HarvestSharedSlot>, slot_index: u8) -> Result<()> {
+        instructions::harvest_shared_slot(ctx, slot_index)
+    }
+
+    /// Harvest every ready plant in the caller's slots in one transaction
+    /// Skips unripe slots and stops cleanly when inventory fills
+    pub fn harvest_all(ctx: Context<HarvestAll>) -> Result<()> {
+        instructions::harvest_all(ctx)
+    }
+
+    /// Spend a fertilizer charge to move a growing plant's planted_at earlier
+    /// Capped so the plant never becomes ready on the spot
+    pub fn fertilize_slot(ctx: Context<FertilizeSlot>, slot_index: u8) -> Result<()> {
+        instructions::fertilize_slot(ctx, slot_index)
+    }
+
+    /// Remove a rotten plant (left unharvested past ROT_WINDOW_SECONDS)
+    /// Frees the slot with no inventory gain
+    pub fn clear_slot(ctx: Context<ClearSlot>, slot_index: u8) -> Result<()> {
+        instructions::clear_slot(ctx, slot_index)
+    }
+    
     /// Legacy harvest instruction (kept for backwards compatibility)
     /// Note: New code should use harvest_strain instead
     pub fn harvest(
@@ -96,17 +314,155 @@ pub mod droog_game {
     ) -> Result<()> {
         instructions::sell_to_customer(ctx, customer_index, strain_level)
     }
+    
+    /// Buy a temporary inventory expansion (+3 cap for 90s) with reputation
+    pub fn use_expansion(ctx: Context<UseExpansion>) -> Result<()> {
+        instructions::use_expansion(ctx)
+    }
+    
+    /// Buy a permanent inventory upgrade (+2 cap, up to +6) with reputation
+    pub fn upgrade_inventory(ctx: Context<UpgradeInventory>) -> Result<()> {
+        instructions::upgrade_inventory(ctx)
+    }
+    
+    /// Raid a player whose live smell is at or above SMELL_RAID_THRESHOLD
+    /// Permissionless; confiscates their inventory, once per rotation bucket
+    pub fn trigger_raid(ctx: Context<TriggerRaid>, player: Pubkey) -> Result<()> {
+        instructions::trigger_raid(ctx, player)
+    }
 
     /// Finalize a match and distribute stake to winner
+    /// Participants only, until FINALIZE_GRACE_SECONDS after end_ts (then anyone)
     /// 
     /// Settlement code - treat as sacred:
     /// - Requires status == Active
-    /// - Winner determined by sales count (on-chain)
-    /// - Entire escrow balance goes to winner
+    /// - Winner determined by sales count, then reputation (on-chain)
+    /// - Entire escrow balance goes to winner (or their payout_destination override)
+    /// - Equal sales split the escrow 50/50 (immediate settlement only)
+    /// - A loser with positive reputation gets a capped consolation from the pot
     pub fn finalize_match(ctx: Context<FinalizeMatch>) -> Result<()> {
         instructions::finalize_match(ctx)
     }
     
+    /// Concede the match before end_ts; the opponent is paid the full escrow at once
+    /// Either player; a second surrender fails with MatchAlreadyFinalized
+    pub fn surrender_match(ctx: Context<SurrenderMatch>) -> Result<()> {
+        instructions::surrender_match(ctx)
+    }
+    
+    /// Freeze a delayed settlement during its challenge window (either player)
+    pub fn freeze_settlement(ctx: Context<FreezeSettlement>) -> Result<()> {
+        instructions::freeze_settlement(ctx)
+    }
+    
+    /// Pay out a delayed settlement once its challenge window has passed
+    /// Permissionless: the destination is fixed by the recorded winner
+    pub fn execute_settlement(ctx: Context<ExecuteSettlement>) -> Result<()> {
+        instructions::execute_settlement(ctx)
+    }
+    
+    /// Offer the loser double-or-nothing on a delayed settlement (recorded winner only)
+    /// The pot stays in escrow until the loser answers or the settlement executes
+    pub fn offer_double_or_nothing(ctx: Context<OfferDoubleOrNothing>) -> Result<()> {
+        instructions::offer_double_or_nothing(ctx)
+    }
+    
+    /// Accept double-or-nothing: the pot and the loser's matching stake start
+    /// a new Active match under a fresh match_id_hash (NO BURN)
+    /// - Only after the challenge window; expected_stake_amount must equal the offered pot
+    pub fn accept_double_or_nothing(
+        ctx: Context<AcceptDoubleOrNothing>,
+        match_id_hash: [u8; 32],
+        expected_stake_amount: u64,
+    ) -> Result<()> {
+        instructions::accept_double_or_nothing(ctx, match_id_hash, expected_stake_amount)
+    }
+    
+    /// Decline double-or-nothing; the original winner's settlement proceeds
+    pub fn decline_double_or_nothing(ctx: Context<DeclineDoubleOrNothing>) -> Result<()> {
+        instructions::decline_double_or_nothing(ctx)
+    }
+    
+    /// Void a delayed settlement and refund both players (dispute admin only)
+    /// Only within the challenge window, or after a player froze the settlement
+    pub fn reverse_finalization(ctx: Context<ReverseFinalization>) -> Result<()> {
+        instructions::reverse_finalization(ctx)
+    }
+    
+    /// Release a frozen delayed settlement to `winner` (dispute admin only)
+    /// The recorded winner or the other player; execute_settlement then pays out
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, winner: Pubkey) -> Result<()> {
+        instructions::resolve_dispute(ctx, winner)
+    }
+    
+    /// Set or clear the program-wide kill-switch (dispute admin only)
+    /// While paused, gameplay and settlement instructions reject with ProgramPaused;
+    /// read-only views keep working
+    pub fn set_program_paused(ctx: Context<SetProgramPaused>, paused: bool) -> Result<()> {
+        instructions::set_program_paused(ctx, paused)
+    }
+    
+    /// Name the owner of the token account that receives the treasury rake
+    /// (dispute admin only); no rake is taken until one is set
+    pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+        instructions::set_treasury(ctx, treasury)
+    }
+    
+    /// Set the balance tunables (growth times, smell rates, strain rotation)
+    /// that new matches snapshot at init (config authority only)
+    pub fn set_game_config(
+        ctx: Context<SetGameConfig>,
+        tunables: GameTunables,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_game_config(ctx, tunables, new_authority)
+    }
+    
+    /// Emit an analytical snapshot of the match (read-only)
+    /// Projected winner, sales/reputation gaps, inventory on hand, time remaining
+    pub fn get_match_insight(ctx: Context<GetMatchInsight>) -> Result<()> {
+        instructions::get_match_insight(ctx)
+    }
+    
+    /// Emit a 32-byte checksum of the match's key account fields (read-only)
+    /// For integrity monitoring; grow/delivery state are optional
+    pub fn get_match_checksum(ctx: Context<GetMatchChecksum>) -> Result<()> {
+        instructions::get_match_checksum(ctx)
+    }
+    
+    /// Emit when each of the caller's growing plants becomes ready (read-only)
+    /// Flags which ones finish before end_ts
+    pub fn get_projected_ready(ctx: Context<GetProjectedReady>) -> Result<()> {
+        instructions::get_projected_ready(ctx)
+    }
+    
+    /// Emit every customer's deterministic display flavor (read-only)
+    /// Identical for both players; derived from the match_id
+    pub fn get_customer_flavors(ctx: Context<GetCustomerFlavors>) -> Result<()> {
+        instructions::get_customer_flavors(ctx)
+    }
+    
+    /// Emit both players' current smell, as trigger_raid computes it (read-only)
+    pub fn view_smell(ctx: Context<ViewSmell>) -> Result<()> {
+        instructions::view_smell(ctx)
+    }
+    
+    /// Emit the projected settlement (winner, escrow, payout) without mutating state
+    /// Uses the same math as finalize_match
+    pub fn preview_payout(ctx: Context<PreviewPayout>) -> Result<()> {
+        instructions::preview_payout(ctx)
+    }
+    
+    /// Emit severity, suggested action and retryability for a DroogError code (read-only)
+    pub fn get_error_hint(ctx: Context<GetErrorHint>, code: u32) -> Result<()> {
+        instructions::get_error_hint(ctx, code)
+    }
+    
+    /// Emit the default gameplay and staking constants for clients (read-only)
+    pub fn get_constants(ctx: Context<GetConstants>) -> Result<()> {
+        instructions::get_constants(ctx)
+    }
+    
     // ========== Delivery State Instructions ==========
     
     /// Initialize the delivery state PDA for a match
@@ -125,7 +481,9 @@ pub mod droog_game {
     
     /// Refresh delivery slots after 60-second rotation interval
     /// 
-    /// Permissionless: Anyone can call this, but it only succeeds if:
+    /// Permissionless: Anyone can call this (participants earn refresh credit,
+    /// keepers earn a small reward from the refresh pool if funded),
+    /// but it only succeeds if:
     /// - 60 seconds have passed since last refresh
     /// - Match is still active
     /// 
@@ -135,4 +493,9 @@ pub mod droog_game {
     pub fn refresh_delivery_slots(ctx: Context<RefreshDeliverySlots>) -> Result<()> {
         instructions::refresh_delivery_slots(ctx)
     }
+    
+    /// Deposit SOL into a match's keeper incentive pool (permissionless)
+    pub fn fund_refresh_pool(ctx: Context<FundRefreshPool>, amount: u64) -> Result<()> {
+        instructions::fund_refresh_pool(ctx, amount)
+    }
 }
@@ -0,0 +1,192 @@
+// ============================================================================
+// PDA DERIVATION
+// ============================================================================
+//
+// One source of truth for every program-derived address. Clients and tests
+// derive accounts through these helpers instead of re-typing seeds; the tests
+// below pin each helper to the seeds written in the account constraints.
+//
+// Every per-match PDA is seeded on the full 32-byte match_id_hash (never the
+// 8-byte match_id). The match PDA additionally takes both player keys, in
+// canonical order (player_a < player_b, enforced by init_match).
+
+use anchor_lang::prelude::*;
+use crate::state::{
+    DOUBLE_OR_NOTHING_SEED, ESCROW_AUTH_SEED, GAME_CONFIG_SEED, PROGRAM_CONFIG_SEED, SETTLEMENT_RECORD_SEED,
+};
+
+/// Seeds: ["match", match_id_hash, player_a, player_b]
+pub const MATCH_SEED: &[u8] = b"match";
+
+/// Seeds: ["stake", match_id_hash]
+pub const STAKE_SEED: &[u8] = b"stake";
+
+/// Escrow token account. Seeds: ["escrow", match_id_hash]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Seeds: ["grow", match_id_hash]
+pub const GROW_SEED: &[u8] = b"grow";
+
+/// Seeds: ["delivery", match_id_hash]
+pub const DELIVERY_SEED: &[u8] = b"delivery";
+
+/// MatchState PDA (players must already be in canonical order)
+pub fn derive_match_pda(match_id_hash: &[u8; 32], player_a: &Pubkey, player_b: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MATCH_SEED, match_id_hash.as_ref(), player_a.as_ref(), player_b.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// MatchStakeState PDA
+pub fn derive_stake_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// Escrow token account PDA (owned by the escrow authority)
+pub fn derive_escrow_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// Escrow authority PDA (signs burn/refund/payout CPIs)
+pub fn derive_escrow_authority_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_AUTH_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// MatchGrowState PDA
+pub fn derive_grow_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GROW_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// MatchDeliveryState PDA
+pub fn derive_delivery_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DELIVERY_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// SettlementRecord PDA (created by finalize_match)
+pub fn derive_settlement_record_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SETTLEMENT_RECORD_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// DoubleOrNothingOffer PDA (created by offer_double_or_nothing)
+pub fn derive_double_or_nothing_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DOUBLE_OR_NOTHING_SEED, match_id_hash.as_ref()], &crate::ID)
+}
+
+/// Program-wide ProgramConfig PDA (kill-switch)
+pub fn derive_program_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &crate::ID)
+}
+
+/// Program-wide GameConfig PDA (balance tunables)
+pub fn derive_game_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GAME_CONFIG_SEED], &crate::ID)
+}
+
+/// Every address a client needs to create and play a match
+/// 
+/// Sorts the two players into canonical order, so callers may pass them
+/// either way round.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MatchPdas {
+    pub player_a: Pubkey,
+    pub player_b: Pubkey,
+    pub match_state: Pubkey,
+    pub stake_state: Pubkey,
+    pub escrow_token_account: Pubkey,
+    pub escrow_authority: Pubkey,
+    pub grow_state: Pubkey,
+    pub delivery_state: Pubkey,
+    pub settlement_record: Pubkey,
+    pub program_config: Pubkey,
+    pub game_config: Pubkey,
+}
+
+impl MatchPdas {
+    pub fn derive(match_id_hash: &[u8; 32], player_one: &Pubkey, player_two: &Pubkey) -> Self {
+        let (player_a, player_b) = if player_one < player_two {
+            (*player_one, *player_two)
+        } else {
+            (*player_two, *player_one)
+        };
+        Self {
+            player_a,
+            player_b,
+            match_state: derive_match_pda(match_id_hash, &player_a, &player_b).0,
+            stake_state: derive_stake_pda(match_id_hash).0,
+            escrow_token_account: derive_escrow_pda(match_id_hash).0,
+            escrow_authority: derive_escrow_authority_pda(match_id_hash).0,
+            grow_state: derive_grow_pda(match_id_hash).0,
+            delivery_state: derive_delivery_pda(match_id_hash).0,
+            settlement_record: derive_settlement_record_pda(match_id_hash).0,
+            program_config: derive_program_config_pda().0,
+            game_config: derive_game_config_pda().0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DoubleOrNothingOffer, MatchDeliveryState, MatchGrowState, MatchStakeState, SettlementRecord};
+    
+    #[test]
+    fn test_helpers_match_constraint_seeds() {
+        let hash = [7u8; 32];
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (a, b) = if a < b { (a, b) } else { (b, a) };
+        let find = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID);
+        
+        // Seeds exactly as written in the #[account(seeds = ...)] constraints
+        assert_eq!(derive_match_pda(&hash, &a, &b), find(&[b"match", &hash, a.as_ref(), b.as_ref()]));
+        assert_eq!(derive_stake_pda(&hash), find(&[b"stake", &hash]));
+        assert_eq!(derive_escrow_pda(&hash), find(&[b"escrow", &hash]));
+        assert_eq!(derive_escrow_authority_pda(&hash), find(&[b"escrow_auth", &hash]));
+        assert_eq!(derive_grow_pda(&hash), find(&[b"grow", &hash]));
+        assert_eq!(derive_delivery_pda(&hash), find(&[b"delivery", &hash]));
+        assert_eq!(derive_settlement_record_pda(&hash), find(&[b"settlement", &hash]));
+        assert_eq!(derive_double_or_nothing_pda(&hash), find(&[b"double", &hash]));
+        assert_eq!(derive_program_config_pda(), find(&[b"config"]));
+        assert_eq!(derive_game_config_pda(), find(&[b"game_config"]));
+        
+        // The per-account helpers go through the same seeds
+        assert_eq!(MatchStakeState::derive_escrow_authority(&hash), derive_escrow_authority_pda(&hash));
+        assert_eq!(MatchGrowState::derive_pda(&hash), derive_grow_pda(&hash));
+        assert_eq!(MatchDeliveryState::derive_pda(&hash), derive_delivery_pda(&hash));
+        assert_eq!(SettlementRecord::derive_pda(&hash), derive_settlement_record_pda(&hash));
+        assert_eq!(DoubleOrNothingOffer::derive_pda(&hash), derive_double_or_nothing_pda(&hash));
+    }
+    
+    #[test]
+    fn test_match_pdas_bundle() {
+        let hash = [9u8; 32];
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        
+        // Player order doesn't matter; the match PDA uses the canonical order
+        let pdas = MatchPdas::derive(&hash, &a, &b);
+        assert_eq!(pdas, MatchPdas::derive(&hash, &b, &a));
+        assert!(pdas.player_a < pdas.player_b);
+        assert_eq!(pdas.match_state, derive_match_pda(&hash, &pdas.player_a, &pdas.player_b).0);
+        assert_eq!(pdas.escrow_authority, derive_escrow_authority_pda(&hash).0);
+        
+        // Every account is distinct, and a different hash moves all per-match ones
+        let all = [
+            pdas.match_state,
+            pdas.stake_state,
+            pdas.escrow_token_account,
+            pdas.escrow_authority,
+            pdas.grow_state,
+            pdas.delivery_state,
+            pdas.settlement_record,
+            pdas.program_config,
+            pdas.game_config,
+        ];
+        for (i, key) in all.iter().enumerate() {
+            assert!(!all[..i].contains(key));
+        }
+        let other = MatchPdas::derive(&[10u8; 32], &a, &b);
+        assert_ne!(other.match_state, pdas.match_state);
+        assert_ne!(other.delivery_state, pdas.delivery_state);
+        assert_eq!(other.program_config, pdas.program_config);
+    }
+}
@@ -1,13 +1,51 @@
 use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
 
-#[account]
+/// One customer on the map (element of the zero-copy MatchState.customers)
+/// 
+/// Fields are ordered by alignment with explicit padding, so the Borsh and
+/// in-memory layouts match. `last_served_by` is only meaningful once `served`
+/// is set (zero-copy accounts cannot hold an Option).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default, Pod, Zeroable)]
+#[repr(C)]
 pub struct CustomerState {
-    pub layer: u8,                        // 1, 2, or 3
     pub last_served_ts: i64,              // Last service timestamp (0 if never served)
-    pub total_serves: u32,                 // Total times served in this match
-    pub last_served_by: Option<Pubkey>,   // Who last served this customer
+    pub player_a_last_served_ts: i64,     // Player A's last service here (0 if never; drives A's cooldown)
+    pub player_b_last_served_ts: i64,     // Player B's last service here (0 if never; drives B's cooldown)
+    pub last_served_by: Pubkey,           // Who last served this customer (valid when served != 0)
+    pub total_serves: u32,                // Total times served in this match
+    pub last_rep_earned: i32,             // Reputation the last server earned here (stealable)
+    pub layer: u8,                        // 1, 2, or 3
+    pub served: u8,                       // 1 once anyone has served this customer
+    pub padding: [u8; 6],                 // Explicit alignment padding; always zero
 }
 
 impl CustomerState {
-    pub const SIZE: usize = 1 + 8 + 4 + 1 + 32; // layer + timestamp + serves + Option discriminator + Pubkey
+    pub const SIZE: usize = 8 + 8 + 8 + 32 + 4 + 4 + 1 + 1 + 6; // timestamps + last server + serves + last_rep_earned + layer + served flag + padding
+    
+    /// A never-served customer in the given layer
+    pub fn new(layer: u8) -> Self {
+        Self { layer, ..Self::default() }
+    }
+    
+    /// Who last served this customer (None if never served)
+    pub fn last_served_by(&self) -> Option<Pubkey> {
+        (self.served != 0).then_some(self.last_served_by)
+    }
+    
+    /// Record `player` as the last server
+    pub fn set_last_served_by(&mut self, player: Pubkey) {
+        self.last_served_by = player;
+        self.served = 1;
+    }
+    
+    /// When the given player last served this customer (0 if never)
+    /// Cooldowns are per player: one player's serve never locks out the other
+    pub fn last_served_ts_of(&self, is_player_a: bool) -> i64 {
+        if is_player_a {
+            self.player_a_last_served_ts
+        } else {
+            self.player_b_last_served_ts
+        }
+    }
 }
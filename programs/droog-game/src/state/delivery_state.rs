@@ -1,11 +1,39 @@
 use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use crate::errors::DroogError;
+use crate::deterministic;
 
-/// Delivery slot rotation interval in seconds
-/// Updated for fast-paced 10-minute matches
-pub const DELIVERY_ROTATION_INTERVAL: i64 = 60;
+pub use crate::deterministic::{
+    DELIVERY_ROTATION_INTERVAL, MAX_DELIVERY_SPOTS, MIN_DELIVERY_SPOTS,
+    DEFAULT_EXTRA_DELIVERY_SPOTS, MAX_EXTRA_DELIVERY_SPOTS,
+};
+use crate::state::grow_state::VARIANT_COUNT;
+use crate::state::match_state::MatchState;
 
-/// Maximum number of active delivery spots at any time
-pub const MAX_DELIVERY_SPOTS: usize = 5;
+/// A refresh within this many seconds of the board becoming due counts as prompt
+pub const REFRESH_PROMPT_WINDOW_SECONDS: i64 = 10;
+
+/// Reputation bonus for serving a customer exactly the variant they demanded
+pub const VARIANT_DEMAND_BONUS: i32 = 3;
+
+/// Reputation penalty for serving a demanding customer the wrong variant
+pub const VARIANT_DEMAND_PENALTY: i32 = -2;
+
+/// A rush customer is only servable for this many seconds from the start of its bucket
+pub const RUSH_WINDOW_SECONDS: i64 = 15;
+
+/// Extra reputation for serving a rush customer inside its window
+pub const RUSH_REP_BONUS: i32 = 5;
+
+/// PDA seed prefix for a match's keeper incentive pool (system-owned, holds SOL)
+/// Seeds: [REFRESH_POOL_SEED, match_id_hash]
+pub const REFRESH_POOL_SEED: &[u8] = b"refresh_pool";
+
+/// Lamports paid to a non-participant who performs a due rotation (~one signature fee)
+pub const KEEPER_REFRESH_REWARD_LAMPORTS: u64 = 5_000;
+
+/// Roughly 1 in RUSH_BUCKET_ODDS rotation buckets spawns a rush customer
+pub const RUSH_BUCKET_ODDS: u64 = 4;
 
 /// Customer index ranges by layer (CANONICAL mapping)
 /// Layer 3 (Inner Core): indices 0-2   (3 customers)
@@ -18,12 +46,97 @@ pub const LAYER2_END: u8 = 10;  // inclusive
 pub const LAYER1_START: u8 = 11;
 pub const LAYER1_END: u8 = 22;  // inclusive
 
+/// Total customers on the map (length of MatchState.customers)
+pub const CUSTOMER_ROSTER_SIZE: u8 = 23;
+
+/// Per-layer customer counts for a match, chosen at init for different map shapes
+/// Indices are assigned inner to outer: Layer 3 first, then Layer 2, then Layer 1.
+/// LayerConfig::DEFAULT reproduces the LAYER*_START/END constants above.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Pod, Zeroable)]
+#[repr(C)]
+pub struct LayerConfig {
+    pub layer3_count: u8,
+    pub layer2_count: u8,
+    pub layer1_count: u8,
+}
+
+impl Default for LayerConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl LayerConfig {
+    pub const SIZE: usize = 3;
+    
+    /// 3 inner / 8 middle / 12 outer
+    pub const DEFAULT: Self = Self {
+        layer3_count: LAYER3_END - LAYER3_START + 1,
+        layer2_count: LAYER2_END - LAYER2_START + 1,
+        layer1_count: LAYER1_END - LAYER1_START + 1,
+    };
+    
+    /// Counts must cover the roster exactly. Selection needs at least one
+    /// inner customer and two each of middle/outer so its extra picks can't collide.
+    pub fn validate(&self) -> Result<()> {
+        let total = self.layer3_count as u16 + self.layer2_count as u16 + self.layer1_count as u16;
+        require!(
+            total == CUSTOMER_ROSTER_SIZE as u16
+                && self.layer3_count >= 1
+                && self.layer2_count >= 2
+                && self.layer1_count >= 2,
+            DroogError::InvalidLayerConfig
+        );
+        Ok(())
+    }
+    
+    /// First index of Layer 3 (always 0)
+    pub fn layer3_start(&self) -> u8 {
+        0
+    }
+    
+    /// First index of Layer 2
+    pub fn layer2_start(&self) -> u8 {
+        self.layer3_count
+    }
+    
+    /// First index of Layer 1
+    pub fn layer1_start(&self) -> u8 {
+        self.layer3_count + self.layer2_count
+    }
+    
+    /// Derive layer from customer index under this configuration
+    pub fn layer_from_index(&self, customer_index: u8) -> u8 {
+        if customer_index < self.layer2_start() {
+            3  // Inner Core
+        } else if customer_index < self.layer1_start() {
+            2  // Middle Ring
+        } else {
+            1  // Outer Ring
+        }
+    }
+}
+
 /// Match-scoped delivery state PDA
-/// Seeds: ["delivery", match_id.to_le_bytes()]
+/// Seeds: ["delivery", match_id_hash]
+/// 
+/// Seeded on the full 32-byte hash like match/stake/escrow. match_id is only
+/// 8 bytes (caller-supplied, or formerly the hash's first 8 bytes), so seeding
+/// on it let two matches whose hashes share a prefix collide on the same
+/// delivery PDA.
+/// Migration: delivery PDAs created under the old ["delivery", match_id.to_le_bytes()]
+/// seeds are not reachable by the new constraints; in-flight matches must
+/// re-run init_delivery_state.
 /// 
 /// This PDA tracks which customers are currently available for delivery.
 /// Availability rotates every 60 seconds using deterministic on-chain randomness.
 /// 
+/// Stale boards: rotation is lazy (refresh_delivery_slots), so a board can be
+/// past its nominal rotation with nobody having refreshed it, or with no
+/// refresh possible at all late in the match. Such a board stays
+/// authoritative for sells until a refresh actually replaces it; only the
+/// rush customer expires on its own (rush_expires_ts).
+/// 
 /// Authority: Solana ONLY
 /// - Client renders indicators but cannot influence availability
 /// - sell_to_customer validates against this state
@@ -32,6 +145,9 @@ pub struct MatchDeliveryState {
     /// Unique match identifier (must match corresponding MatchState)
     pub match_id: u64,
     
+    /// 32-byte hash used for PDA derivation (matches MatchState)
+    pub match_id_hash: [u8; 32],
+    
     /// Timestamp of last delivery slot refresh
     /// Used to enforce 60s minimum between refreshes
     pub last_update_ts: i64,
@@ -42,19 +158,94 @@ pub struct MatchDeliveryState {
     
     /// Count of valid entries in available_customers
     /// Guaranteed: 3 <= active_count <= MAX_DELIVERY_SPOTS
+    /// (exactly 3 + extra_spots from algo v3; sales can't lower it, they mark spots INVALID_INDEX)
     pub active_count: u8,
     
     /// PDA bump seed
     pub bump: u8,
+    
+    /// Variant demanded by each delivery spot (parallel to available_customers)
+    /// NO_VARIANT_DEMAND = any variant accepted
+    pub demanded_variants: [u8; MAX_DELIVERY_SPOTS],
+    
+    /// Short-lived extra customer for this bucket (INVALID_INDEX if none)
+    /// Not part of available_customers - only servable before rush_expires_ts
+    pub rush_customer: u8,
+    
+    /// Timestamp after which the rush customer can no longer be served
+    pub rush_expires_ts: i64,
+    
+    /// Deterministic algorithm version (copied from MatchState at init)
+    pub algo_version: u8,
+    
+    /// Per-layer customer counts (copied from MatchState at init)
+    pub layer_config: LayerConfig,
+    
+    /// Spots beyond the per-layer minimums (copied from MatchState at init)
+    pub extra_spots: u8,
+    
+    /// Rotations triggered via refresh_delivery_slots (the initial board doesn't count)
+    pub rotation_count: u32,
+    
+    /// Rotation bucket the current board was selected for
+    /// A refresh must land in a strictly later bucket
+    pub last_processed_bucket: u64,
 }
 
 impl MatchDeliveryState {
     /// Account size calculation
-    /// 8 (discriminator) + 8 (match_id) + 8 (last_update_ts) + 5 (available_customers) + 1 (active_count) + 1 (bump)
-    pub const SIZE: usize = 8 + 8 + 8 + MAX_DELIVERY_SPOTS + 1 + 1;
+    /// 8 (discriminator) + 8 (match_id) + 32 (match_id_hash) + 8 (last_update_ts) + 7 (available_customers)
+    /// + 1 (active_count) + 1 (bump) + 7 (demanded_variants) + 1 (rush_customer) + 8 (rush_expires_ts)
+    /// + 1 (algo_version) + 3 (layer_config) + 1 (extra_spots) + 4 (rotation_count)
+    /// + 8 (last_processed_bucket)
+    pub const SIZE: usize = 8 + 8 + 32 + 8 + MAX_DELIVERY_SPOTS + 1 + 1 + MAX_DELIVERY_SPOTS + 1 + 8 + 1
+        + LayerConfig::SIZE + 1 + 4 + 8;
     
     /// Invalid customer index sentinel value
-    pub const INVALID_INDEX: u8 = 255;
+    pub const INVALID_INDEX: u8 = deterministic::INVALID_INDEX;
+    
+    /// Sentinel for a delivery spot that accepts any variant
+    pub const NO_VARIANT_DEMAND: u8 = 255;
+    
+    /// Whether this account has been set up (a fresh init_if_needed account is zeroed)
+    pub fn is_initialized(&self) -> bool {
+        self.match_id_hash != [0u8; 32]
+    }
+    
+    /// Guard for retried init_delivery_state calls.
+    /// Ok(false): fresh account, proceed with init.
+    /// Ok(true): already set up for this exact match, caller should no-op.
+    /// Err(AlreadyInitialized): set up for something else, never re-initialize.
+    pub fn check_reinit(&self, match_id_hash: &[u8; 32], match_id: u64) -> Result<bool> {
+        if !self.is_initialized() {
+            return Ok(false);
+        }
+        require!(
+            self.match_id_hash == *match_id_hash && self.match_id == match_id,
+            DroogError::AlreadyInitialized
+        );
+        Ok(true)
+    }
+    
+    /// Set up a freshly created delivery account for match_state and select
+    /// the initial spots (shared by init_delivery_state and rematch)
+    pub fn initialize(&mut self, match_state: &MatchState, bump: u8, current_ts: i64) {
+        self.match_id = match_state.match_id;
+        self.match_id_hash = match_state.match_id_hash; // Canonical PDA seed
+        self.bump = bump;
+        self.algo_version = match_state.algo_version;
+        self.layer_config = match_state.layer_config;
+        self.extra_spots = match_state.extra_delivery_spots;
+        self.rotation_count = 0;
+        
+        // Compute initial delivery spots using deterministic seed
+        self.rotate(current_ts);
+    }
+    
+    /// Derive the delivery state PDA for a match
+    pub fn derive_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+        crate::pda::derive_delivery_pda(match_id_hash)
+    }
     
     /// Check if a customer index is currently available for delivery
     pub fn is_customer_available(&self, customer_index: u8) -> bool {
@@ -76,6 +267,7 @@ impl MatchDeliveryState {
             if i < MAX_DELIVERY_SPOTS && self.available_customers[i] == customer_index {
                 // Replace with INVALID_INDEX to mark as unavailable
                 self.available_customers[i] = Self::INVALID_INDEX;
+                self.demanded_variants[i] = Self::NO_VARIANT_DEMAND;
                 return true;
             }
         }
@@ -83,130 +275,137 @@ impl MatchDeliveryState {
     }
     
     /// Compute deterministic hash for slot selection
-    /// This hash is reproducible by any client given match_id and timestamp bucket
+    /// This hash is reproducible by any client given algo_version, match_id and timestamp bucket
     /// 
     /// Hash formula: mix(match_id, timestamp_bucket) where timestamp_bucket = ts / 60
-    pub fn compute_delivery_seed(match_id: u64, current_ts: i64) -> u64 {
-        let timestamp_bucket = (current_ts / DELIVERY_ROTATION_INTERVAL) as u64;
-        
-        // Simple deterministic hash using XOR and multiplication
-        let mut hash: u64 = match_id;
-        hash ^= timestamp_bucket;
-        
-        // Avalanche mixing to ensure good distribution
-        hash = hash.wrapping_mul(0x517cc1b727220a95);
-        hash ^= hash >> 32;
-        hash = hash.wrapping_mul(0x7fb5d329728ea185);
-        hash ^= hash >> 27;
-        
-        hash
+    /// - v0 (legacy): XOR before mixing, so (match_id ^ bucket) collisions share a seed
+    /// - v1: match_id is pre-multiplied before combining, then fully avalanched
+    pub fn compute_delivery_seed(algo_version: u8, match_id: u64, current_ts: i64) -> u64 {
+        deterministic::delivery_seed(algo_version, match_id, current_ts)
     }
     
     /// Select delivery spots deterministically from a seed
     /// 
-    /// Guarantees:
-    /// - Exactly 1 spot from Layer 3 (indices 0-2)
-    /// - Exactly 1 spot from Layer 2 (indices 3-10)
-    /// - Exactly 1 spot from Layer 1 (indices 11-22)
-    /// - 2 additional spots from any layer
+    /// Guarantees (layer ranges come from the match's LayerConfig):
+    /// - Exactly 1 spot from Layer 3 (indices 0-2 by default)
+    /// - Exactly 1 spot from Layer 2 (indices 3-10 by default)
+    /// - Exactly 1 spot from Layer 1 (indices 11-22 by default)
+    /// - `extra_spots` additional spots from any layer (2 by default); before
+    ///   algo v3 a colliding second pick could leave the board one short
     /// 
     /// Returns array of customer indices and count of valid entries
-    pub fn select_delivery_spots(seed: u64) -> ([u8; MAX_DELIVERY_SPOTS], u8) {
-        let mut spots = [Self::INVALID_INDEX; MAX_DELIVERY_SPOTS];
-        let mut count: u8 = 0;
-        
-        // Layer 3: 3 customers (indices 0-2)
-        let layer3_count = (LAYER3_END - LAYER3_START + 1) as u64;
-        let layer3_pick = LAYER3_START + ((seed % layer3_count) as u8);
-        spots[count as usize] = layer3_pick;
-        count += 1;
-        
-        // Layer 2: 8 customers (indices 3-10)
-        let layer2_count = (LAYER2_END - LAYER2_START + 1) as u64;
-        let layer2_pick = LAYER2_START + (((seed >> 8) % layer2_count) as u8);
-        spots[count as usize] = layer2_pick;
-        count += 1;
-        
-        // Layer 1: 12 customers (indices 11-22)
-        let layer1_count = (LAYER1_END - LAYER1_START + 1) as u64;
-        let layer1_pick = LAYER1_START + (((seed >> 16) % layer1_count) as u8);
-        spots[count as usize] = layer1_pick;
-        count += 1;
-        
-        // Additional spot 1: from Layer 1 or Layer 2 (weighted toward outer layers)
-        // Use different seed bits to avoid correlation
-        let additional1_seed = seed >> 24;
-        if additional1_seed % 3 == 0 {
-            // Layer 2 pick (different from first L2 pick)
-            let l2_offset = ((additional1_seed >> 4) % layer2_count) as u8;
-            let pick = LAYER2_START + l2_offset;
-            // Avoid duplicate
-            if !Self::contains_spot(&spots, count, pick) {
-                spots[count as usize] = pick;
-                count += 1;
-            } else {
-                // Fallback to next index
-                let fallback = LAYER2_START + ((l2_offset + 1) % layer2_count as u8);
-                spots[count as usize] = fallback;
-                count += 1;
-            }
-        } else {
-            // Layer 1 pick (different from first L1 pick)
-            let l1_offset = ((additional1_seed >> 4) % layer1_count) as u8;
-            let pick = LAYER1_START + l1_offset;
-            if !Self::contains_spot(&spots, count, pick) {
-                spots[count as usize] = pick;
-                count += 1;
-            } else {
-                let fallback = LAYER1_START + ((l1_offset + 1) % layer1_count as u8);
-                spots[count as usize] = fallback;
-                count += 1;
+    pub fn select_delivery_spots(
+        algo_version: u8,
+        seed: u64,
+        layers: &LayerConfig,
+        extra_spots: u8,
+    ) -> ([u8; MAX_DELIVERY_SPOTS], u8) {
+        deterministic::select_delivery_spots(
+            algo_version,
+            seed,
+            layers.layer3_count,
+            layers.layer2_count,
+            layers.layer1_count,
+            extra_spots,
+        )
+    }
+    
+    /// Extra spots chosen at init must fit the board (0..=MAX_EXTRA_DELIVERY_SPOTS)
+    pub fn validate_extra_spots(extra_spots: u8) -> Result<()> {
+        require!(
+            extra_spots <= MAX_EXTRA_DELIVERY_SPOTS,
+            DroogError::InvalidDeliverySpotCount
+        );
+        Ok(())
+    }
+    
+    /// Select which delivery spots demand a specific variant
+    /// 
+    /// Deterministic from the same rotation seed (remixed so demands don't
+    /// correlate with spot selection). Each valid spot has a 1-in-4 chance of
+    /// demanding a variant in 0..VARIANT_COUNT.
+    pub fn compute_variant_demands(seed: u64, count: u8) -> [u8; MAX_DELIVERY_SPOTS] {
+        let mut demands = [Self::NO_VARIANT_DEMAND; MAX_DELIVERY_SPOTS];
+        
+        let mut hash = seed ^ 0x9e3779b97f4a7c15;
+        hash = hash.wrapping_mul(0x517cc1b727220a95);
+        hash ^= hash >> 29;
+        
+        for (i, demand) in demands.iter_mut().enumerate().take(count as usize) {
+            let bits = hash >> (i * 8);
+            if bits.is_multiple_of(4) {
+                *demand = ((bits >> 2) % VARIANT_COUNT as u64) as u8;
             }
         }
         
-        // Additional spot 2: from any layer (weighted toward skill challenge)
-        let additional2_seed = seed >> 40;
-        let layer_choice = additional2_seed % 6;
-        
-        if layer_choice < 2 {
-            // Layer 3 (rare second L3 spot for high-skill play)
-            let l3_offset = ((additional2_seed >> 4) % layer3_count) as u8;
-            let pick = LAYER3_START + l3_offset;
-            if !Self::contains_spot(&spots, count, pick) {
-                spots[count as usize] = pick;
-                count += 1;
-            }
-        } else if layer_choice < 4 {
-            // Layer 2
-            let l2_offset = ((additional2_seed >> 4) % layer2_count) as u8;
-            let pick = LAYER2_START + l2_offset;
-            if !Self::contains_spot(&spots, count, pick) {
-                spots[count as usize] = pick;
-                count += 1;
-            } else {
-                let fallback = LAYER2_START + ((l2_offset + 2) % layer2_count as u8);
-                if !Self::contains_spot(&spots, count, fallback) {
-                    spots[count as usize] = fallback;
-                    count += 1;
-                }
-            }
-        } else {
-            // Layer 1
-            let l1_offset = ((additional2_seed >> 4) % layer1_count) as u8;
-            let pick = LAYER1_START + l1_offset;
-            if !Self::contains_spot(&spots, count, pick) {
-                spots[count as usize] = pick;
-                count += 1;
-            } else {
-                let fallback = LAYER1_START + ((l1_offset + 2) % layer1_count as u8);
-                if !Self::contains_spot(&spots, count, fallback) {
-                    spots[count as usize] = fallback;
-                    count += 1;
-                }
+        demands
+    }
+    
+    /// Get the variant demanded by a currently available customer, if any
+    pub fn demanded_variant(&self, customer_index: u8) -> Option<u8> {
+        for i in 0..self.active_count as usize {
+            if i < MAX_DELIVERY_SPOTS && self.available_customers[i] == customer_index {
+                let demand = self.demanded_variants[i];
+                return if demand == Self::NO_VARIANT_DEMAND { None } else { Some(demand) };
             }
         }
+        None
+    }
+    
+    /// Reputation modifier for serving a customer with a variant demand
+    /// - No demand: 0
+    /// - Exact variant: VARIANT_DEMAND_BONUS
+    /// - Wrong (or unknown) variant: VARIANT_DEMAND_PENALTY
+    pub fn variant_demand_modifier(demanded: Option<u8>, served: Option<u8>) -> i32 {
+        match demanded {
+            None => 0,
+            Some(d) if served == Some(d) => VARIANT_DEMAND_BONUS,
+            Some(_) => VARIANT_DEMAND_PENALTY,
+        }
+    }
+    
+    /// Deterministically pick a rush customer for the bucket containing current_ts
+    /// 
+    /// About 1 in RUSH_BUCKET_ODDS buckets gets one. The rush customer is never
+    /// already on the board and expires RUSH_WINDOW_SECONDS after the bucket
+    /// starts (not after the refresh), so late refreshes don't extend it.
+    /// Returns (INVALID_INDEX, 0) when this bucket has no rush.
+    pub fn compute_rush_customer(
+        seed: u64,
+        spots: &[u8; MAX_DELIVERY_SPOTS],
+        count: u8,
+        current_ts: i64,
+    ) -> (u8, i64) {
+        let mut hash = seed ^ 0xc2b2ae3d27d4eb4f;
+        hash = hash.wrapping_mul(0x7fb5d329728ea185);
+        hash ^= hash >> 31;
+        
+        if !hash.is_multiple_of(RUSH_BUCKET_ODDS) {
+            return (Self::INVALID_INDEX, 0);
+        }
+        
+        // Walk from a seeded start until we find a customer not on the board
+        let start = ((hash >> 8) % 23) as u8;
+        let mut pick = start;
+        while Self::contains_spot(spots, count, pick) {
+            pick = (pick + 1) % 23;
+        }
         
-        (spots, count)
+        let bucket_start = current_ts - current_ts.rem_euclid(DELIVERY_ROTATION_INTERVAL);
+        (pick, bucket_start.saturating_add(RUSH_WINDOW_SECONDS))
+    }
+    
+    /// Check if customer_index is the rush customer and still inside its window
+    pub fn is_rush_active(&self, customer_index: u8, current_ts: i64) -> bool {
+        self.rush_customer != Self::INVALID_INDEX
+            && self.rush_customer == customer_index
+            && current_ts < self.rush_expires_ts
+    }
+    
+    /// Consume the rush customer after it has been served
+    pub fn clear_rush(&mut self) {
+        self.rush_customer = Self::INVALID_INDEX;
+        self.rush_expires_ts = 0;
     }
     
     /// Helper: check if a spot is already in the array
@@ -219,27 +418,74 @@ impl MatchDeliveryState {
         false
     }
     
-    /// Check if refresh is needed (60s have passed since last update)
+    /// Keeper reward the pool can pay without dropping below rent exemption
+    /// Returns 0 when the pool is underfunded (the refresh still succeeds)
+    pub fn keeper_reward_payable(pool_lamports: u64, rent_exempt_minimum: u64) -> u64 {
+        if pool_lamports >= rent_exempt_minimum.saturating_add(KEEPER_REFRESH_REWARD_LAMPORTS) {
+            KEEPER_REFRESH_REWARD_LAMPORTS
+        } else {
+            0
+        }
+    }
+    
+    /// Replace the board with the selection for the bucket containing current_ts
+    /// 
+    /// The seed comes from current_ts itself, so after a long gap (nobody
+    /// refreshed for several intervals) the board jumps straight to the
+    /// current bucket's deterministic selection, never an intermediate one.
+    /// Returns the number of rotation buckets skipped since last_update_ts.
+    pub fn rotate(&mut self, current_ts: i64) -> u64 {
+        let missed = Self::get_rotation_bucket(current_ts)
+            .saturating_sub(Self::get_rotation_bucket(self.last_update_ts))
+            .saturating_sub(1);
+        
+        let seed = Self::compute_delivery_seed(self.algo_version, self.match_id, current_ts);
+        let (spots, count) =
+            Self::select_delivery_spots(self.algo_version, seed, &self.layer_config, self.extra_spots);
+        let (rush_customer, rush_expires_ts) = Self::compute_rush_customer(seed, &spots, count, current_ts);
+        
+        self.available_customers = spots;
+        self.active_count = count;
+        self.demanded_variants = Self::compute_variant_demands(seed, count);
+        self.rush_customer = rush_customer;
+        self.rush_expires_ts = rush_expires_ts;
+        self.last_update_ts = current_ts;
+        self.last_processed_bucket = Self::get_rotation_bucket(current_ts);
+        
+        missed
+    }
+    
+    /// Check if refresh is needed (60s have passed since last update and
+    /// current_ts is in a bucket that hasn't been processed yet)
     pub fn needs_refresh(&self, current_ts: i64) -> bool {
-        current_ts >= self.last_update_ts + DELIVERY_ROTATION_INTERVAL
+        current_ts >= self.last_update_ts.saturating_add(DELIVERY_ROTATION_INTERVAL)
+            && Self::get_rotation_bucket(current_ts) > self.last_processed_bucket
+    }
+    
+    /// Guard for refresh_delivery_slots: at most one rotation per bucket
+    pub fn check_refresh(&self, current_ts: i64) -> Result<()> {
+        require!(self.needs_refresh(current_ts), DroogError::DeliveryRotationTooSoon);
+        Ok(())
+    }
+    
+    /// Check if a refresh at current_ts is prompt (within the window after it became due)
+    /// Used to reward participants who keep their own board fresh
+    pub fn is_prompt_refresh(&self, current_ts: i64) -> bool {
+        current_ts < self.last_update_ts
+            .saturating_add(DELIVERY_ROTATION_INTERVAL + REFRESH_PROMPT_WINDOW_SECONDS)
     }
     
     /// Get the current rotation bucket number
-    /// Useful for client sync: bucket = ts / 60
+    /// Useful for client sync: bucket = floor(ts / 60)
     pub fn get_rotation_bucket(current_ts: i64) -> u64 {
-        (current_ts / DELIVERY_ROTATION_INTERVAL) as u64
+        deterministic::rotation_bucket(current_ts)
     }
     
-    /// Derive layer from customer index (mirrors MatchState::layer_from_index)
-    /// This is duplicated here for locality but uses the same canonical mapping
+    /// Derive layer from customer index (default layout; per-match layouts go
+    /// through layer_config). Delegates to the canonical
+    /// MatchState::layer_from_index so reputation and delivery can't drift.
     pub fn layer_from_index(customer_index: u8) -> u8 {
-        if customer_index <= LAYER3_END {
-            3  // Inner Core
-        } else if customer_index <= LAYER2_END {
-            2  // Middle Ring
-        } else {
-            1  // Outer Ring
-        }
+        MatchState::layer_from_index(customer_index)
     }
     
     /// Get count of available spots per layer for the current state
@@ -253,7 +499,7 @@ impl MatchDeliveryState {
             if i < MAX_DELIVERY_SPOTS {
                 let idx = self.available_customers[i];
                 if idx != Self::INVALID_INDEX {
-                    match Self::layer_from_index(idx) {
+                    match self.layer_config.layer_from_index(idx) {
                         1 => l1 += 1,
                         2 => l2 += 1,
                         3 => l3 += 1,
@@ -268,8 +514,219 @@ impl MatchDeliveryState {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::deterministic::{ALGO_VERSION_LEGACY, CURRENT_ALGO_VERSION};
+    
+    pub(crate) fn test_delivery_state() -> MatchDeliveryState {
+        MatchDeliveryState {
+            match_id: 1,
+            match_id_hash: [1u8; 32],
+            last_update_ts: 1_000,
+            available_customers: [MatchDeliveryState::INVALID_INDEX; MAX_DELIVERY_SPOTS],
+            active_count: 0,
+            bump: 255,
+            demanded_variants: [MatchDeliveryState::NO_VARIANT_DEMAND; MAX_DELIVERY_SPOTS],
+            rush_customer: MatchDeliveryState::INVALID_INDEX,
+            rush_expires_ts: 0,
+            algo_version: CURRENT_ALGO_VERSION,
+            layer_config: LayerConfig::DEFAULT,
+            extra_spots: DEFAULT_EXTRA_DELIVERY_SPOTS,
+            rotation_count: 0,
+            last_processed_bucket: MatchDeliveryState::get_rotation_bucket(1_000),
+        }
+    }
+    
+    #[test]
+    fn test_refresh_once_per_bucket() {
+        let mut state = test_delivery_state();
+        
+        // First refresh in bucket 18 ([1080, 1140)) goes through
+        let ts = 1_080;
+        state.check_refresh(ts).unwrap();
+        state.rotate(ts);
+        assert_eq!(state.last_processed_bucket, 18);
+        
+        // A second refresh landing in the same bucket is rejected
+        assert_eq!(
+            state.check_refresh(ts + 30).unwrap_err(),
+            DroogError::DeliveryRotationTooSoon.into()
+        );
+        
+        // Even if the timestamp guard alone would pass
+        state.last_update_ts = ts - DELIVERY_ROTATION_INTERVAL;
+        assert_eq!(
+            state.check_refresh(ts + 1).unwrap_err(),
+            DroogError::DeliveryRotationTooSoon.into()
+        );
+        
+        // The next bucket is fine
+        state.check_refresh(1_140).unwrap();
+    }
+    
+    #[test]
+    fn test_stale_board_stays_servable_until_refreshed() {
+        let mut state = test_delivery_state();
+        state.rotate(1_000);
+        let board_bucket = state.last_processed_bucket;
+        let customer = state.available_customers[0];
+        let demand = state.demanded_variant(customer);
+        
+        // Several intervals later nobody has refreshed: the board is overdue
+        let ts = 1_000 + 3 * DELIVERY_ROTATION_INTERVAL + 5;
+        assert!(state.needs_refresh(ts));
+        assert!(MatchDeliveryState::get_rotation_bucket(ts) > board_bucket);
+        
+        // ...but a sale against it goes through exactly as before
+        assert!(state.is_customer_available(customer));
+        assert_eq!(state.demanded_variant(customer), demand);
+        assert!(state.remove_customer(customer));
+        assert!(!state.is_customer_available(customer));
+        assert!(!state.is_rush_active(state.rush_customer, ts));
+        assert_eq!(state.last_processed_bucket, board_bucket);
+        
+        // The next refresh replaces it with the current bucket's board
+        state.check_refresh(ts).unwrap();
+        assert_eq!(state.rotate(ts), 2);
+        assert_eq!(state.last_processed_bucket, MatchDeliveryState::get_rotation_bucket(ts));
+        assert!(!state.needs_refresh(ts));
+    }
+    
+    #[test]
+    fn test_keeper_reward_payable() {
+        let rent = 890_880;
+        assert_eq!(MatchDeliveryState::keeper_reward_payable(0, rent), 0);
+        assert_eq!(
+            MatchDeliveryState::keeper_reward_payable(rent + KEEPER_REFRESH_REWARD_LAMPORTS - 1, rent),
+            0
+        );
+        assert_eq!(
+            MatchDeliveryState::keeper_reward_payable(rent + KEEPER_REFRESH_REWARD_LAMPORTS, rent),
+            KEEPER_REFRESH_REWARD_LAMPORTS
+        );
+    }
+    
+    #[test]
+    fn test_custom_layer_config() {
+        // Bigger inner core, smaller outer ring: 5 / 8 / 10
+        let layers = LayerConfig { layer3_count: 5, layer2_count: 8, layer1_count: 10 };
+        assert!(layers.validate().is_ok());
+        
+        assert_eq!(layers.layer_from_index(0), 3);
+        assert_eq!(layers.layer_from_index(4), 3);
+        assert_eq!(layers.layer_from_index(5), 2);
+        assert_eq!(layers.layer_from_index(12), 2);
+        assert_eq!(layers.layer_from_index(13), 1);
+        assert_eq!(layers.layer_from_index(22), 1);
+        
+        // Default config reproduces the canonical constants
+        for i in 0..CUSTOMER_ROSTER_SIZE {
+            assert_eq!(
+                LayerConfig::DEFAULT.layer_from_index(i),
+                MatchDeliveryState::layer_from_index(i)
+            );
+        }
+        
+        // Selection honors the custom ranges: one per layer, no duplicates
+        for seed in [0u64, 1, 12345, 0xDEADBEEF, u64::MAX] {
+            let (spots, count) =
+                MatchDeliveryState::select_delivery_spots(CURRENT_ALGO_VERSION, seed, &layers, DEFAULT_EXTRA_DELIVERY_SPOTS);
+            let valid = &spots[..count as usize];
+            for layer in 1..=3u8 {
+                assert!(valid.iter().any(|&s| layers.layer_from_index(s) == layer));
+            }
+            for (i, a) in valid.iter().enumerate() {
+                assert!(*a < CUSTOMER_ROSTER_SIZE);
+                assert!(!valid[i + 1..].contains(a));
+            }
+            
+            let state = MatchDeliveryState {
+                available_customers: spots,
+                active_count: count,
+                layer_config: layers,
+                ..test_delivery_state()
+            };
+            let (l1, l2, l3) = state.get_layer_distribution();
+            assert_eq!((l1 + l2 + l3), count);
+            assert!(l3 >= 1);
+        }
+        
+        // Counts must sum to the roster and leave room for selection
+        assert!(LayerConfig { layer3_count: 3, layer2_count: 8, layer1_count: 11 }.validate().is_err());
+        assert!(LayerConfig { layer3_count: 0, layer2_count: 11, layer1_count: 12 }.validate().is_err());
+        assert!(LayerConfig { layer3_count: 20, layer2_count: 1, layer1_count: 2 }.validate().is_err());
+    }
+    
+    #[test]
+    fn test_delivery_double_init_guard() {
+        let state = test_delivery_state();
+        
+        // Zeroed account (first init) proceeds
+        let fresh = MatchDeliveryState { match_id: 0, match_id_hash: [0u8; 32], ..test_delivery_state() };
+        assert!(!fresh.check_reinit(&[1u8; 32], 1).unwrap());
+        
+        // Retry for the same match is an idempotent no-op; mismatch is rejected
+        assert!(state.check_reinit(&[1u8; 32], 1).unwrap());
+        assert_eq!(
+            state.check_reinit(&[2u8; 32], 1).unwrap_err(),
+            DroogError::AlreadyInitialized.into()
+        );
+    }
+    
+    #[test]
+    fn test_delivery_pda_hash_seeded() {
+        // Two hashes sharing their first 8 bytes (and thus their prefix-derived match_id)
+        let mut hash_a = [0xABu8; 32];
+        let mut hash_b = [0xABu8; 32];
+        hash_a[31] = 1;
+        hash_b[31] = 2;
+        let id_a = u64::from_le_bytes(hash_a[..8].try_into().unwrap());
+        let id_b = u64::from_le_bytes(hash_b[..8].try_into().unwrap());
+        assert_eq!(id_a, id_b);
+        
+        // Old scheme: same PDA for both matches
+        let old_a = Pubkey::find_program_address(&[b"delivery", id_a.to_le_bytes().as_ref()], &crate::ID);
+        let old_b = Pubkey::find_program_address(&[b"delivery", id_b.to_le_bytes().as_ref()], &crate::ID);
+        assert_eq!(old_a, old_b);
+        
+        // New scheme: distinct PDAs
+        assert_ne!(
+            MatchDeliveryState::derive_pda(&hash_a).0,
+            MatchDeliveryState::derive_pda(&hash_b).0
+        );
+    }
+    
+    #[test]
+    fn test_rush_customer_window() {
+        let spots = [0, 3, 11, 12, 4, MatchDeliveryState::INVALID_INDEX, MatchDeliveryState::INVALID_INDEX];
+        
+        // Find a seed that spawns a rush and check it's deterministic and off-board
+        let seed = (0..64u64)
+            .find(|&s| MatchDeliveryState::compute_rush_customer(s, &spots, 5, 1_020).0 != MatchDeliveryState::INVALID_INDEX)
+            .expect("some bucket should spawn a rush");
+        let (rush, expires) = MatchDeliveryState::compute_rush_customer(seed, &spots, 5, 1_020);
+        assert_eq!((rush, expires), MatchDeliveryState::compute_rush_customer(seed, &spots, 5, 1_020));
+        assert!(rush < 23 && !spots.contains(&rush));
+        
+        // Expiry is anchored to the bucket start (1_020 is in bucket [1_020, 1_080))
+        assert_eq!(expires, 1_020 + RUSH_WINDOW_SECONDS);
+        assert_eq!(MatchDeliveryState::compute_rush_customer(seed, &spots, 5, 1_079).1, expires);
+        
+        let mut state = MatchDeliveryState {
+            rush_customer: rush,
+            rush_expires_ts: expires,
+            ..test_delivery_state()
+        };
+        
+        // Servable inside the window, not after
+        assert!(state.is_rush_active(rush, expires - 1));
+        assert!(!state.is_rush_active(rush, expires));
+        assert!(!state.is_rush_active((rush + 1) % 23, expires - 1));
+        
+        // Consumed once served
+        state.clear_rush();
+        assert!(!state.is_rush_active(rush, expires - 1));
+    }
     
     #[test]
     fn test_layer_from_index() {
@@ -287,14 +744,65 @@ mod tests {
         assert_eq!(MatchDeliveryState::layer_from_index(22), 1);
     }
     
+    #[test]
+    fn test_layer_from_index_matches_canonical() {
+        // Every roster index maps identically through both entry points
+        for i in 0..=22u8 {
+            assert_eq!(
+                MatchDeliveryState::layer_from_index(i),
+                MatchState::layer_from_index(i),
+                "customer {i}"
+            );
+        }
+    }
+    
+    #[test]
+    fn test_board_sizes_minimum_to_max() {
+        assert!(MatchDeliveryState::validate_extra_spots(0).is_ok());
+        assert!(MatchDeliveryState::validate_extra_spots(MAX_EXTRA_DELIVERY_SPOTS).is_ok());
+        assert_eq!(
+            MatchDeliveryState::validate_extra_spots(MAX_EXTRA_DELIVERY_SPOTS + 1).unwrap_err(),
+            DroogError::InvalidDeliverySpotCount.into()
+        );
+        
+        // Minimums-only board: exactly one customer per layer
+        let (spots, count) = MatchDeliveryState::select_delivery_spots(CURRENT_ALGO_VERSION, 7, &LayerConfig::DEFAULT, 0);
+        let mut state = MatchDeliveryState {
+            available_customers: spots,
+            active_count: count,
+            extra_spots: 0,
+            ..test_delivery_state()
+        };
+        assert_eq!(count, MIN_DELIVERY_SPOTS as u8);
+        assert_eq!(state.get_layer_distribution(), (1, 1, 1));
+        
+        // Full board: every spot up to MAX_DELIVERY_SPOTS is servable and removable
+        let (spots, count) =
+            MatchDeliveryState::select_delivery_spots(CURRENT_ALGO_VERSION, 7, &LayerConfig::DEFAULT, MAX_EXTRA_DELIVERY_SPOTS);
+        assert_eq!(count as usize, MAX_DELIVERY_SPOTS);
+        state.available_customers = spots;
+        state.active_count = count;
+        state.extra_spots = MAX_EXTRA_DELIVERY_SPOTS;
+        let last = spots[MAX_DELIVERY_SPOTS - 1];
+        assert!(state.is_customer_available(last));
+        assert!(state.remove_customer(last));
+        assert!(!state.is_customer_available(last));
+        let (l1, l2, l3) = state.get_layer_distribution();
+        assert_eq!(l1 + l2 + l3, count - 1);
+    }
+    
     #[test]
     fn test_select_delivery_spots_layer_guarantee() {
-        // Test multiple seeds to ensure layer guarantees hold
-        for seed in [0, 1, 100, 999999, u64::MAX] {
-            let (spots, count) = MatchDeliveryState::select_delivery_spots(seed);
+        // Test multiple seeds and every board size to ensure layer guarantees hold
+        for (seed, extra) in [0, 1, 100, 999999, u64::MAX]
+            .into_iter()
+            .flat_map(|seed| (0..=MAX_EXTRA_DELIVERY_SPOTS).map(move |extra| (seed, extra)))
+        {
+            let (spots, count) =
+                MatchDeliveryState::select_delivery_spots(CURRENT_ALGO_VERSION, seed, &LayerConfig::DEFAULT, extra);
             
-            // Must have at least 3 spots (one per layer)
-            assert!(count >= 3, "Expected at least 3 spots, got {}", count);
+            // Always the full 3 + extra board (one per layer first)
+            assert_eq!(count, MIN_DELIVERY_SPOTS as u8 + extra);
             
             // Check layer distribution
             let mut has_l1 = false;
@@ -317,19 +825,137 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_variant_demands() {
+        for seed in [0, 1, 100, 999999, u64::MAX] {
+            let demands = MatchDeliveryState::compute_variant_demands(seed, 5);
+            assert_eq!(demands, MatchDeliveryState::compute_variant_demands(seed, 5));
+            for d in demands {
+                assert!(d == MatchDeliveryState::NO_VARIANT_DEMAND || d < VARIANT_COUNT);
+            }
+        }
+        
+        let mut state = MatchDeliveryState {
+            available_customers: [0, 3, 11, 255, 255, 255, 255],
+            active_count: 3,
+            demanded_variants: [2, MatchDeliveryState::NO_VARIANT_DEMAND, 0, 255, 255, 255, 255],
+            ..test_delivery_state()
+        };
+        
+        assert_eq!(state.demanded_variant(0), Some(2));
+        assert_eq!(state.demanded_variant(3), None);
+        assert_eq!(state.demanded_variant(11), Some(0));
+        
+        // Exact match earns the bonus, mismatch the penalty, no demand is neutral
+        assert_eq!(MatchDeliveryState::variant_demand_modifier(Some(2), Some(2)), VARIANT_DEMAND_BONUS);
+        assert_eq!(MatchDeliveryState::variant_demand_modifier(Some(2), Some(1)), VARIANT_DEMAND_PENALTY);
+        assert_eq!(MatchDeliveryState::variant_demand_modifier(Some(2), None), VARIANT_DEMAND_PENALTY);
+        assert_eq!(MatchDeliveryState::variant_demand_modifier(None, Some(1)), 0);
+        
+        // Serving clears the demand along with the spot
+        state.remove_customer(0);
+        assert_eq!(state.demanded_variants[0], MatchDeliveryState::NO_VARIANT_DEMAND);
+    }
+    
+    #[test]
+    fn test_rotate_after_long_gap_uses_current_bucket() {
+        let mut state = test_delivery_state();
+        state.rotate(1_000);
+        assert_eq!(state.last_update_ts, 1_000);
+        
+        // Nobody refreshed for a long gap: 1_000 is bucket 16, 1_380 is bucket 23,
+        // so buckets 17-22 were never shown
+        let current_ts = 1_000 + 6 * DELIVERY_ROTATION_INTERVAL + 20;
+        let missed = state.rotate(current_ts);
+        assert_eq!(missed, 6);
+        assert_eq!(state.last_update_ts, current_ts);
+        
+        // Board is exactly the current bucket's deterministic selection...
+        let seed = MatchDeliveryState::compute_delivery_seed(state.algo_version, state.match_id, current_ts);
+        let (spots, count) = MatchDeliveryState::select_delivery_spots(state.algo_version, seed, &state.layer_config, state.extra_spots);
+        assert_eq!((state.available_customers, state.active_count), (spots, count));
+        assert_eq!(state.demanded_variants, MatchDeliveryState::compute_variant_demands(seed, count));
+        assert_eq!(
+            (state.rush_customer, state.rush_expires_ts),
+            MatchDeliveryState::compute_rush_customer(seed, &spots, count, current_ts)
+        );
+        
+        // ...and not what an intermediate bucket would have shown
+        let intermediate = (1..=6)
+            .map(|k| {
+                let ts = 1_000 + k * DELIVERY_ROTATION_INTERVAL;
+                let seed = MatchDeliveryState::compute_delivery_seed(state.algo_version, state.match_id, ts);
+                MatchDeliveryState::select_delivery_spots(state.algo_version, seed, &state.layer_config, state.extra_spots).0
+            })
+            .collect::<Vec<_>>();
+        assert_ne!(intermediate.last(), Some(&state.available_customers));
+        
+        // Any time inside the same bucket yields the same board
+        let mut same_bucket = state.clone();
+        same_bucket.rotate(current_ts - 20 + DELIVERY_ROTATION_INTERVAL - 1);
+        assert_eq!(same_bucket.available_customers, state.available_customers);
+        
+        // A refresh right at the next boundary misses nothing
+        assert_eq!(state.rotate(current_ts - 20 + DELIVERY_ROTATION_INTERVAL), 0);
+    }
+    
+    #[test]
+    fn test_is_prompt_refresh() {
+        let state = test_delivery_state();
+        
+        let due = 1_000 + DELIVERY_ROTATION_INTERVAL;
+        assert!(state.is_prompt_refresh(due));
+        assert!(state.is_prompt_refresh(due + REFRESH_PROMPT_WINDOW_SECONDS - 1));
+        assert!(!state.is_prompt_refresh(due + REFRESH_PROMPT_WINDOW_SECONDS));
+    }
+    
     #[test]
     fn test_deterministic_seed() {
         // Same inputs must produce same output
-        let seed1 = MatchDeliveryState::compute_delivery_seed(12345, 1000);
-        let seed2 = MatchDeliveryState::compute_delivery_seed(12345, 1000);
+        let seed1 = MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 12345, 1000);
+        let seed2 = MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 12345, 1000);
         assert_eq!(seed1, seed2);
         
         // Different timestamps in same bucket produce same seed
-        let seed3 = MatchDeliveryState::compute_delivery_seed(12345, 1059); // Same bucket as 1000
+        let seed3 = MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 12345, 1019); // Same bucket as 1000 ([960, 1020))
         assert_eq!(seed1, seed3);
         
         // Different bucket produces different seed
-        let seed4 = MatchDeliveryState::compute_delivery_seed(12345, 1060); // Next bucket
+        let seed4 = MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 12345, 1020); // Next bucket
         assert_ne!(seed1, seed4);
     }
+    
+    #[test]
+    fn test_delivery_seed_versions() {
+        
+        // Each version is deterministic on its own
+        let v0 = MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 12345, 1020);
+        let v1 = MatchDeliveryState::compute_delivery_seed(CURRENT_ALGO_VERSION, 12345, 1020);
+        assert_eq!(v0, MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 12345, 1020));
+        assert_eq!(v1, MatchDeliveryState::compute_delivery_seed(CURRENT_ALGO_VERSION, 12345, 1020));
+        
+        // ...and the versions disagree for the same inputs
+        assert_ne!(v0, v1);
+        
+        // v0 pinned value guards replay of historical matches
+        assert_eq!(v0, {
+            let mut h: u64 = 12345 ^ 17;
+            h = h.wrapping_mul(0x517cc1b727220a95);
+            h ^= h >> 32;
+            h = h.wrapping_mul(0x7fb5d329728ea185);
+            h ^ (h >> 27)
+        });
+        
+        // v0 XOR collision (match_id ^ bucket) is gone in v1
+        let bucket = 1020 / DELIVERY_ROTATION_INTERVAL;
+        let ts_b = (bucket ^ 1) * DELIVERY_ROTATION_INTERVAL;
+        assert_eq!(
+            MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 0, 1020),
+            MatchDeliveryState::compute_delivery_seed(ALGO_VERSION_LEGACY, 1, ts_b)
+        );
+        assert_ne!(
+            MatchDeliveryState::compute_delivery_seed(CURRENT_ALGO_VERSION, 0, 1020),
+            MatchDeliveryState::compute_delivery_seed(CURRENT_ALGO_VERSION, 1, ts_b)
+        );
+    }
 }
@@ -0,0 +1,329 @@
+use anchor_lang::prelude::*;
+use crate::state::stake_state::{LifecycleAction, MatchStakeState};
+use crate::errors::DroogError;
+
+/// PDA seed for a match's DoubleOrNothingOffer
+pub const DOUBLE_OR_NOTHING_SEED: &[u8] = b"double";
+
+/// Where a double-or-nothing offer stands
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ContinuationStatus {
+    /// Waiting on the loser
+    #[default]
+    Offered,
+    /// Loser accepted; the pot moved into the continuation match
+    Accepted,
+    /// Loser declined; the pending settlement pays the winner as usual
+    Declined,
+}
+
+/// Double-or-nothing continuation offer PDA
+/// Seeds: ["double", match_id_hash] (the settling match)
+/// 
+/// Opened by the recorded winner of a delayed-settlement match while its pot
+/// is still in escrow (Settling). If the loser accepts once the challenge
+/// window has closed but before the settlement executes, the pot rolls into a new, already Active match where the loser
+/// stakes the same amount again, so the pot doubles; the winner of that match
+/// takes everything. The account stays behind as the record of the outcome,
+/// so a match can be offered at most once.
+#[account]
+pub struct DoubleOrNothingOffer {
+    /// Settling match the offer is for
+    pub match_id_hash: [u8; 32],
+    
+    /// Recorded winner of that match (made the offer)
+    pub winner: Pubkey,
+    
+    /// The other player (accepts or declines)
+    pub loser: Pubkey,
+    
+    /// Pending payout at stake; the loser must match it to accept
+    pub pot: u64,
+    
+    /// Offered, Accepted or Declined
+    pub status: ContinuationStatus,
+    
+    /// match_id_hash of the continuation match (zero until accepted)
+    pub continuation_match_id_hash: [u8; 32],
+    
+    /// Offer timestamp
+    pub offered_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl DoubleOrNothingOffer {
+    /// 8 (discriminator) + 32 (match_id_hash) + 32 (winner) + 32 (loser) + 8 (pot)
+    /// + 1 (status) + 32 (continuation_match_id_hash) + 8 (offered_at) + 1 (bump) = 154 bytes
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 32 + 8 + 1;
+    
+    /// Open the offer for a settling match; only the recorded winner of a
+    /// decided match may offer, and their share must be a valid stake for the
+    /// continuation
+    pub fn open(
+        &mut self,
+        stake_state: &MatchStakeState,
+        winner: &Pubkey,
+        offered_at: i64,
+        bump: u8,
+    ) -> Result<()> {
+        Self::check_source(stake_state)?;
+        let pending = &stake_state.pending_settlement;
+        require!(*winner == pending.winner && !pending.is_draw, DroogError::InvalidPlayer);
+        MatchStakeState::validate_stake_amount(pending.amount)?;
+        
+        self.match_id_hash = stake_state.match_id_hash;
+        self.winner = pending.winner;
+        self.loser = if pending.winner == stake_state.player_a {
+            stake_state.player_b
+        } else {
+            stake_state.player_a
+        };
+        self.pot = pending.amount;
+        self.status = ContinuationStatus::Offered;
+        self.continuation_match_id_hash = [0; 32];
+        self.offered_at = offered_at;
+        self.bump = bump;
+        Ok(())
+    }
+    
+    /// The pot is still in escrow: the source match is Settling and not
+    /// frozen (a frozen settlement belongs to the dispute admin)
+    pub fn check_source(stake_state: &MatchStakeState) -> Result<()> {
+        stake_state.check_lifecycle(LifecycleAction::DoubleOrNothing)?;
+        require!(!stake_state.pending_settlement.frozen, DroogError::SettlementFrozen);
+        Ok(())
+    }
+    
+    /// Accept into the continuation match; returns the loser's stake (the pot)
+    /// Only once the challenge window has closed (the result can no longer be
+    /// frozen or reversed), only for the pot the loser agreed to match, and
+    /// only while the offer's winner is still the recorded winner (an offer
+    /// made before resolve_dispute overturned the result is stale)
+    pub fn accept(
+        &mut self,
+        stake_state: &MatchStakeState,
+        continuation_match_id_hash: [u8; 32],
+        expected_pot: u64,
+        current_ts: i64,
+    ) -> Result<u64> {
+        require!(self.status == ContinuationStatus::Offered, DroogError::DoubleOrNothingNotOffered);
+        Self::check_source(stake_state)?;
+        require!(self.winner == stake_state.pending_settlement.winner, DroogError::InvalidPlayer);
+        require!(
+            current_ts >= stake_state.pending_settlement.executable_at_ts,
+            DroogError::SettlementWindowOpen
+        );
+        require!(expected_pot == self.pot, DroogError::StakeAmountChanged);
+        self.status = ContinuationStatus::Accepted;
+        self.continuation_match_id_hash = continuation_match_id_hash;
+        Ok(self.pot)
+    }
+    
+    /// Decline; the source match settles to the winner as usual
+    pub fn decline(&mut self) -> Result<()> {
+        require!(self.status == ContinuationStatus::Offered, DroogError::DoubleOrNothingNotOffered);
+        self.status = ContinuationStatus::Declined;
+        Ok(())
+    }
+    
+    /// Pot of the continuation match: the carried pot plus the loser's equal stake
+    pub fn continuation_pot(&self) -> Result<u64> {
+        self.pot.checked_mul(2).ok_or_else(|| DroogError::CalculationOverflow.into())
+    }
+    
+    /// Derive the offer PDA for a match
+    pub fn derive_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+        crate::pda::derive_double_or_nothing_pda(match_id_hash)
+    }
+}
+
+#[event]
+pub struct DoubleOrNothingOfferedEvent {
+    pub match_id: u64,
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+    pub pot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DoubleOrNothingAcceptedEvent {
+    /// Settling match whose pot was carried over
+    pub previous_match_id: u64,
+    /// The continuation match (already Active)
+    pub match_id: u64,
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+    /// Doubled pot now in the continuation's escrow
+    pub pot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DoubleOrNothingDeclinedEvent {
+    pub match_id: u64,
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::stake_state::tests::test_stake;
+    use crate::state::{MatchState, MatchStatus, PendingSettlement, STAKE_AMOUNT};
+    
+    fn settling_stake() -> MatchStakeState {
+        let mut stake = MatchStakeState { delayed_settlement: true, ..test_stake() };
+        stake.status = MatchStatus::Active;
+        stake.player_b_escrowed = STAKE_AMOUNT;
+        let winner = stake.player_b;
+        stake.record_pending_settlement(PendingSettlement { winner, amount: 1_800_000, ..Default::default() }, 1_000);
+        stake
+    }
+    
+    fn empty_offer() -> DoubleOrNothingOffer {
+        DoubleOrNothingOffer {
+            match_id_hash: [0u8; 32],
+            winner: Pubkey::default(),
+            loser: Pubkey::default(),
+            pot: 0,
+            status: ContinuationStatus::Offered,
+            continuation_match_id_hash: [0u8; 32],
+            offered_at: 0,
+            bump: 0,
+        }
+    }
+    
+    /// First moment the offer can be accepted (settling_stake's window closes)
+    fn window_end(stake: &MatchStakeState) -> i64 {
+        stake.pending_settlement.executable_at_ts
+    }
+    
+    fn offered(stake: &MatchStakeState) -> DoubleOrNothingOffer {
+        let mut offer = empty_offer();
+        offer.open(stake, &stake.player_b, 1_010, 254).unwrap();
+        offer
+    }
+    
+    #[test]
+    fn test_only_the_settling_winner_can_offer() {
+        let stake = settling_stake();
+        let offer = offered(&stake);
+        assert_eq!(offer.status, ContinuationStatus::Offered);
+        assert_eq!((offer.winner, offer.loser), (stake.player_b, stake.player_a));
+        assert_eq!((offer.pot, offer.offered_at, offer.bump), (1_800_000, 1_010, 254));
+        
+        // The loser can't offer, nor can anyone once the pot has left escrow
+        let mut offer = empty_offer();
+        assert_eq!(
+            offer.open(&stake, &stake.player_a, 1_010, 254).unwrap_err(),
+            DroogError::InvalidPlayer.into()
+        );
+        let paid = MatchStakeState { status: MatchStatus::Finalized, ..stake.clone() };
+        assert_eq!(
+            offer.open(&paid, &stake.player_b, 1_010, 254).unwrap_err(),
+            DroogError::SettlementNotPending.into()
+        );
+        let mut frozen = stake.clone();
+        frozen.freeze_settlement(1_001).unwrap();
+        assert_eq!(
+            offer.open(&frozen, &stake.player_b, 1_010, 254).unwrap_err(),
+            DroogError::SettlementFrozen.into()
+        );
+        
+        // A drawn match has no winner to offer
+        let mut drawn = stake.clone();
+        drawn.pending_settlement.is_draw = true;
+        assert_eq!(
+            offer.open(&drawn, &stake.player_b, 1_010, 254).unwrap_err(),
+            DroogError::InvalidPlayer.into()
+        );
+    }
+    
+    #[test]
+    fn test_accept_doubles_the_pot() {
+        let source = settling_stake();
+        let mut offer = offered(&source);
+        let continuation_hash = [5u8; 32];
+        
+        // Not while the result can still be challenged, nor for another pot
+        assert_eq!(
+            offer.accept(&source, continuation_hash, offer.pot, window_end(&source) - 1).unwrap_err(),
+            DroogError::SettlementWindowOpen.into()
+        );
+        assert_eq!(
+            offer.accept(&source, continuation_hash, offer.pot - 1, window_end(&source)).unwrap_err(),
+            DroogError::StakeAmountChanged.into()
+        );
+        assert_eq!(offer.status, ContinuationStatus::Offered);
+        
+        let loser_stake = offer.accept(&source, continuation_hash, offer.pot, window_end(&source)).unwrap();
+        assert_eq!(loser_stake, offer.pot);
+        assert_eq!(offer.status, ContinuationStatus::Accepted);
+        assert_eq!(offer.continuation_match_id_hash, continuation_hash);
+        
+        // The continuation starts Active with both sides escrowing the pot
+        let match_state = MatchState { match_id_hash: continuation_hash, ..test_match() };
+        let mut stake = test_stake();
+        stake.initialize_continuation(&match_state, &source, loser_stake, 1_020, 253, 252);
+        assert_eq!(stake.status, MatchStatus::Active);
+        assert_eq!(
+            stake.player_a_escrowed + stake.player_b_escrowed,
+            offer.continuation_pot().unwrap()
+        );
+        assert_eq!(offer.continuation_pot().unwrap(), 2 * 1_800_000);
+        
+        // All-or-nothing and chainable: no pot split, the next pot stays in escrow
+        assert!(stake.delayed_settlement && !stake.practice);
+        assert!(!stake.pot_split.is_enabled());
+        assert!(stake.allows(LifecycleAction::Gameplay));
+        
+        // An offer is answered once
+        assert_eq!(
+            offer.accept(&source, continuation_hash, offer.pot, window_end(&source)).unwrap_err(),
+            DroogError::DoubleOrNothingNotOffered.into()
+        );
+        assert_eq!(offer.decline().unwrap_err(), DroogError::DoubleOrNothingNotOffered.into());
+    }
+    
+    #[test]
+    fn test_overturned_result_voids_the_offer() {
+        // Offered during the challenge window, then frozen and overturned
+        let mut source = settling_stake();
+        let mut offer = offered(&source);
+        let m = MatchState { player_a: source.player_a, player_b: source.player_b, ..test_match() };
+        source.freeze_settlement(1_020).unwrap();
+        source.resolve_dispute(source.player_a, &m, 1_030).unwrap();
+        assert_eq!(source.pending_settlement.winner, offer.loser);
+        
+        // The new winner can't carry their own winnings into the stale offer
+        assert_eq!(
+            offer.accept(&source, [5u8; 32], offer.pot, window_end(&source)).unwrap_err(),
+            DroogError::InvalidPlayer.into()
+        );
+        assert_eq!(offer.status, ContinuationStatus::Offered);
+        source.check_execute_settlement(window_end(&source)).unwrap();
+    }
+    
+    #[test]
+    fn test_decline_leaves_the_pot_to_the_winner() {
+        let source = settling_stake();
+        let mut offer = offered(&source);
+        offer.decline().unwrap();
+        assert_eq!(offer.status, ContinuationStatus::Declined);
+        assert_eq!(offer.continuation_match_id_hash, [0; 32]);
+        assert_eq!(
+            offer.accept(&source, [5u8; 32], offer.pot, window_end(&source)).unwrap_err(),
+            DroogError::DoubleOrNothingNotOffered.into()
+        );
+        
+        // The pending settlement is untouched and pays the original winner
+        assert_eq!(source.pending_settlement.winner, offer.winner);
+        assert_eq!(source.pending_settlement.amount, offer.pot);
+        source.check_execute_settlement(source.pending_settlement.executable_at_ts).unwrap();
+    }
+}
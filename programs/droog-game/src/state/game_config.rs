@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use crate::errors::DroogError;
+use crate::state::{DISPUTE_ADMIN, GROWTH_TIMES, SMELL_RATES};
+
+/// Seeds: ["game_config"]
+/// Distinct from the kill-switch Config PDA, which already owns ["config"]
+pub const GAME_CONFIG_SEED: &[u8] = b"game_config";
+
+/// Level 1 strains rotate every 2 minutes by default
+pub const DEFAULT_LEVEL1_ROTATION_SECONDS: i64 = 2 * 60;
+
+/// Level 2 strains rotate every 3 minutes by default
+pub const DEFAULT_LEVEL2_ROTATION_SECONDS: i64 = 3 * 60;
+
+/// Finalizer reward ceiling: 5% of the pot
+pub const MAX_FINALIZER_REWARD_BPS: u16 = 500;
+
+/// Growth jitter ceiling: a plant grows in 50-150% of its level's time
+pub const MAX_GROWTH_JITTER_PERCENT: u8 = 50;
+
+/// Balance knobs that can change between matches without a redeploy
+/// 
+/// init_match snapshots the GameConfig's tunables (or DEFAULT when none
+/// exists) into MatchState, and the grow state copies them at init, so an
+/// update never changes the rules of a match already in flight.
+/// 
+/// Fields are ordered by alignment with explicit padding so the struct can
+/// live inside the zero-copy MatchState; its Borsh and in-memory layouts match.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct GameTunables {
+    /// Growth time in seconds per strain level (1, 2, 3)
+    pub growth_times: [i64; 3],
+    
+    /// Rotation period of the active Level 1 strains (is_strain_active)
+    pub level1_rotation_seconds: i64,
+    
+    /// Rotation period of the active Level 2 strain (is_strain_active)
+    pub level2_rotation_seconds: i64,
+    
+    /// Smell added per minute of growth per strain level (1, 2, 3)
+    pub smell_rates: [u16; 3],
+    
+    /// Share of the pot (bps) paid to the participant who calls
+    /// finalize_match; 0 disables the reward
+    pub finalizer_reward_bps: u16,
+    
+    /// Per-plant growth time jitter, +/- this percent of the level's growth
+    /// time, rolled deterministically at planting; 0 disables it
+    pub growth_jitter_percent: u8,
+    
+    /// Explicit alignment padding; must be zero
+    pub padding: [u8; 7],
+}
+
+impl GameTunables {
+    /// Size: 3 * 8 (growth times) + 8 + 8 (rotation periods) + 3 * 2 (smell rates)
+    /// + 2 (finalizer_reward_bps) + 1 (growth_jitter_percent) + 7 (padding) = 56 bytes
+    pub const SIZE: usize = 3 * 8 + 8 + 8 + 3 * 2 + 2 + 1 + 7;
+    
+    /// The compiled-in constants (used when no GameConfig exists)
+    pub const DEFAULT: GameTunables = GameTunables {
+        growth_times: GROWTH_TIMES,
+        smell_rates: SMELL_RATES,
+        level1_rotation_seconds: DEFAULT_LEVEL1_ROTATION_SECONDS,
+        level2_rotation_seconds: DEFAULT_LEVEL2_ROTATION_SECONDS,
+        finalizer_reward_bps: 0,
+        growth_jitter_percent: 0,
+        padding: [0; 7],
+    };
+    
+    /// Get growth time for a strain level (1, 2, or 3; 0 otherwise)
+    pub fn growth_time(&self, strain_level: u8) -> i64 {
+        match strain_level {
+            1..=3 => self.growth_times[(strain_level - 1) as usize],
+            _ => 0,
+        }
+    }
+    
+    /// Most seconds a plant of this level can be jittered either way
+    pub fn growth_jitter_span(&self, strain_level: u8) -> i64 {
+        self.growth_time(strain_level).saturating_mul(self.growth_jitter_percent as i64) / 100
+    }
+    
+    /// Slowest possible growth for a level (growth time plus the full jitter)
+    pub fn max_growth_time(&self, strain_level: u8) -> i64 {
+        self.growth_time(strain_level).saturating_add(self.growth_jitter_span(strain_level))
+    }
+    
+    /// Get smell rate per minute for a strain level (1, 2, or 3; 0 otherwise)
+    pub fn smell_rate(&self, strain_level: u8) -> u16 {
+        match strain_level {
+            1..=3 => self.smell_rates[(strain_level - 1) as usize],
+            _ => 0,
+        }
+    }
+    
+    /// Growth times and rotation periods must be positive; the finalizer
+    /// reward and growth jitter at most their MAX_ constants
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.growth_times.iter().all(|&t| t > 0)
+                && self.level1_rotation_seconds > 0
+                && self.level2_rotation_seconds > 0
+                && self.finalizer_reward_bps <= MAX_FINALIZER_REWARD_BPS
+                && self.growth_jitter_percent <= MAX_GROWTH_JITTER_PERCENT
+                && self.padding == [0; 7],
+            DroogError::InvalidGameConfig
+        );
+        Ok(())
+    }
+}
+
+impl Default for GameTunables {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Program-wide balance parameters PDA
+/// Seeds: ["game_config"]
+/// 
+/// Optional: created by the dispute admin on the first set_game_config, which
+/// may hand control to another authority. A deployment that never created it
+/// plays with GameTunables::DEFAULT.
+#[account]
+pub struct GameConfig {
+    /// May update the tunables (and hand over authority)
+    pub authority: Pubkey,
+    
+    /// Tunables snapshotted by every new match
+    pub tunables: GameTunables,
+    
+    /// Last time the tunables were changed
+    pub updated_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl GameConfig {
+    /// 8 (discriminator) + 32 (authority) + GameTunables::SIZE + 8 (updated_at) + 1 (bump)
+    pub const SIZE: usize = 8 + 32 + GameTunables::SIZE + 8 + 1;
+    
+    /// Tunables a new match should snapshot
+    /// `config` is the account at the GameConfig PDA address (checked by the
+    /// caller's seeds constraint, so it can't be skipped). Only an
+    /// uninitialized account (system-owned, no data) falls back to the defaults.
+    pub fn load_tunables(config: &AccountInfo) -> Result<GameTunables> {
+        if config.owner == &anchor_lang::system_program::ID && config.data_is_empty() {
+            return Ok(GameTunables::DEFAULT);
+        }
+        require_keys_eq!(*config.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let data = config.try_borrow_data()?;
+        Ok(GameConfig::try_deserialize(&mut &data[..])?.tunables)
+    }
+    
+    /// Who may write the tunables: the stored authority, or the dispute
+    /// admin while the account is freshly created (no authority yet)
+    pub fn check_authority(&self, signer: Pubkey) -> Result<()> {
+        if self.authority == Pubkey::default() {
+            require!(signer == DISPUTE_ADMIN, DroogError::NotDisputeAdmin);
+        } else {
+            require!(signer == self.authority, DroogError::NotConfigAuthority);
+        }
+        Ok(())
+    }
+}
+
+/// Emitted when set_game_config changes the tunables
+#[event]
+pub struct GameConfigUpdatedEvent {
+    pub authority: Pubkey,
+    pub tunables: GameTunables,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn load(data: &mut [u8], owner: &Pubkey) -> Result<GameTunables> {
+        let key = Pubkey::find_program_address(&[GAME_CONFIG_SEED], &crate::ID).0;
+        let mut lamports = 1_000_000;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+        GameConfig::load_tunables(&info)
+    }
+    
+    #[test]
+    fn test_load_tunables_defaults_without_config() {
+        let system = anchor_lang::system_program::ID;
+        assert_eq!(load(&mut [], &system).unwrap(), GameTunables::DEFAULT);
+        assert_eq!(GameTunables::DEFAULT.growth_time(3), GROWTH_TIMES[2]);
+        assert_eq!(GameTunables::DEFAULT.smell_rate(0), 0);
+        
+        let tunables = GameTunables {
+            growth_times: [5, 20, 40],
+            ..GameTunables::DEFAULT
+        };
+        let config = GameConfig { authority: Pubkey::new_unique(), tunables, updated_at: 1_000, bump: 255 };
+        let mut data = Vec::with_capacity(GameConfig::SIZE);
+        config.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), GameConfig::SIZE);
+        assert_eq!(load(&mut data, &crate::ID).unwrap(), tunables);
+        
+        // An existing config can't be swapped for a look-alike owned elsewhere
+        assert_eq!(
+            load(&mut data, &Pubkey::new_unique()).unwrap_err(),
+            ErrorCode::AccountOwnedByWrongProgram.into()
+        );
+        assert!(load(&mut [], &crate::ID).is_err());
+        
+        // Zero periods would divide by zero in is_strain_active
+        let broken = GameTunables { level2_rotation_seconds: 0, ..tunables };
+        assert_eq!(broken.validate().unwrap_err(), DroogError::InvalidGameConfig.into());
+        assert!(tunables.validate().is_ok());
+        
+        let greedy = GameTunables { finalizer_reward_bps: MAX_FINALIZER_REWARD_BPS + 1, ..tunables };
+        assert_eq!(greedy.validate().unwrap_err(), DroogError::InvalidGameConfig.into());
+        let chaotic = GameTunables { growth_jitter_percent: MAX_GROWTH_JITTER_PERCENT + 1, ..tunables };
+        assert_eq!(chaotic.validate().unwrap_err(), DroogError::InvalidGameConfig.into());
+        let padded = GameTunables { padding: [1; 7], ..tunables };
+        assert_eq!(padded.validate().unwrap_err(), DroogError::InvalidGameConfig.into());
+    }
+    
+    #[test]
+    fn test_config_authority() {
+        // A fresh config can only be claimed by the dispute admin
+        let mut config = GameConfig { authority: Pubkey::default(), tunables: GameTunables::DEFAULT, updated_at: 0, bump: 255 };
+        assert_eq!(config.check_authority(Pubkey::new_unique()).unwrap_err(), DroogError::NotDisputeAdmin.into());
+        assert!(config.check_authority(DISPUTE_ADMIN).is_ok());
+        
+        // After a handover only the new authority may write
+        config.authority = Pubkey::new_unique();
+        assert!(config.check_authority(config.authority).is_ok());
+        assert_eq!(config.check_authority(DISPUTE_ADMIN).unwrap_err(), DroogError::NotConfigAuthority.into());
+    }
+}
@@ -1,46 +1,307 @@
 use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use solana_program::hash::hashv;
+use crate::errors::DroogError;
 use crate::state::customer_state::CustomerState;
+use crate::deterministic::{self, mix64};
+use crate::state::delivery_state::LayerConfig;
+use crate::state::game_config::GameTunables;
+use crate::state::rep_ledger::{
+    RepLedger, REP_REASON_BASE, REP_REASON_DEMAND, REP_REASON_REFRESH, REP_REASON_RUSH,
+    REP_REASON_STEAL, REP_REASON_VARIANT, REP_REASON_DIVERSITY,
+};
+use crate::state::replay::{
+    ReplayFrame, REPLAY_ACTOR_OTHER, REPLAY_ACTOR_PLAYER_A, REPLAY_ACTOR_PLAYER_B,
+};
 
-#[account]
+/// Deterministic selection algorithm versions (see crate::deterministic)
+pub use crate::deterministic::{ALGO_VERSION_LEGACY, CURRENT_ALGO_VERSION};
+
+/// Compact action codes for OpponentActionEvent
+pub const OPPONENT_ACTION_SALE: u8 = 0;
+pub const OPPONENT_ACTION_HARVEST: u8 = 1;
+
+/// Sales moving reputation by at least this much (either direction) notify the opponent
+pub const SIGNIFICANT_REP_DELTA: i32 = 3;
+
+/// How the winner was picked (reported in MatchFinalizedEvent and StakePayoutEvent)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecidedBy {
+    /// One player made more sales
+    SalesMargin,
+    /// Equal sales and equal reputation: Player A wins (first mover advantage)
+    FirstMoverTiebreak,
+    /// Equal sales and reputation: the pot was split (recorded at finalize, paid then or at execute)
+    Draw,
+    /// Equal sales: the player with higher (clamped) reputation wins
+    ReputationTiebreak,
+    /// The other player conceded before the end (surrender_match)
+    Surrender,
+    /// The dispute admin overturned the recorded winner (resolve_dispute)
+    Overturned,
+}
+
+/// Outcome if the match ended now (see MatchState::determine_winner)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Standings {
+    pub winner: Pubkey,
+    pub loser: Pubkey,
+    pub winner_sales: u32,
+    pub loser_sales: u32,
+    pub decided_by: DecidedBy,
+}
+
+/// Opening lock (opt-in at init): no planting or selling for this many
+/// seconds after start_ts, so both clients can load and sync before play
+pub const START_GRACE_SECONDS: i64 = 5;
+
+/// Standard match length (10 minutes, fast-paced)
+pub const MATCH_DURATION_SECONDS: i64 = 10 * 60;
+
+/// Shortest accepted match window: end_ts must be strictly more than this
+/// past start_ts, so endgame lock and growth timing stay meaningful
+pub const MIN_MATCH_DURATION_SECONDS: i64 = 60;
+
+/// After end_ts + this, anyone (e.g. a keeper) may finalize an abandoned match
+pub const FINALIZE_GRACE_SECONDS: i64 = 3600;
+
+/// Anti-collusion signal (see MatchState::is_low_engagement): combined sales
+/// at or below this, finalized within LOW_ENGAGEMENT_FINALIZE_WINDOW_SECONDS
+/// of end_ts, with no delivery rotations, flags the match for monitoring
+pub const LOW_ENGAGEMENT_MAX_SALES: u32 = 2;
+pub const LOW_ENGAGEMENT_FINALIZE_WINDOW_SECONDS: i64 = 60;
+
+/// Diversity bonus: reputation awarded at finalize per distinct customer served
+/// Rewards covering the map over camping a few customers; it feeds the
+/// reputation tiebreak and the loser consolation
+pub const DIVERSITY_REP_PER_CUSTOMER: i32 = 1;
+
+/// Momentum ("on fire" indicator): each sale adds MOMENTUM_PER_SALE, capped
+/// at MOMENTUM_MAX, and the stored value halves every MOMENTUM_HALF_LIFE_SECONDS
+/// of inactivity. Purely cosmetic; scoring and settlement ignore it
+pub const MOMENTUM_PER_SALE: i32 = 10;
+pub const MOMENTUM_MAX: i32 = 100;
+pub const MOMENTUM_HALF_LIFE_SECONDS: i64 = 30;
+
+/// Per-match reputation gates on planting higher strain levels
+/// Early game is played on Level 1 until a player's reputation unlocks premium
+/// strains. A threshold of 0 leaves that level open; all zeros (the default)
+/// disables the progression.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, Pod, Zeroable)]
+#[repr(C)]
+pub struct StrainUnlock {
+    /// Minimum reputation to plant Level 2
+    pub level2_min_reputation: i32,
+    
+    /// Minimum reputation to plant Level 3
+    pub level3_min_reputation: i32,
+}
+
+impl StrainUnlock {
+    /// 4 (level2_min_reputation) + 4 (level3_min_reputation)
+    pub const SIZE: usize = 4 + 4;
+    
+    /// Thresholds are non-negative and Level 3 is never easier than Level 2
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.level2_min_reputation >= 0
+                && self.level3_min_reputation >= self.level2_min_reputation,
+            DroogError::InvalidStrainUnlock
+        );
+        Ok(())
+    }
+    
+    /// Whether a player at this reputation may plant strain_level
+    /// (Level 1 and 0-threshold levels are always open)
+    pub fn is_unlocked(&self, strain_level: u8, reputation: i32) -> bool {
+        let min_reputation = match strain_level {
+            2 => self.level2_min_reputation,
+            3 => self.level3_min_reputation,
+            _ => 0,
+        };
+        min_reputation == 0 || reputation >= min_reputation
+    }
+}
+
+/// Distinct customers served, from a player's served-customer bitmask
+/// (bit i = customer i; bits past the 23 customers are ignored)
+pub fn diversity_score(bitmask: u32) -> u32 {
+    (bitmask & ((1 << 23) - 1)).count_ones()
+}
+
+/// Emitted alongside significant sales/harvests, tagged with the opponent
+/// so clients can cheaply filter for "my opponent just did X"
+#[event]
+pub struct OpponentActionEvent {
+    pub match_id: u64,
+    pub actor: Pubkey,
+    pub opponent: Pubkey,
+    /// OPPONENT_ACTION_SALE or OPPONENT_ACTION_HARVEST
+    pub action: u8,
+    pub strain_level: u8,
+    /// Customer served (255 for harvests)
+    pub customer_index: u8,
+    /// Reputation change for the actor (0 for harvests)
+    pub reputation_delta: i32,
+    pub timestamp: i64,
+}
+
+/// Match account (zero-copy)
+/// 
+/// Loaded in place through AccountLoader rather than Borsh-deserialized, so
+/// the 23-customer roster is never copied onto the heap. Instructions take it
+/// as AccountLoader<MatchState> and borrow it with load()/load_mut() (or
+/// load_init() when creating it); keep it borrowed rather than copied. Fields are grouped
+/// by alignment (8, then 32-byte keys, then 4, then 1) with explicit trailing
+/// padding; flags are stored as u8 (0/1) and read through the same-named
+/// accessors, and Options as a key plus a flag.
+/// 
+/// Migration: match accounts created before the zero-copy layout cannot be
+/// loaded; in-flight matches must be finished or reaped under the old program.
+#[account(zero_copy)]
 pub struct MatchState {
+    // ========== 8-byte aligned ==========
     pub match_id: u64,                    // Unique match identifier
-    pub match_id_hash: [u8; 32],          // 32-byte hash used for PDA seeds (canonical)
     pub start_ts: i64,                    // Match start timestamp
     pub end_ts: i64,                      // Match end timestamp (start + 30 min)
+    pub last_refresh_reward_bucket: u64,   // Last rotation bucket whose refresh incentive was claimed
+    pub player_a_sales_bucket: u64,        // Rotation bucket player_a_bucket_sales counts
+    pub player_b_sales_bucket: u64,        // Rotation bucket player_b_bucket_sales counts
+    pub player_a_momentum_ts: i64,         // When player_a_momentum was last written
+    pub player_b_momentum_ts: i64,         // When player_b_momentum was last written
+    pub final_payout: u64,                 // Winner's payout (pending amount until executed; whole pot in a draw; 0 if voided)
+    pub finalized_ts: i64,                 // When finalize/surrender ended the match (0 = not yet)
+    pub customers: [CustomerState; 23],  // Fixed array of 23 customers
+    pub tunables: GameTunables,            // GameConfig snapshot: growth, smell, rotation periods (fixed at init)
+    
+    // ========== 32-byte keys ==========
+    pub match_id_hash: [u8; 32],          // 32-byte hash used for PDA seeds (canonical)
     pub player_a: Pubkey,                 // Player A wallet
     pub player_b: Pubkey,                 // Player B wallet
-    pub customers: [CustomerState; 23],  // Fixed array of 23 customers
+    pub winner: Pubkey,                    // Paid winner once finalized (valid when has_winner != 0)
+    
+    // ========== 4-byte aligned ==========
     pub player_a_sales: u32,              // Total sales count for player A
     pub player_b_sales: u32,               // Total sales count for player B
     pub player_a_reputation: i32,          // Reputation score (can be negative)
     pub player_b_reputation: i32,          // Reputation score
-    pub is_finalized: bool,                // Match finalization state (immutable after true)
-    pub bump: u8,                         // PDA bump seed
+    pub player_a_refreshes: u32,           // Delivery refreshes triggered by player A
+    pub player_b_refreshes: u32,           // Delivery refreshes triggered by player B
+    pub replay_seq: u32,                   // Next ReplayFrame sequence number
+    pub player_a_served_mask: u32,         // Bit i set once player A has sold to customer i
+    pub player_b_served_mask: u32,         // Bit i set once player B has sold to customer i
+    pub player_a_momentum: i32,            // Player A's momentum as of player_a_momentum_ts (decays lazily)
+    pub player_b_momentum: i32,            // Player B's momentum as of player_b_momentum_ts (decays lazily)
+    pub player_a_rep_ledger: RepLedger,    // Recent reputation deltas for player A
+    pub player_b_rep_ledger: RepLedger,    // Recent reputation deltas for player B
+    pub strain_unlock: StrainUnlock,       // Reputation needed to plant Level 2/3 (all zeros = off, fixed at init)
+    
+    // ========== 1-byte ==========
+    pub is_finalized: u8,                  // Match finalization state (immutable once set)
+    pub bump: u8,                          // PDA bump seed
+    pub algo_version: u8,                  // Deterministic algorithm version (fixed at init)
+    pub layer_config: LayerConfig,         // Per-layer customer counts (fixed at init)
+    pub delivery_less: u8,                 // Delivery system disabled; sales gated by cooldowns only
+    pub rep_ledger_enabled: u8,            // Record reputation deltas in the per-player ledgers (opt-in at init)
+    pub early_end_eligible: u8,            // Mercy rule hit; finalize may run before end_ts (sticky)
+    pub extra_delivery_spots: u8,          // Delivery spots beyond the per-layer minimums (fixed at init)
+    pub start_grace: u8,                   // Opening lock: no plant/sell for START_GRACE_SECONDS (opt-in at init)
+    pub hide_live_scores: u8,              // Fog of war: sales emit no player-attributed score events until finalize
+    pub max_sales_per_bucket: u8,          // Per-player sales cap per rotation bucket (0 = off, fixed at init)
+    pub player_a_bucket_sales: u8,         // Player A's sales in player_a_sales_bucket
+    pub player_b_bucket_sales: u8,         // Player B's sales in player_b_sales_bucket
+    pub shared_land: u8,                   // Contested neutral slots on grow_state (opt-in at init)
+    pub has_winner: u8,                    // winner is set (0 before finalize, for a draw, and once voided)
+    pub padding: [u8; 3],                  // Explicit alignment padding; always zero
 }
 
 impl MatchState {
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 32 + 32 + (23 * CustomerState::SIZE) + 4 + 4 + 4 + 4 + 1 + 1;
+    /// In-memory (and on-chain, after the discriminator) size of the account
+    pub const SIZE: usize = core::mem::size_of::<MatchState>();
+    
+    /// Match finalization state (immutable once set)
+    pub fn is_finalized(&self) -> bool {
+        self.is_finalized != 0
+    }
+    
+    /// Delivery system disabled; sales gated by cooldowns only
+    pub fn delivery_less(&self) -> bool {
+        self.delivery_less != 0
+    }
+    
+    /// Reputation deltas are recorded in the per-player ledgers
+    pub fn rep_ledger_enabled(&self) -> bool {
+        self.rep_ledger_enabled != 0
+    }
+    
+    /// Mercy rule hit; finalize may run before end_ts (sticky)
+    pub fn early_end_eligible(&self) -> bool {
+        self.early_end_eligible != 0
+    }
+    
+    /// Opening lock: no plant/sell for START_GRACE_SECONDS
+    pub fn start_grace(&self) -> bool {
+        self.start_grace != 0
+    }
+    
+    /// Fog of war: sales emit no player-attributed score events until finalize
+    pub fn hide_live_scores(&self) -> bool {
+        self.hide_live_scores != 0
+    }
+    
+    /// Contested neutral slots on grow_state
+    pub fn shared_land(&self) -> bool {
+        self.shared_land != 0
+    }
+    
+    /// Paid winner once finalized (None before, and for a draw)
+    pub fn winner(&self) -> Option<Pubkey> {
+        (self.has_winner != 0).then_some(self.winner)
+    }
     
     // Reputation bounds to prevent overflow/underflow
     pub const REP_MIN: i32 = -1000;
     pub const REP_MAX: i32 = 1000;
     
+    /// Reputation bonus for a participant who refreshes the delivery board promptly
+    pub const REFRESH_REP_BONUS: i32 = 1;
+    
+    /// Serving a customer the opponent served within this window is a steal
+    pub const STEAL_WINDOW_SECONDS: i64 = 60;
+    
+    /// Share of the opponent's earned reputation transferred on a steal (rounded up)
+    pub const STEAL_TAX_PERCENT: i32 = 50;
+    
     /// AUTHORITATIVE layer derivation from customer_index.
     /// This is the CANONICAL way to determine layer from index.
     /// Layer is NEVER stored - always derived.
     /// 
-    /// Index ranges:
+    /// Index ranges (default layout, LayerConfig::DEFAULT):
     /// - 0-2:   Layer 3 (Inner Core)
     /// - 3-10:  Layer 2 (Middle Ring)
     /// - 11-22: Layer 1 (Outer Ring)
     pub fn layer_from_index(customer_index: u8) -> u8 {
-        if customer_index < 3 {
-            3  // Inner Core
-        } else if customer_index < 11 {
-            2  // Middle Ring
-        } else {
-            1  // Outer Ring
-        }
+        LayerConfig::DEFAULT.layer_from_index(customer_index)
+    }
+    
+    /// Domain separator for derive_match_id
+    pub const MATCH_ID_DOMAIN: &'static [u8] = b"droog:match_id";
+    
+    /// Derive a match_id from the full match_id_hash (used when init omits it)
+    /// sha256(MATCH_ID_DOMAIN || match_id_hash), first 8 bytes little-endian.
+    /// Every byte of the hash contributes, so hashes sharing a prefix still
+    /// get unrelated ids.
+    pub fn derive_match_id(match_id_hash: &[u8; 32]) -> u64 {
+        let digest = hashv(&[Self::MATCH_ID_DOMAIN, match_id_hash.as_ref()]).to_bytes();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(bytes)
+    }
+    
+    /// Layer for a customer under this match's configured layout
+    /// Gameplay paths use this rather than the default-layout helper above
+    pub fn customer_layer(&self, customer_index: u8) -> u8 {
+        self.layer_config.layer_from_index(customer_index)
     }
     
     /// Customer cooldowns adjusted for 10-minute matches
@@ -53,26 +314,53 @@ impl MatchState {
         }
     }
     
+    /// Maximum patience adjustment (seconds, either direction) to a customer's cooldown
+    pub const CUSTOMER_PATIENCE_MAX_SECONDS: i64 = 3;
+    
+    /// Deterministic per-customer "patience" trait derived from the map seed
+    /// 
+    /// Returns a cooldown modifier in [-CUSTOMER_PATIENCE_MAX_SECONDS, +CUSTOMER_PATIENCE_MAX_SECONDS]:
+    /// impatient customers (negative) come back sooner, patient ones (positive) later.
+    /// Fixed for the whole match, so learning the map pays off.
+    pub fn customer_patience(seed: u64, customer_index: u8) -> i64 {
+        let span = (Self::CUSTOMER_PATIENCE_MAX_SECONDS * 2 + 1) as u64;
+        let roll = mix64(seed ^ (customer_index as u64).wrapping_mul(0x9e3779b97f4a7c15)) % span;
+        roll as i64 - Self::CUSTOMER_PATIENCE_MAX_SECONDS
+    }
+    
+    /// Every customer's display flavor for this match (see deterministic::customer_flavor)
+    pub fn customer_flavors(&self) -> [u8; 23] {
+        core::array::from_fn(|index| deterministic::customer_flavor(self.match_id, index as u8))
+    }
+    
     /// Get cooldown for a customer by index (derives layer automatically)
-    pub fn get_cooldown_for_customer(customer_index: u8) -> i64 {
+    /// Includes the customer's patience modifier for the given map seed
+    pub fn get_cooldown_for_customer(seed: u64, customer_index: u8) -> i64 {
         let layer = Self::layer_from_index(customer_index);
-        Self::get_customer_cooldown(layer)
+        Self::get_customer_cooldown(layer) + Self::customer_patience(seed, customer_index)
+    }
+    
+    /// Cooldown for a customer under this match's layout and map seed (match_id)
+    pub fn customer_cooldown(&self, customer_index: u8) -> i64 {
+        let layer = self.customer_layer(customer_index);
+        Self::get_customer_cooldown(layer) + Self::customer_patience(self.match_id, customer_index)
     }
     
-    pub fn is_customer_available(&self, customer_index: usize, current_ts: i64) -> bool {
+    /// Whether the given player's cooldown on a customer has elapsed
+    /// Only that player's own last serve counts; the opponent's doesn't lock them out
+    pub fn is_customer_available(&self, customer_index: usize, is_player_a: bool, current_ts: i64) -> bool {
         if customer_index >= 23 {
             return false;
         }
         
-        let customer = &self.customers[customer_index];
-        if customer.last_served_ts == 0 {
+        let last_served_ts = self.customers[customer_index].last_served_ts_of(is_player_a);
+        if last_served_ts == 0 {
             return true;
         }
         
-        // Derive layer from index (authoritative)
-        let layer = Self::layer_from_index(customer_index as u8);
-        let cooldown = Self::get_customer_cooldown(layer);
-        current_ts >= customer.last_served_ts + cooldown
+        // Derive layer from index (authoritative), adjusted by customer patience
+        let cooldown = self.customer_cooldown(customer_index as u8);
+        current_ts >= last_served_ts + cooldown
     }
     
     /// Validate strain for customer. Layer is derived from customer_index.
@@ -82,7 +370,7 @@ impl MatchState {
         }
         
         // Derive layer from index (authoritative - never trust stored layer)
-        let layer = Self::layer_from_index(customer_index as u8);
+        let layer = self.customer_layer(customer_index as u8);
         match layer {
             1 => strain_level == 1,
             2 => strain_level == 1 || strain_level == 2,
@@ -117,16 +405,16 @@ impl MatchState {
     /// Check if a strain is currently active based on rotation schedule
     /// Rotation boundaries are half-open intervals [start, end) to prevent overlap
     /// 
-    /// Adjusted for 10-minute matches:
-    /// Level 1: 2 active strains, rotates every 2 minutes (120 seconds)
-    /// Level 2: 1 active strain, rotates every 3 minutes (180 seconds)
+    /// Periods come from the match's tunables (defaults for 10-minute matches):
+    /// Level 1: 2 active strains, rotates every level1_rotation_seconds (2 minutes)
+    /// Level 2: 1 active strain, rotates every level2_rotation_seconds (3 minutes)
     /// Level 3: Always active (1 strain)
     pub fn is_strain_active(&self, strain_id: u8, current_ts: i64) -> bool {
         let elapsed = current_ts - self.start_ts;
         
         // Level 1 strains: 0, 1, 2
         if strain_id < 3 {
-            let rotation_period = 2 * 60; // 2 minutes (was 10)
+            let rotation_period = self.tunables.level1_rotation_seconds;
             let rotation_index = (elapsed / rotation_period) as usize;
             
             // Rotation pattern: [0,1] -> [1,2] -> [2,0] -> [0,1] ...
@@ -142,7 +430,7 @@ impl MatchState {
         
         // Level 2 strains: 3, 4, 5
         if strain_id < 6 {
-            let rotation_period = 3 * 60; // 3 minutes (was 15)
+            let rotation_period = self.tunables.level2_rotation_seconds;
             let rotation_index = (elapsed / rotation_period) as usize;
             
             // Rotate through: 3 -> 4 -> 5 -> 3 ...
@@ -160,4 +448,1417 @@ impl MatchState {
     pub fn clamp_reputation(rep: i32) -> i32 {
         rep.max(Self::REP_MIN).min(Self::REP_MAX)
     }
+    
+    /// Apply handicap starting reputations (clamped to REP bounds)
+    /// Lets a stronger player spot the weaker one reputation points at init
+    pub fn apply_starting_reputation(&mut self, player_a_rep_start: i32, player_b_rep_start: i32) {
+        self.player_a_reputation = Self::clamp_reputation(player_a_rep_start);
+        self.player_b_reputation = Self::clamp_reputation(player_b_rep_start);
+        self.player_a_rep_ledger = RepLedger {
+            starting_reputation: self.player_a_reputation,
+            ..RepLedger::default()
+        };
+        self.player_b_rep_ledger = RepLedger {
+            starting_reputation: self.player_b_reputation,
+            ..RepLedger::default()
+        };
+    }
+    
+    /// Winner, loser, and how the winner was picked if the match ended now
+    /// 1. More sales wins (on-chain authoritative)
+    /// 2. Equal sales: higher clamped reputation wins
+    /// 3. Equal on both: Player A (first mover advantage)
+    pub fn determine_winner(&self) -> Standings {
+        self.standings_with_reputation(self.player_a_reputation, self.player_b_reputation)
+    }
+    
+    /// determine_winner's rules against the given reputations (e.g. projected
+    /// ones) instead of the stored ones
+    pub fn standings_with_reputation(&self, player_a_reputation: i32, player_b_reputation: i32) -> Standings {
+        let a_rep = Self::clamp_reputation(player_a_reputation);
+        let b_rep = Self::clamp_reputation(player_b_reputation);
+        let (a_wins, decided_by) = if self.player_a_sales != self.player_b_sales {
+            (self.player_a_sales > self.player_b_sales, DecidedBy::SalesMargin)
+        } else if a_rep != b_rep {
+            (a_rep > b_rep, DecidedBy::ReputationTiebreak)
+        } else {
+            (true, DecidedBy::FirstMoverTiebreak)
+        };
+        
+        if a_wins {
+            Standings {
+                winner: self.player_a,
+                loser: self.player_b,
+                winner_sales: self.player_a_sales,
+                loser_sales: self.player_b_sales,
+                decided_by,
+            }
+        } else {
+            Standings {
+                winner: self.player_b,
+                loser: self.player_a,
+                winner_sales: self.player_b_sales,
+                loser_sales: self.player_a_sales,
+                decided_by,
+            }
+        }
+    }
+    
+    /// Current reputation of player A or B
+    pub fn reputation_of(&self, is_player_a: bool) -> i32 {
+        if is_player_a {
+            self.player_a_reputation
+        } else {
+            self.player_b_reputation
+        }
+    }
+    
+    /// Apply a reputation change (clamped) and record it in the ledger if enabled
+    /// Returns the delta actually applied after clamping
+    pub fn adjust_reputation(&mut self, is_player_a: bool, delta: i32, reasons: u16) -> i32 {
+        let ledger_enabled = self.rep_ledger_enabled();
+        let (reputation, ledger) = if is_player_a {
+            (&mut self.player_a_reputation, &mut self.player_a_rep_ledger)
+        } else {
+            (&mut self.player_b_reputation, &mut self.player_b_rep_ledger)
+        };
+        
+        let before = *reputation;
+        *reputation = Self::clamp_reputation(before.saturating_add(delta));
+        let applied = *reputation - before;
+        
+        if ledger_enabled && delta != 0 {
+            ledger.append(applied, reasons);
+        }
+        applied
+    }
+    
+    /// REP_REASON_* bits for a sale's nonzero modifiers
+    pub fn sale_rep_reasons(variant_bonus: i32, variant_demand_bonus: i32, rush_bonus: i32) -> u16 {
+        let mut reasons = REP_REASON_BASE;
+        if variant_bonus != 0 {
+            reasons |= REP_REASON_VARIANT;
+        }
+        if variant_demand_bonus != 0 {
+            reasons |= REP_REASON_DEMAND;
+        }
+        if rush_bonus != 0 {
+            reasons |= REP_REASON_RUSH;
+        }
+        reasons
+    }
+    
+    /// Transfer reputation from the opponent when `stealer` serves a customer
+    /// the opponent served within STEAL_WINDOW_SECONDS.
+    /// STEAL_TAX_PERCENT of what the opponent earned there moves to the stealer.
+    /// Must be called BEFORE the customer's last_served_* fields are overwritten.
+    /// Returns the amount transferred (0 if this serve isn't a steal).
+    pub fn steal_reputation(&mut self, customer_index: usize, stealer: Pubkey, current_ts: i64) -> i32 {
+        let customer = &mut self.customers[customer_index];
+        let victim = match customer.last_served_by() {
+            Some(prev) if prev != stealer => prev,
+            _ => return 0,
+        };
+        
+        if current_ts - customer.last_served_ts >= Self::STEAL_WINDOW_SECONDS
+            || customer.last_rep_earned <= 0
+        {
+            return 0;
+        }
+        
+        let stolen = (customer.last_rep_earned.saturating_mul(Self::STEAL_TAX_PERCENT) + 99) / 100;
+        customer.last_rep_earned = customer.last_rep_earned.saturating_sub(stolen);
+        
+        let victim_is_a = if victim == self.player_a && stealer == self.player_b {
+            true
+        } else if victim == self.player_b && stealer == self.player_a {
+            false
+        } else {
+            return 0;
+        };
+        self.adjust_reputation(victim_is_a, -stolen, REP_REASON_STEAL);
+        self.adjust_reputation(!victim_is_a, stolen, REP_REASON_STEAL);
+        
+        stolen
+    }
+    
+    /// Winner when `surrendering` concedes at current_ts (their opponent)
+    /// Only before end_ts (finalize handles ended matches) and only once
+    pub fn surrender_winner(&self, surrendering: Pubkey, current_ts: i64) -> Result<Pubkey> {
+        require!(!self.is_finalized(), DroogError::MatchAlreadyFinalized);
+        require!(current_ts < self.end_ts, DroogError::MatchEnded);
+        self.opponent_of(surrendering).ok_or_else(|| DroogError::InvalidPlayer.into())
+    }
+    
+    /// Close the match with its result snapshot (finalize_match, surrender_match)
+    /// so post-match queries are a plain account fetch. winner is None for a
+    /// draw; final_payout is what the winner is paid (the pending amount under
+    /// delayed settlement), or the whole pot split in a draw.
+    pub fn record_result(&mut self, winner: Option<Pubkey>, final_payout: u64, finalized_ts: i64) {
+        self.is_finalized = 1;
+        self.winner = winner.unwrap_or_default();
+        self.has_winner = u8::from(winner.is_some());
+        self.final_payout = final_payout;
+        self.finalized_ts = finalized_ts;
+    }
+    
+    /// Keep the result snapshot current as a delayed settlement plays out:
+    /// resolve_dispute (overturned winner and re-split amount),
+    /// reverse_finalization (voided: no winner, nothing paid) and
+    /// execute_settlement (the payout actually made). The match stays
+    /// finalized at its original finalized_ts.
+    pub fn revise_result(&mut self, winner: Option<Pubkey>, final_payout: u64) {
+        self.winner = winner.unwrap_or_default();
+        self.has_winner = u8::from(winner.is_some());
+        self.final_payout = final_payout;
+    }
+    
+    /// The other participant, or None if `player` isn't in this match
+    pub fn opponent_of(&self, player: Pubkey) -> Option<Pubkey> {
+        if player == self.player_a {
+            Some(self.player_b)
+        } else if player == self.player_b {
+            Some(self.player_a)
+        } else {
+            None
+        }
+    }
+    
+    /// Build an OpponentActionEvent for a sale if it's significant:
+    /// any Layer 3 customer, or a reputation swing of SIGNIFICANT_REP_DELTA or more
+    pub fn significant_sale_event(
+        &self,
+        actor: Pubkey,
+        customer_index: u8,
+        strain_level: u8,
+        reputation_delta: i32,
+        current_ts: i64,
+    ) -> Option<OpponentActionEvent> {
+        if !self.reveals_live_scores() {
+            return None;
+        }
+        let significant = self.customer_layer(customer_index) == 3
+            || reputation_delta.abs() >= SIGNIFICANT_REP_DELTA;
+        if !significant {
+            return None;
+        }
+        Some(OpponentActionEvent {
+            match_id: self.match_id,
+            actor,
+            opponent: self.opponent_of(actor)?,
+            action: OPPONENT_ACTION_SALE,
+            strain_level,
+            customer_index,
+            reputation_delta,
+            timestamp: current_ts,
+        })
+    }
+    
+    /// Build an OpponentActionEvent for a harvest if it's significant (Level 3 only)
+    pub fn significant_harvest_event(
+        &self,
+        actor: Pubkey,
+        strain_level: u8,
+        current_ts: i64,
+    ) -> Option<OpponentActionEvent> {
+        if strain_level != 3 {
+            return None;
+        }
+        Some(OpponentActionEvent {
+            match_id: self.match_id,
+            actor,
+            opponent: self.opponent_of(actor)?,
+            action: OPPONENT_ACTION_HARVEST,
+            strain_level,
+            customer_index: u8::MAX,
+            reputation_delta: 0,
+            timestamp: current_ts,
+        })
+    }
+    
+    /// Decide whether a sale goes through the delivery system
+    /// Ok(true): apply delivery checks. Ok(false): delivery-less match, skip them.
+    /// Err(DeliveryStateNotInitialized): delivery match whose delivery_state was never set up
+    pub fn check_delivery_access(&self, has_delivery_state: bool) -> Result<bool> {
+        if self.delivery_less() {
+            return Ok(false);
+        }
+        require!(has_delivery_state, DroogError::DeliveryStateNotInitialized);
+        Ok(true)
+    }
+    
+    /// Build the next ReplayFrame and advance the sequence counter
+    /// Every gameplay instruction emits exactly one frame
+    pub fn next_replay_frame(&mut self, action: u8, actor: Pubkey, p0: u8, p1: u8, p2: u8, ts: i64) -> ReplayFrame {
+        let actor_flag = if actor == self.player_a {
+            REPLAY_ACTOR_PLAYER_A
+        } else if actor == self.player_b {
+            REPLAY_ACTOR_PLAYER_B
+        } else {
+            REPLAY_ACTOR_OTHER
+        };
+        let frame = ReplayFrame {
+            seq: self.replay_seq,
+            action,
+            actor_flag,
+            p0,
+            p1,
+            p2,
+            ts,
+        };
+        self.replay_seq = self.replay_seq.saturating_add(1);
+        frame
+    }
+    
+    /// Whether sales may emit player-attributed score events during play
+    /// (false in hide_live_scores matches; finalize reveals everything)
+    pub fn reveals_live_scores(&self) -> bool {
+        !self.hide_live_scores()
+    }
+    
+    /// Actor to put in a sale's replay frame: anonymized (REPLAY_ACTOR_OTHER)
+    /// when live scores are hidden, so frame counts don't leak sales per player
+    pub fn sale_replay_actor(&self, player: Pubkey) -> Pubkey {
+        if self.reveals_live_scores() {
+            player
+        } else {
+            Pubkey::default()
+        }
+    }
+    
+    /// Set up a freshly created match account (shared by init_match and rematch)
+    /// 
+    /// Identity, match window, zeroed counters and the customer roster for
+    /// layer_config. The caller then applies the per-match rules and starting
+    /// reputations.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        match_id: u64,
+        match_id_hash: [u8; 32],
+        player_a: Pubkey,
+        player_b: Pubkey,
+        start_ts: i64,
+        layer_config: LayerConfig,
+        bump: u8,
+    ) -> Result<()> {
+        self.match_id = match_id;
+        self.match_id_hash = match_id_hash;
+        self.set_match_window(start_ts, start_ts.saturating_add(MATCH_DURATION_SECONDS))?;
+        self.player_a = player_a;
+        self.player_b = player_b;
+        self.player_a_sales = 0;
+        self.player_b_sales = 0;
+        self.is_finalized = 0;
+        self.bump = bump;
+        self.player_a_refreshes = 0;
+        self.player_b_refreshes = 0;
+        self.algo_version = CURRENT_ALGO_VERSION;
+        self.layer_config = layer_config;
+        self.last_refresh_reward_bucket = 0;
+        self.replay_seq = 0;
+        self.early_end_eligible = 0;
+        self.player_a_sales_bucket = 0;
+        self.player_b_sales_bucket = 0;
+        self.player_a_bucket_sales = 0;
+        self.player_b_bucket_sales = 0;
+        self.player_a_served_mask = 0;
+        self.player_b_served_mask = 0;
+        self.strain_unlock = StrainUnlock::default();
+        self.shared_land = 0;
+        self.player_a_momentum = 0;
+        self.player_a_momentum_ts = 0;
+        self.player_b_momentum = 0;
+        self.player_b_momentum_ts = 0;
+        self.tunables = GameTunables::DEFAULT;
+        self.winner = Pubkey::default();
+        self.has_winner = 0;
+        self.final_payout = 0;
+        self.finalized_ts = 0;
+        
+        // Initialize customers with deterministic layer assignments
+        for (i, customer) in self.customers.iter_mut().enumerate() {
+            *customer = CustomerState::new(layer_config.layer_from_index(i as u8));
+        }
+        Ok(())
+    }
+    
+    /// Copy another match's per-match rules and starting reputations (rematch)
+    /// The layout comes through initialize; this covers everything else init_match takes.
+    pub fn copy_rules_from(&mut self, source: &MatchState) {
+        self.rep_ledger_enabled = source.rep_ledger_enabled;
+        self.apply_starting_reputation(
+            source.player_a_rep_ledger.starting_reputation,
+            source.player_b_rep_ledger.starting_reputation,
+        );
+        self.delivery_less = source.delivery_less;
+        self.extra_delivery_spots = source.extra_delivery_spots;
+        self.start_grace = source.start_grace;
+        self.hide_live_scores = source.hide_live_scores;
+        self.max_sales_per_bucket = source.max_sales_per_bucket;
+        self.strain_unlock = source.strain_unlock;
+        self.shared_land = source.shared_land;
+        self.tunables = source.tunables;
+    }
+    
+    /// Reject planting a strain level the player's reputation hasn't unlocked
+    /// (checked against reputation at planting time)
+    pub fn check_strain_unlocked(&self, is_player_a: bool, strain_level: u8) -> Result<()> {
+        require!(
+            self.strain_unlock.is_unlocked(strain_level, self.reputation_of(is_player_a)),
+            DroogError::StrainLevelLocked
+        );
+        Ok(())
+    }
+    
+    /// Set the match window, enforcing end_ts > start_ts + MIN_MATCH_DURATION_SECONDS
+    /// Every path that sets start_ts/end_ts must go through here
+    pub fn set_match_window(&mut self, start_ts: i64, end_ts: i64) -> Result<()> {
+        require!(
+            end_ts > start_ts.saturating_add(MIN_MATCH_DURATION_SECONDS),
+            DroogError::InvalidMatchTiming
+        );
+        self.start_ts = start_ts;
+        self.end_ts = end_ts;
+        Ok(())
+    }
+    
+    /// Record that the player sold to this customer (served-customer bitmask)
+    pub fn mark_customer_served(&mut self, is_player_a: bool, customer_index: u8) {
+        let bit = 1u32 << customer_index;
+        if is_player_a {
+            self.player_a_served_mask |= bit;
+        } else {
+            self.player_b_served_mask |= bit;
+        }
+    }
+    
+    /// Award both players' diversity bonus (finalize, before picking the winner)
+    /// DIVERSITY_REP_PER_CUSTOMER per distinct customer served, clamped and
+    /// logged like any other reputation change. Returns the applied (a, b) deltas.
+    pub fn apply_diversity_bonus(&mut self) -> (i32, i32) {
+        let (a_bonus, b_bonus) = self.diversity_bonus();
+        (
+            self.adjust_reputation(true, a_bonus, REP_REASON_DIVERSITY),
+            self.adjust_reputation(false, b_bonus, REP_REASON_DIVERSITY),
+        )
+    }
+    
+    /// Diversity bonus each player would get at finalize (before clamping)
+    pub fn diversity_bonus(&self) -> (i32, i32) {
+        (
+            diversity_score(self.player_a_served_mask) as i32 * DIVERSITY_REP_PER_CUSTOMER,
+            diversity_score(self.player_b_served_mask) as i32 * DIVERSITY_REP_PER_CUSTOMER,
+        )
+    }
+    
+    /// (a, b) reputation as finalize will see it: with the diversity bonus
+    /// applied, unless finalize already applied it
+    pub fn projected_reputation(&self) -> (i32, i32) {
+        if self.is_finalized() {
+            return (self.player_a_reputation, self.player_b_reputation);
+        }
+        let (a_bonus, b_bonus) = self.diversity_bonus();
+        (
+            Self::clamp_reputation(self.player_a_reputation.saturating_add(a_bonus)),
+            Self::clamp_reputation(self.player_b_reputation.saturating_add(b_bonus)),
+        )
+    }
+    
+    /// Count a sale against the player's per-bucket cap (if enabled)
+    /// The count resets when the rotation bucket changes, so inventory has to
+    /// be sold steadily instead of dumped at the end
+    pub fn record_sale_for_rate_cap(&mut self, is_player_a: bool, current_ts: i64) -> Result<()> {
+        if self.max_sales_per_bucket == 0 {
+            return Ok(());
+        }
+        let bucket = deterministic::rotation_bucket(current_ts);
+        let (sales_bucket, bucket_sales) = if is_player_a {
+            (&mut self.player_a_sales_bucket, &mut self.player_a_bucket_sales)
+        } else {
+            (&mut self.player_b_sales_bucket, &mut self.player_b_bucket_sales)
+        };
+        if *sales_bucket != bucket {
+            *sales_bucket = bucket;
+            *bucket_sales = 0;
+        }
+        require!(*bucket_sales < self.max_sales_per_bucket, DroogError::SalesRateExceeded);
+        *bucket_sales += 1;
+        Ok(())
+    }
+    
+    /// Momentum left after `elapsed` idle seconds
+    /// Halves every MOMENTUM_HALF_LIFE_SECONDS, interpolating linearly within a
+    /// half-life so the value falls smoothly instead of in steps
+    pub fn decay_momentum(value: i32, elapsed: i64) -> i32 {
+        if value <= 0 || elapsed <= 0 {
+            return value.max(0);
+        }
+        let half_lives = elapsed / MOMENTUM_HALF_LIFE_SECONDS;
+        if half_lives >= 31 {
+            return 0;
+        }
+        let halved = value >> half_lives;
+        let rem = elapsed % MOMENTUM_HALF_LIFE_SECONDS;
+        halved - ((halved as i64 * rem) / (2 * MOMENTUM_HALF_LIFE_SECONDS)) as i32
+    }
+    
+    /// A player's current momentum (stored value decayed up to current_ts)
+    pub fn momentum_of(&self, is_player_a: bool, current_ts: i64) -> i32 {
+        let (value, ts) = if is_player_a {
+            (self.player_a_momentum, self.player_a_momentum_ts)
+        } else {
+            (self.player_b_momentum, self.player_b_momentum_ts)
+        };
+        Self::decay_momentum(value, current_ts.saturating_sub(ts))
+    }
+    
+    /// Bump the seller's momentum for a sale; returns the new value
+    pub fn record_sale_momentum(&mut self, is_player_a: bool, current_ts: i64) -> i32 {
+        let momentum = self
+            .momentum_of(is_player_a, current_ts)
+            .saturating_add(MOMENTUM_PER_SALE)
+            .min(MOMENTUM_MAX);
+        if is_player_a {
+            self.player_a_momentum = momentum;
+            self.player_a_momentum_ts = current_ts;
+        } else {
+            self.player_b_momentum = momentum;
+            self.player_b_momentum_ts = current_ts;
+        }
+        momentum
+    }
+    
+    /// Reject planting/selling during the opening grace (if enabled)
+    pub fn check_start_grace(&self, current_ts: i64) -> Result<()> {
+        require!(
+            !self.start_grace() || current_ts >= self.start_ts.saturating_add(START_GRACE_SECONDS),
+            DroogError::StartGraceActive
+        );
+        Ok(())
+    }
+    
+    /// Whether a match finalized at finalized_at looks passively played
+    /// (near-zero sales from both, finalized right at the end, no rotations)
+    /// Only a signal for off-chain monitoring; settlement is unaffected
+    pub fn is_low_engagement(&self, rotation_count: u32, finalized_at: i64) -> bool {
+        self.player_a_sales.saturating_add(self.player_b_sales) <= LOW_ENGAGEMENT_MAX_SALES
+            && finalized_at.saturating_sub(self.end_ts) <= LOW_ENGAGEMENT_FINALIZE_WINDOW_SECONDS
+            && rotation_count == 0
+    }
+    
+    /// Only participants may finalize during the first FINALIZE_GRACE_SECONDS
+    /// after end_ts; after that anyone may (the payout still goes to the winner)
+    pub fn check_finalize_caller(&self, caller: Pubkey, current_ts: i64) -> Result<()> {
+        let is_participant = caller == self.player_a || caller == self.player_b;
+        require!(
+            is_participant || current_ts >= self.end_ts.saturating_add(FINALIZE_GRACE_SECONDS),
+            DroogError::UnauthorizedFinalization
+        );
+        Ok(())
+    }
+    
+    /// Whether finalize may run now: after end_ts, or early once the mercy rule fired
+    pub fn can_finalize_at(&self, current_ts: i64) -> bool {
+        current_ts >= self.end_ts || self.early_end_eligible()
+    }
+    
+    /// Claim the refresh incentive for a rotation bucket
+    /// Only the first refresher of a bucket is rewarded; returns false on re-claims
+    pub fn claim_refresh_incentive(&mut self, rotation_bucket: u64) -> bool {
+        if rotation_bucket <= self.last_refresh_reward_bucket {
+            return false;
+        }
+        self.last_refresh_reward_bucket = rotation_bucket;
+        true
+    }
+    
+    /// Credit a participant for triggering a delivery refresh
+    /// Prompt refreshes (right at the rotation boundary) also earn REFRESH_REP_BONUS.
+    /// Returns false if the caller is not a participant (keeper refresh, no credit).
+    pub fn credit_refresh(&mut self, caller: Pubkey, prompt: bool) -> bool {
+        let bonus = if prompt { Self::REFRESH_REP_BONUS } else { 0 };
+        
+        if caller == self.player_a {
+            self.player_a_refreshes = self.player_a_refreshes.saturating_add(1);
+            self.adjust_reputation(true, bonus, REP_REASON_REFRESH);
+        } else if caller == self.player_b {
+            self.player_b_refreshes = self.player_b_refreshes.saturating_add(1);
+            self.adjust_reputation(false, bonus, REP_REASON_REFRESH);
+        } else {
+            return false;
+        }
+        
+        true
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    
+    pub(crate) fn test_match() -> MatchState {
+        MatchState {
+            match_id: 1,
+            match_id_hash: [1u8; 32],
+            start_ts: 1_000,
+            end_ts: 1_600,
+            player_a: Pubkey::new_unique(),
+            player_b: Pubkey::new_unique(),
+            customers: core::array::from_fn(|i| CustomerState::new(MatchState::layer_from_index(i as u8))),
+            player_a_sales: 0,
+            player_b_sales: 0,
+            player_a_reputation: 0,
+            player_b_reputation: 0,
+            is_finalized: 0,
+            bump: 255,
+            player_a_refreshes: 0,
+            player_b_refreshes: 0,
+            algo_version: CURRENT_ALGO_VERSION,
+            layer_config: LayerConfig::DEFAULT,
+            last_refresh_reward_bucket: 0,
+            replay_seq: 0,
+            delivery_less: 0,
+            rep_ledger_enabled: 0,
+            player_a_rep_ledger: RepLedger::default(),
+            player_b_rep_ledger: RepLedger::default(),
+            early_end_eligible: 0,
+            extra_delivery_spots: crate::state::DEFAULT_EXTRA_DELIVERY_SPOTS,
+            start_grace: 0,
+            hide_live_scores: 0,
+            max_sales_per_bucket: 0,
+            player_a_sales_bucket: 0,
+            player_b_sales_bucket: 0,
+            player_a_bucket_sales: 0,
+            player_b_bucket_sales: 0,
+            player_a_served_mask: 0,
+            player_b_served_mask: 0,
+            strain_unlock: StrainUnlock::default(),
+            shared_land: 0,
+            player_a_momentum: 0,
+            player_a_momentum_ts: 0,
+            player_b_momentum: 0,
+            player_b_momentum_ts: 0,
+            tunables: GameTunables::DEFAULT,
+            winner: Pubkey::default(),
+            final_payout: 0,
+            finalized_ts: 0,
+            has_winner: 0,
+            padding: [0; 3],
+        }
+    }
+    
+    #[test]
+    fn test_apply_starting_reputation() {
+        let mut m = test_match();
+        
+        m.apply_starting_reputation(0, 25);
+        assert_eq!(m.player_a_reputation, 0);
+        assert_eq!(m.player_b_reputation, 25);
+        
+        // Out-of-bounds handicaps are clamped
+        m.apply_starting_reputation(i32::MIN, i32::MAX);
+        assert_eq!(m.player_a_reputation, MatchState::REP_MIN);
+        assert_eq!(m.player_b_reputation, MatchState::REP_MAX);
+    }
+    
+    #[test]
+    fn test_credit_refresh() {
+        let mut m = test_match();
+        let (a, b) = (m.player_a, m.player_b);
+        
+        // Prompt refresh by player A earns credit and the bonus
+        assert!(m.credit_refresh(a, true));
+        assert_eq!(m.player_a_refreshes, 1);
+        assert_eq!(m.player_a_reputation, MatchState::REFRESH_REP_BONUS);
+        
+        // Late refresh by player B is counted but earns no bonus
+        assert!(m.credit_refresh(b, false));
+        assert_eq!(m.player_b_refreshes, 1);
+        assert_eq!(m.player_b_reputation, 0);
+        
+        // Keeper refresh credits nobody
+        assert!(!m.credit_refresh(Pubkey::new_unique(), true));
+        assert_eq!(m.player_a_refreshes, 1);
+        assert_eq!(m.player_b_refreshes, 1);
+    }
+    
+    #[test]
+    fn test_opponent_action_attribution() {
+        let m = test_match();
+        let (a, b) = (m.player_a, m.player_b);
+        
+        // Layer 3 sale by A notifies B
+        let ev = m.significant_sale_event(a, 0, 3, 3, 1_100).unwrap();
+        assert_eq!((ev.actor, ev.opponent, ev.action), (a, b, OPPONENT_ACTION_SALE));
+        assert_eq!(ev.customer_index, 0);
+        
+        // Big negative swing on an outer customer by B notifies A
+        let ev = m.significant_sale_event(b, 15, 3, -3, 1_100).unwrap();
+        assert_eq!((ev.actor, ev.opponent), (b, a));
+        
+        // Routine outer-ring sale stays quiet
+        assert!(m.significant_sale_event(a, 15, 1, 1, 1_100).is_none());
+        
+        // Level 3 harvests notify; lower levels don't
+        let ev = m.significant_harvest_event(b, 3, 1_200).unwrap();
+        assert_eq!((ev.actor, ev.opponent, ev.action), (b, a, OPPONENT_ACTION_HARVEST));
+        assert!(m.significant_harvest_event(a, 2, 1_200).is_none());
+        
+        // Non-participants never produce an event
+        assert!(m.significant_harvest_event(Pubkey::new_unique(), 3, 1_200).is_none());
+    }
+    
+    #[test]
+    fn test_refresh_incentive_single_claim() {
+        let mut m = test_match();
+        let a = m.player_a;
+        
+        // First refresher of bucket 17 claims and (as a participant) earns rep
+        assert!(m.claim_refresh_incentive(17));
+        assert!(m.credit_refresh(a, true));
+        assert_eq!(m.player_a_reputation, MatchState::REFRESH_REP_BONUS);
+        
+        // Same bucket can't be claimed twice (nor an older one)
+        assert!(!m.claim_refresh_incentive(17));
+        assert!(!m.claim_refresh_incentive(16));
+        
+        // Next rotation is claimable again
+        assert!(m.claim_refresh_incentive(18));
+    }
+    
+    #[test]
+    fn test_replay_frames() {
+        use crate::state::replay::*;
+        
+        let mut m = test_match();
+        let (a, b) = (m.player_a, m.player_b);
+        
+        let frames = [
+            m.next_replay_frame(REPLAY_ACTION_PLANT, a, 2, 3, 1, 1_010),
+            m.next_replay_frame(REPLAY_ACTION_REPLACE, b, 0, 1, 2, 1_020),
+            m.next_replay_frame(REPLAY_ACTION_HARVEST, a, 2, 3, 1, 1_200),
+            m.next_replay_frame(REPLAY_ACTION_SELL, b, 14, 1, 2, 1_210),
+            m.next_replay_frame(REPLAY_ACTION_REFRESH, Pubkey::new_unique(), 5, 9, 0, 1_260),
+        ];
+        
+        // Monotonic seq from 0, actor flags attributed correctly
+        for (i, f) in frames.iter().enumerate() {
+            assert_eq!(f.seq, i as u32);
+        }
+        assert_eq!(m.replay_seq, 5);
+        let flags: Vec<u8> = frames.iter().map(|f| f.actor_flag).collect();
+        assert_eq!(flags, vec![
+            REPLAY_ACTOR_PLAYER_A, REPLAY_ACTOR_PLAYER_B, REPLAY_ACTOR_PLAYER_A,
+            REPLAY_ACTOR_PLAYER_B, REPLAY_ACTOR_OTHER,
+        ]);
+        
+        // Packed layout: seq | action | actor | p0 p1 p2 | ts
+        let sell = frames[3].pack();
+        assert_eq!(&sell[0..4], &3u32.to_le_bytes());
+        assert_eq!(sell[4..9], [REPLAY_ACTION_SELL, REPLAY_ACTOR_PLAYER_B, 14, 1, 2]);
+        assert_eq!(&sell[9..17], &1_210i64.to_le_bytes());
+        
+        // Concatenated stream is SIZE bytes per frame
+        let stream: Vec<u8> = frames.iter().flat_map(|f| f.pack()).collect();
+        assert_eq!(stream.len(), frames.len() * ReplayFrame::SIZE);
+        assert_eq!(stream[4 * ReplayFrame::SIZE + 4], REPLAY_ACTION_REFRESH);
+    }
+    
+    #[test]
+    fn test_delivery_access_modes() {
+        // Delivery-enabled match: needs delivery_state
+        let m = test_match();
+        assert!(m.check_delivery_access(true).unwrap());
+        assert_eq!(
+            m.check_delivery_access(false).unwrap_err(),
+            DroogError::DeliveryStateNotInitialized.into()
+        );
+        
+        // Delivery-less match: cooldowns only, with or without the account
+        let m = MatchState { delivery_less: 1, ..test_match() };
+        assert!(!m.check_delivery_access(false).unwrap());
+        assert!(!m.check_delivery_access(true).unwrap());
+    }
+    
+    #[test]
+    fn test_steal_cycle() {
+        let mut m = test_match();
+        let (a, b) = (m.player_a, m.player_b);
+        let idx = 0; // Inner core customer
+        
+        // A serves first: nothing to steal, records earned rep
+        assert_eq!(m.steal_reputation(idx, a, 1_010), 0);
+        m.player_a_reputation = 3;
+        m.customers[idx].last_served_ts = 1_010;
+        m.customers[idx].set_last_served_by(a);
+        m.customers[idx].last_rep_earned = 3;
+        
+        // Same player re-serving is never a steal
+        assert_eq!(m.steal_reputation(idx, a, 1_040), 0);
+        
+        // B steals inside the window: ceil(3 * 50%) = 2 moves from A to B
+        assert_eq!(m.steal_reputation(idx, b, 1_040), 2);
+        assert_eq!(m.player_a_reputation, 1);
+        assert_eq!(m.player_b_reputation, 2);
+        assert_eq!(m.customers[idx].last_rep_earned, 1);
+        
+        // B now holds the customer; A steals back outside the window -> nothing
+        m.customers[idx].last_served_ts = 1_040;
+        m.customers[idx].set_last_served_by(b);
+        m.customers[idx].last_rep_earned = 3;
+        assert_eq!(m.steal_reputation(idx, a, 1_040 + MatchState::STEAL_WINDOW_SECONDS), 0);
+        assert_eq!(m.player_b_reputation, 2);
+        
+        // Negative-rep serves leave nothing to steal
+        m.customers[idx].last_rep_earned = -2;
+        assert_eq!(m.steal_reputation(idx, a, 1_050), 0);
+    }
+    
+    #[test]
+    fn test_rep_ledger_sums_to_net_reputation() {
+        use crate::state::rep_ledger::{REP_LEDGER_LEN, REP_REASON_REPLACE};
+        
+        let mut m = MatchState { rep_ledger_enabled: 1, ..test_match() };
+        m.apply_starting_reputation(5, -2);
+        let (a, b) = (m.player_a, m.player_b);
+        
+        // Sale with variant + rush, a steal, a refresh bonus, a replace cost
+        let reasons = MatchState::sale_rep_reasons(1, 0, 5);
+        assert_eq!(reasons, REP_REASON_BASE | REP_REASON_VARIANT | REP_REASON_RUSH);
+        m.adjust_reputation(true, 9, reasons);
+        m.customers[0].last_served_ts = 1_010;
+        m.customers[0].set_last_served_by(a);
+        m.customers[0].last_rep_earned = 9;
+        assert_eq!(m.steal_reputation(0, b, 1_020), 5);
+        assert!(m.credit_refresh(b, true));
+        m.adjust_reputation(false, -1, REP_REASON_REPLACE);
+        
+        for (ledger, reputation) in [
+            (&m.player_a_rep_ledger, m.player_a_reputation),
+            (&m.player_b_rep_ledger, m.player_b_reputation),
+        ] {
+            assert!(ledger.is_complete());
+            assert_eq!(ledger.net_reputation(), reputation);
+        }
+        assert_eq!(m.player_a_rep_ledger.recent().next().unwrap().reasons, reasons);
+        assert_eq!(m.player_b_rep_ledger.recent().last().unwrap().reasons, REP_REASON_REPLACE);
+        
+        // Clamped changes record what was actually applied
+        m.adjust_reputation(true, 5_000, REP_REASON_BASE);
+        assert_eq!(m.player_a_reputation, MatchState::REP_MAX);
+        assert_eq!(m.player_a_rep_ledger.net_reputation(), MatchState::REP_MAX);
+        
+        // Ring buffer keeps only the most recent deltas
+        for _ in 0..REP_LEDGER_LEN {
+            m.adjust_reputation(false, 1, REP_REASON_BASE);
+        }
+        assert!(!m.player_b_rep_ledger.is_complete());
+        assert_eq!(m.player_b_rep_ledger.recent().count(), REP_LEDGER_LEN);
+        assert!(m.player_b_rep_ledger.recent().all(|e| e.delta == 1));
+        
+        // Disabled (default): reputation still changes, nothing is logged
+        let mut off = test_match();
+        off.adjust_reputation(true, 3, REP_REASON_BASE);
+        assert_eq!(off.player_a_reputation, 3);
+        assert_eq!(off.player_a_rep_ledger.appended, 0);
+    }
+    
+    #[test]
+    fn test_match_id_uses_full_hash() {
+        let mut hash_a = [7u8; 32];
+        let mut hash_b = [7u8; 32];
+        hash_a[31] = 1;
+        hash_b[31] = 2;
+        
+        // Shared 8-byte prefix no longer means a shared match_id
+        assert_eq!(hash_a[..8], hash_b[..8]);
+        assert_ne!(MatchState::derive_match_id(&hash_a), MatchState::derive_match_id(&hash_b));
+        
+        // ...and the id isn't just the raw prefix
+        let prefix = u64::from_le_bytes([7u8; 8]);
+        assert_ne!(MatchState::derive_match_id(&hash_a), prefix);
+        
+        // Deterministic for clients mirroring it
+        assert_eq!(MatchState::derive_match_id(&hash_a), MatchState::derive_match_id(&hash_a));
+        
+        // Every byte position matters
+        let ids: std::collections::HashSet<u64> = (0..32)
+            .map(|i| {
+                let mut h = [0u8; 32];
+                h[i] = 1;
+                MatchState::derive_match_id(&h)
+            })
+            .collect();
+        assert_eq!(ids.len(), 32);
+    }
+    
+    #[test]
+    fn test_mercy_rule_enables_early_finalize() {
+        use crate::state::stake_state::MercyRule;
+        
+        let rule = MercyRule { sales_margin: 5, sales_cap: 0 };
+        let mut m = test_match();
+        
+        // Close match: still has to run to end_ts
+        m.player_a_sales = 4;
+        m.player_b_sales = 1;
+        m.early_end_eligible = u8::from(rule.is_triggered(m.player_a_sales, m.player_b_sales));
+        assert!(!m.can_finalize_at(m.end_ts - 1));
+        assert!(m.can_finalize_at(m.end_ts));
+        
+        // Blowout: finalize is allowed immediately
+        m.player_a_sales = 6;
+        m.early_end_eligible = u8::from(rule.is_triggered(m.player_a_sales, m.player_b_sales));
+        assert!(m.can_finalize_at(m.start_ts + 1));
+    }
+    
+    #[test]
+    fn test_customer_patience_deterministic_and_bounded() {
+        let max = MatchState::CUSTOMER_PATIENCE_MAX_SECONDS;
+        
+        for seed in [0u64, 1, 12345, u64::MAX] {
+            for idx in 0..23u8 {
+                let patience = MatchState::customer_patience(seed, idx);
+                assert!((-max..=max).contains(&patience));
+                assert_eq!(patience, MatchState::customer_patience(seed, idx));
+                
+                // Cooldown stays within the patience band around the layer base
+                let base = MatchState::get_customer_cooldown(MatchState::layer_from_index(idx));
+                let cooldown = MatchState::get_cooldown_for_customer(seed, idx);
+                assert_eq!(cooldown, base + patience);
+                assert!(cooldown > 0);
+            }
+        }
+        
+        // Customers on the same map don't all share one temperament
+        let traits: Vec<i64> = (0..23u8).map(|i| MatchState::customer_patience(12345, i)).collect();
+        assert!(traits.iter().any(|&t| t != traits[0]));
+    }
+    
+    #[test]
+    fn test_availability_uses_patience() {
+        let mut m = test_match();
+        let idx = 5u8;
+        let cooldown = m.customer_cooldown(idx);
+        assert_eq!(cooldown, MatchState::get_cooldown_for_customer(m.match_id, idx));
+        
+        m.customers[idx as usize].player_a_last_served_ts = 1_000;
+        assert!(!m.is_customer_available(idx as usize, true, 1_000 + cooldown - 1));
+        assert!(m.is_customer_available(idx as usize, true, 1_000 + cooldown));
+    }
+    
+    #[test]
+    fn test_customer_cooldown_is_per_player() {
+        let mut m = test_match();
+        let idx = 4usize;
+        let cooldown = m.customer_cooldown(idx as u8);
+        
+        // A serves; B can serve the same customer right after
+        m.customers[idx].player_a_last_served_ts = 1_000;
+        assert!(!m.is_customer_available(idx, true, 1_000));
+        assert!(m.is_customer_available(idx, false, 1_000));
+        
+        // B serves back-to-back; each is now on their own cooldown
+        m.customers[idx].player_b_last_served_ts = 1_001;
+        assert!(!m.is_customer_available(idx, false, 1_001));
+        assert!(m.is_customer_available(idx, true, 1_000 + cooldown));
+        assert!(!m.is_customer_available(idx, false, 1_000 + cooldown));
+        assert!(m.is_customer_available(idx, false, 1_001 + cooldown));
+    }
+    
+    #[test]
+    fn test_start_grace_blocks_opening_actions() {
+        let mut m = test_match();
+        
+        // Off by default: play opens at start_ts
+        assert!(m.check_start_grace(m.start_ts).is_ok());
+        
+        // Enabled: rejected for START_GRACE_SECONDS, allowed afterward
+        m.start_grace = 1;
+        for ts in [m.start_ts, m.start_ts + START_GRACE_SECONDS - 1] {
+            assert_eq!(m.check_start_grace(ts).unwrap_err(), DroogError::StartGraceActive.into());
+        }
+        assert!(m.check_start_grace(m.start_ts + START_GRACE_SECONDS).is_ok());
+        assert!(m.check_start_grace(m.end_ts - 1).is_ok());
+    }
+    
+    #[test]
+    fn test_match_window_rejects_inverted_or_short() {
+        let mut m = test_match();
+        let (start, end) = (m.start_ts, m.end_ts);
+        
+        // Inverted, zero-length, and too-short windows are rejected and leave state untouched
+        for end_ts in [start - 1, start, start + MIN_MATCH_DURATION_SECONDS] {
+            assert_eq!(
+                m.set_match_window(start, end_ts).unwrap_err(),
+                DroogError::InvalidMatchTiming.into()
+            );
+        }
+        assert_eq!(m.set_match_window(i64::MAX, i64::MAX).unwrap_err(), DroogError::InvalidMatchTiming.into());
+        assert_eq!((m.start_ts, m.end_ts), (start, end));
+        
+        // The minimal valid window and the standard duration are accepted
+        m.set_match_window(start, start + MIN_MATCH_DURATION_SECONDS + 1).unwrap();
+        assert_eq!(m.end_ts, start + MIN_MATCH_DURATION_SECONDS + 1);
+        m.set_match_window(start, start + MATCH_DURATION_SECONDS).unwrap();
+        assert_eq!((m.start_ts, m.end_ts), (start, start + MATCH_DURATION_SECONDS));
+    }
+    
+    #[test]
+    fn test_low_engagement_flags_trivial_matches() {
+        let mut m = test_match();
+        
+        // Trivial: one sale, finalized right at end_ts, nobody ever rotated the board
+        m.player_a_sales = 1;
+        assert!(m.is_low_engagement(0, m.end_ts));
+        assert!(m.is_low_engagement(0, m.end_ts + LOW_ENGAGEMENT_FINALIZE_WINDOW_SECONDS));
+        
+        // Any sign of real play clears the flag
+        assert!(!m.is_low_engagement(1, m.end_ts));
+        assert!(!m.is_low_engagement(0, m.end_ts + LOW_ENGAGEMENT_FINALIZE_WINDOW_SECONDS + 1));
+        m.player_b_sales = 2;
+        assert!(!m.is_low_engagement(0, m.end_ts));
+        
+        // A real match: steady sales and rotations
+        m.player_a_sales = 9;
+        m.player_b_sales = 7;
+        assert!(!m.is_low_engagement(9, m.end_ts));
+    }
+    
+    #[test]
+    fn test_surrender_pays_opponent_once() {
+        let mut m = test_match();
+        
+        // Either player may concede; the opponent wins
+        assert_eq!(m.surrender_winner(m.player_a, m.start_ts).unwrap(), m.player_b);
+        assert_eq!(m.surrender_winner(m.player_b, m.end_ts - 1).unwrap(), m.player_a);
+        assert_eq!(
+            m.surrender_winner(Pubkey::new_unique(), m.start_ts).unwrap_err(),
+            DroogError::InvalidPlayer.into()
+        );
+        
+        // Not after end_ts: finalize decides ended matches
+        assert_eq!(m.surrender_winner(m.player_a, m.end_ts).unwrap_err(), DroogError::MatchEnded.into());
+        
+        // A second surrender hits the finalized match
+        m.is_finalized = 1;
+        assert_eq!(
+            m.surrender_winner(m.player_b, m.start_ts).unwrap_err(),
+            DroogError::MatchAlreadyFinalized.into()
+        );
+    }
+    
+    #[test]
+    fn test_result_snapshot_after_finalize_and_surrender() {
+        use crate::state::stake_state::tests::test_stake;
+        
+        let pot = 1_800_000;
+        let stake = test_stake();
+        
+        // Normal finalize: B outsold A and takes the pot
+        let mut m = test_match();
+        assert_eq!((m.winner(), m.final_payout, m.finalized_ts), (None, 0, 0));
+        m.player_b_sales = 6;
+        m.player_a_sales = 2;
+        let standings = m.determine_winner();
+        let (winner_amount, _) = stake.settlement_split(&standings, pot, 0);
+        m.record_result(Some(standings.winner), winner_amount, m.end_ts + 5);
+        assert!(m.is_finalized());
+        assert_eq!((m.winner(), m.final_payout, m.finalized_ts), (Some(m.player_b), pot, m.end_ts + 5));
+        
+        // Surrender: the opponent is recorded as the winner of the whole pot
+        let mut m = test_match();
+        let ts = m.start_ts + 30;
+        let winner = m.surrender_winner(m.player_b, ts).unwrap();
+        m.record_result(Some(winner), pot, ts);
+        assert_eq!((m.winner(), m.final_payout, m.finalized_ts), (Some(m.player_a), pot, ts));
+        assert_eq!(m.surrender_winner(m.player_a, ts).unwrap_err(), DroogError::MatchAlreadyFinalized.into());
+    }
+    
+    #[test]
+    fn test_result_snapshot_follows_delayed_settlement() {
+        use crate::state::stake_state::tests::test_stake;
+        use crate::state::{MatchStakeState, MatchStatus, PendingSettlement};
+        
+        // Delayed finalize: B outsold A, the pending amount is recorded
+        let mut m = MatchState { player_b_sales: 6, player_a_sales: 2, player_a_reputation: 10, ..test_match() };
+        let mut stake = MatchStakeState {
+            status: MatchStatus::Active,
+            delayed_settlement: true,
+            player_a: m.player_a,
+            player_b: m.player_b,
+            ..test_stake()
+        };
+        let pot = 1_800_000;
+        let consolation = MatchStakeState::loser_consolation(pot, 10);
+        stake.record_pending_settlement(
+            PendingSettlement { winner: m.player_b, amount: pot - consolation, loser_amount: consolation, ..Default::default() },
+            m.end_ts,
+        );
+        m.record_result(Some(m.player_b), stake.pending_settlement.amount, m.end_ts);
+        
+        // Overturned: the snapshot names the new winner and their amount
+        stake.freeze_settlement(m.end_ts + 1).unwrap();
+        stake.resolve_dispute(m.player_a, &m, m.end_ts + 100).unwrap();
+        m.revise_result(Some(stake.pending_settlement.winner), stake.pending_settlement.amount);
+        assert_eq!((m.winner(), m.final_payout), (Some(m.player_a), pot));
+        assert_eq!(m.finalized_ts, m.end_ts);
+        
+        // Executed: what actually left the escrow (one unit short of the record)
+        let paid = stake.pending_settlement.winner_payout(pot - 1).unwrap();
+        m.revise_result(Some(stake.pending_settlement.winner), paid);
+        assert_eq!((m.winner(), m.final_payout), (Some(m.player_a), pot - 1));
+        
+        // Voided: no winner and nothing paid, but still over
+        stake.status = MatchStatus::Settling;
+        stake.pending_settlement.frozen = true;
+        stake.reverse_finalization(m.end_ts + 200).unwrap();
+        m.revise_result(None, 0);
+        assert_eq!((m.winner(), m.final_payout, m.has_winner), (None, 0, 0));
+        assert!(m.is_finalized());
+    }
+    
+    #[test]
+    fn test_keeper_can_finalize_after_grace() {
+        let m = test_match();
+        let keeper = Pubkey::new_unique();
+        
+        // Participants may finalize right away
+        assert!(m.check_finalize_caller(m.player_a, m.end_ts).is_ok());
+        assert!(m.check_finalize_caller(m.player_b, m.end_ts).is_ok());
+        
+        // Anyone else waits out the grace window
+        assert_eq!(
+            m.check_finalize_caller(keeper, m.end_ts + FINALIZE_GRACE_SECONDS - 1).unwrap_err(),
+            DroogError::UnauthorizedFinalization.into()
+        );
+        assert!(m.check_finalize_caller(keeper, m.end_ts + FINALIZE_GRACE_SECONDS).is_ok());
+    }
+    
+    #[test]
+    fn test_rematch_state_keeps_pair_and_rules() {
+        let mut source = test_match();
+        source.rep_ledger_enabled = 1;
+        source.apply_starting_reputation(5, -3);
+        source.delivery_less = 1;
+        source.start_grace = 1;
+        source.hide_live_scores = 1;
+        source.max_sales_per_bucket = 2;
+        source.strain_unlock = StrainUnlock { level2_min_reputation: 5, level3_min_reputation: 15 };
+        source.shared_land = 1;
+        source.layer_config = LayerConfig { layer3_count: 5, layer2_count: 8, layer1_count: 10 };
+        // Played-out state that must not carry over
+        source.player_a_sales = 7;
+        source.player_b_reputation = 40;
+        source.customers[3].total_serves = 4;
+        source.is_finalized = 1;
+        source.replay_seq = 99;
+        
+        let mut rematch = test_match();
+        rematch.is_finalized = 1;
+        rematch.replay_seq = 12;
+        rematch.initialize(
+            77,
+            [9u8; 32],
+            source.player_a,
+            source.player_b,
+            5_000,
+            source.layer_config,
+            254,
+        ).unwrap();
+        rematch.copy_rules_from(&source);
+        
+        // Same pair (already ordered), fresh window and counters
+        assert_eq!((rematch.player_a, rematch.player_b), (source.player_a, source.player_b));
+        assert_eq!((rematch.match_id, rematch.match_id_hash, rematch.bump), (77, [9u8; 32], 254));
+        assert_eq!((rematch.start_ts, rematch.end_ts), (5_000, 5_000 + MATCH_DURATION_SECONDS));
+        assert_eq!((rematch.player_a_sales, rematch.replay_seq), (0, 0));
+        assert!(!rematch.is_finalized());
+        assert_eq!(rematch.customers[3].total_serves, 0);
+        for (i, customer) in rematch.customers.iter().enumerate() {
+            assert_eq!(customer.layer, source.layer_config.layer_from_index(i as u8));
+        }
+        
+        // Same rules and handicap
+        assert_eq!(rematch.layer_config, source.layer_config);
+        assert_eq!((rematch.player_a_reputation, rematch.player_b_reputation), (5, -3));
+        assert!(rematch.rep_ledger_enabled() && rematch.delivery_less());
+        assert!(rematch.start_grace() && rematch.hide_live_scores());
+        assert_eq!(rematch.max_sales_per_bucket, 2);
+        assert_eq!(rematch.strain_unlock, source.strain_unlock);
+        assert!(rematch.shared_land());
+    }
+    
+    #[test]
+    fn test_strain_unlock_progression() {
+        let mut m = test_match();
+        
+        // Off by default: every level is open, even at negative reputation
+        m.player_a_reputation = -10;
+        for level in 1..=3 {
+            assert!(m.check_strain_unlocked(true, level).is_ok());
+        }
+        
+        m.strain_unlock = StrainUnlock { level2_min_reputation: 5, level3_min_reputation: 15 };
+        assert!(m.strain_unlock.validate().is_ok());
+        m.player_a_reputation = 0;
+        assert!(m.check_strain_unlocked(true, 1).is_ok());
+        assert_eq!(m.check_strain_unlocked(true, 2).unwrap_err(), DroogError::StrainLevelLocked.into());
+        assert_eq!(m.check_strain_unlocked(true, 3).unwrap_err(), DroogError::StrainLevelLocked.into());
+        
+        // Grinding Level 1 sales unlocks Level 2...
+        m.adjust_reputation(true, 5, REP_REASON_BASE);
+        assert!(m.check_strain_unlocked(true, 2).is_ok());
+        assert_eq!(m.check_strain_unlocked(true, 3).unwrap_err(), DroogError::StrainLevelLocked.into());
+        
+        // ...then Level 3
+        m.adjust_reputation(true, 10, REP_REASON_BASE);
+        assert!(m.check_strain_unlocked(true, 3).is_ok());
+        
+        // Gates are per player and follow reputation back down
+        assert_eq!(m.check_strain_unlocked(false, 2).unwrap_err(), DroogError::StrainLevelLocked.into());
+        m.adjust_reputation(true, -1, REP_REASON_BASE);
+        assert_eq!(m.check_strain_unlocked(true, 3).unwrap_err(), DroogError::StrainLevelLocked.into());
+        
+        // Level 3 can't be easier than Level 2, and thresholds can't be negative
+        let inverted = StrainUnlock { level2_min_reputation: 10, level3_min_reputation: 5 };
+        assert_eq!(inverted.validate().unwrap_err(), DroogError::InvalidStrainUnlock.into());
+        let negative = StrainUnlock { level2_min_reputation: -1, level3_min_reputation: 0 };
+        assert_eq!(negative.validate().unwrap_err(), DroogError::InvalidStrainUnlock.into());
+    }
+    
+    #[test]
+    fn test_diversity_score_counts_distinct_customers() {
+        assert_eq!(diversity_score(0), 0);
+        assert_eq!(diversity_score(0b1011), 3);
+        assert_eq!(diversity_score((1 << 23) - 1), 23);
+        // Bits past the roster don't count
+        assert_eq!(diversity_score(u32::MAX), 23);
+        
+        let mut m = test_match();
+        for _ in 0..3 {
+            m.mark_customer_served(true, 22);
+        }
+        m.mark_customer_served(true, 0);
+        assert_eq!(diversity_score(m.player_a_served_mask), 2);
+        assert_eq!(m.player_b_served_mask, 0);
+    }
+    
+    #[test]
+    fn test_diversity_bonus_favors_broad_player() {
+        let mut m = test_match();
+        m.rep_ledger_enabled = 1;
+        
+        // Same sales and reputation: A covered 8 customers, B camped on 2
+        m.player_a_sales = 8;
+        m.player_b_sales = 8;
+        for customer in 0..8 {
+            m.mark_customer_served(true, customer);
+        }
+        for sale in 0..8 {
+            m.mark_customer_served(false, 10 + sale % 2);
+        }
+        assert_eq!(m.determine_winner().decided_by, DecidedBy::FirstMoverTiebreak);
+        
+        // The projection (preview_payout) matches what finalize applies
+        let projected = m.projected_reputation();
+        let projected_standings = m.standings_with_reputation(projected.0, projected.1);
+        let (a_bonus, b_bonus) = m.apply_diversity_bonus();
+        assert_eq!(projected, (m.player_a_reputation, m.player_b_reputation));
+        assert_eq!(projected_standings, m.determine_winner());
+        assert_eq!((a_bonus, b_bonus), (8 * DIVERSITY_REP_PER_CUSTOMER, 2 * DIVERSITY_REP_PER_CUSTOMER));
+        assert_eq!(m.player_b_rep_ledger.entries[0].delta, b_bonus);
+        assert_eq!(m.player_b_rep_ledger.entries[0].reasons, REP_REASON_DIVERSITY);
+        
+        // Broad coverage now wins the tie; it can't outweigh a sales lead
+        let standings = m.determine_winner();
+        assert_eq!(standings.winner, m.player_a);
+        assert_eq!(standings.decided_by, DecidedBy::ReputationTiebreak);
+        m.player_b_sales = 9;
+        assert_eq!(m.determine_winner().winner, m.player_b);
+    }
+    
+    #[test]
+    fn test_sales_rate_cap_per_bucket() {
+        let mut m = test_match();
+        let bucket_start = 1_020; // start of a 60s rotation bucket
+        
+        // Off by default
+        for _ in 0..10 {
+            assert!(m.record_sale_for_rate_cap(true, bucket_start).is_ok());
+        }
+        
+        // Capped at 2 per bucket per player
+        m.max_sales_per_bucket = 2;
+        assert!(m.record_sale_for_rate_cap(true, bucket_start).is_ok());
+        assert!(m.record_sale_for_rate_cap(true, bucket_start + 30).is_ok());
+        assert_eq!(
+            m.record_sale_for_rate_cap(true, bucket_start + 59).unwrap_err(),
+            DroogError::SalesRateExceeded.into()
+        );
+        
+        // The opponent has their own allowance
+        assert!(m.record_sale_for_rate_cap(false, bucket_start + 59).is_ok());
+        
+        // Next bucket: allowance restored
+        assert!(m.record_sale_for_rate_cap(true, bucket_start + 60).is_ok());
+        assert_eq!(m.player_a_bucket_sales, 1);
+        assert!(m.record_sale_for_rate_cap(true, bucket_start + 61).is_ok());
+        assert!(m.record_sale_for_rate_cap(true, bucket_start + 62).is_err());
+    }
+    
+    #[test]
+    fn test_standings_report_deciding_rule() {
+        let mut m = test_match();
+        
+        m.player_a_sales = 4;
+        m.player_b_sales = 6;
+        let s = m.determine_winner();
+        assert_eq!((s.winner, s.loser), (m.player_b, m.player_a));
+        assert_eq!((s.winner_sales, s.loser_sales), (6, 4));
+        assert_eq!(s.decided_by, DecidedBy::SalesMargin);
+        
+        m.player_a_sales = 7;
+        let s = m.determine_winner();
+        assert_eq!(s.winner, m.player_a);
+        assert_eq!(s.decided_by, DecidedBy::SalesMargin);
+        
+        // Equal sales and reputation (including 0-0) go to Player A by the tiebreak
+        for sales in [6, 0] {
+            m.player_a_sales = sales;
+            m.player_b_sales = sales;
+            let s = m.determine_winner();
+            assert_eq!((s.winner, s.loser), (m.player_a, m.player_b));
+            assert_eq!(s.decided_by, DecidedBy::FirstMoverTiebreak);
+        }
+    }
+    
+    #[test]
+    fn test_reputation_breaks_sales_ties() {
+        let mut m = test_match();
+        m.player_a_sales = 5;
+        m.player_b_sales = 5;
+        
+        // Higher reputation wins a sales tie, for either player
+        m.player_a_reputation = 3;
+        m.player_b_reputation = 8;
+        let s = m.determine_winner();
+        assert_eq!((s.winner, s.loser), (m.player_b, m.player_a));
+        assert_eq!((s.winner_sales, s.loser_sales), (5, 5));
+        assert_eq!(s.decided_by, DecidedBy::ReputationTiebreak);
+        
+        m.player_a_reputation = 9;
+        let s = m.determine_winner();
+        assert_eq!(s.winner, m.player_a);
+        assert_eq!(s.decided_by, DecidedBy::ReputationTiebreak);
+        
+        // Reputation is compared after clamping: both beyond REP_MAX are equal
+        m.player_a_reputation = MatchState::REP_MAX + 50;
+        m.player_b_reputation = MatchState::REP_MAX;
+        let s = m.determine_winner();
+        assert_eq!(s.winner, m.player_a);
+        assert_eq!(s.decided_by, DecidedBy::FirstMoverTiebreak);
+        
+        // Sales still come first
+        m.player_b_sales = 6;
+        m.player_a_reputation = MatchState::REP_MAX;
+        m.player_b_reputation = MatchState::REP_MIN;
+        let s = m.determine_winner();
+        assert_eq!(s.winner, m.player_b);
+        assert_eq!(s.decided_by, DecidedBy::SalesMargin);
+    }
+    
+    #[test]
+    fn test_hidden_scores_suppress_attributed_sale_events() {
+        use crate::state::replay::REPLAY_ACTION_SELL;
+        
+        let mut m = test_match();
+        let actor = m.player_a;
+        
+        // Normal match: a big sale notifies the opponent, replay names the seller
+        assert!(m.reveals_live_scores());
+        assert!(m.significant_sale_event(actor, 0, 3, 10, 1_100).is_some());
+        let frame = m.next_replay_frame(REPLAY_ACTION_SELL, m.sale_replay_actor(actor), 0, 3, 1, 1_100);
+        assert_eq!(frame.actor_flag, REPLAY_ACTOR_PLAYER_A);
+        
+        // Fog of war: nothing attributes the sale to a player
+        m.hide_live_scores = 1;
+        assert!(!m.reveals_live_scores());
+        assert!(m.significant_sale_event(actor, 0, 3, 10, 1_100).is_none());
+        let frame = m.next_replay_frame(REPLAY_ACTION_SELL, m.sale_replay_actor(m.player_b), 0, 3, 1, 1_101);
+        assert_eq!(frame.actor_flag, REPLAY_ACTOR_OTHER);
+        assert_eq!(frame.seq, 1); // sequence stays contiguous
+    }
+    
+    #[test]
+    fn test_strain_rotation_uses_tunables_snapshot() {
+        let mut m = test_match();
+        let start = m.start_ts;
+        // Defaults: Level 1 pattern [0,1] for the first 2 minutes
+        assert!(m.is_strain_active(0, start + 60));
+        assert!(!m.is_strain_active(2, start + 60));
+        
+        // A 30s period has already moved on to [2,0] by then
+        m.tunables.level1_rotation_seconds = 30;
+        assert!(m.is_strain_active(2, start + 60));
+        assert!(!m.is_strain_active(1, start + 60));
+        
+        // Level 2 rotates on its own period
+        m.tunables.level2_rotation_seconds = 10;
+        assert!(m.is_strain_active(4, start + 10));
+        assert!(m.is_strain_active(6, start + 10));
+    }
+    
+    #[test]
+    fn test_momentum_rises_on_sales_and_decays() {
+        let mut m = test_match();
+        assert_eq!(m.momentum_of(true, 1_000), 0);
+        
+        // Back-to-back sales stack; the opponent is unaffected
+        assert_eq!(m.record_sale_momentum(true, 1_000), MOMENTUM_PER_SALE);
+        assert_eq!(m.record_sale_momentum(true, 1_000), 2 * MOMENTUM_PER_SALE);
+        assert_eq!(m.momentum_of(false, 1_000), 0);
+        
+        // Inactivity halves it per half-life, smoothly in between
+        let start = 2 * MOMENTUM_PER_SALE;
+        assert_eq!(m.momentum_of(true, 1_000 + MOMENTUM_HALF_LIFE_SECONDS), start / 2);
+        assert_eq!(m.momentum_of(true, 1_000 + 2 * MOMENTUM_HALF_LIFE_SECONDS), start / 4);
+        let mid = m.momentum_of(true, 1_000 + MOMENTUM_HALF_LIFE_SECONDS / 2);
+        assert!(mid < start && mid > start / 2);
+        assert_eq!(m.momentum_of(true, 1_000 + 40 * MOMENTUM_HALF_LIFE_SECONDS), 0);
+        
+        // A later sale builds on the decayed value, not the stored one
+        let later = 1_000 + MOMENTUM_HALF_LIFE_SECONDS;
+        assert_eq!(m.record_sale_momentum(true, later), start / 2 + MOMENTUM_PER_SALE);
+        assert_eq!(m.player_a_momentum_ts, later);
+        
+        // Capped at MOMENTUM_MAX
+        for _ in 0..20 {
+            m.record_sale_momentum(false, 2_000);
+        }
+        assert_eq!(m.momentum_of(false, 2_000), MOMENTUM_MAX);
+        
+        assert_eq!(MatchState::decay_momentum(0, 100), 0);
+        assert_eq!(MatchState::decay_momentum(50, 0), 50);
+        assert_eq!(MatchState::decay_momentum(50, -5), 50);
+    }
+    
+    #[test]
+    fn test_zero_copy_layout_matches_borsh() {
+        // Explicit padding leaves no implicit gaps, so clients decoding the
+        // IDL (Borsh) layout read the same bytes the program maps in place
+        let mut customer = CustomerState::new(2);
+        customer.set_last_served_by(Pubkey::new_unique());
+        customer.last_served_ts = 1_234;
+        customer.last_rep_earned = -3;
+        assert_eq!(customer.try_to_vec().unwrap(), bytemuck::bytes_of(&customer));
+        assert_eq!(CustomerState::SIZE, core::mem::size_of::<CustomerState>());
+        assert_eq!(GameTunables::SIZE, core::mem::size_of::<GameTunables>());
+        assert_eq!(GameTunables::DEFAULT.try_to_vec().unwrap(), bytemuck::bytes_of(&GameTunables::DEFAULT));
+        
+        // Unserved customers report no last server
+        assert_eq!(CustomerState::new(1).last_served_by(), None);
+        assert_eq!(customer.last_served_by(), Some(customer.last_served_by));
+    }
 }
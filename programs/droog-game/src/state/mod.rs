@@ -1,11 +1,23 @@
 pub mod customer_state;
 pub mod delivery_state;
+pub mod double_or_nothing;
+pub mod game_config;
 pub mod grow_state;
 pub mod match_state;
+pub mod program_config;
+pub mod rep_ledger;
+pub mod replay;
+pub mod settlement_record;
 pub mod stake_state;
 
 pub use customer_state::*;
 pub use delivery_state::*;
+pub use double_or_nothing::*;
+pub use game_config::*;
 pub use grow_state::*;
 pub use match_state::*;
+pub use program_config::*;
+pub use rep_ledger::*;
+pub use replay::*;
+pub use settlement_record::*;
 pub use stake_state::*;
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use crate::errors::DroogError;
+
+/// PDA seed for the program-wide Config
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"config";
+
+/// Program-wide Config PDA (emergency kill-switch and treasury)
+/// Seeds: ["config"]
+/// 
+/// Created on first use by set_program_paused or set_treasury (DISPUTE_ADMIN
+/// only). While paused, every state-mutating instruction - gameplay, account
+/// setup, funding and settlement - rejects with ProgramPaused. Exempt (see
+/// PAUSE_EXEMPT_INSTRUCTIONS): read-only views, the admin's own instructions,
+/// and freeze_settlement, so a player can still dispute a result whose
+/// challenge window runs out during a pause. A deployment that never created
+/// the Config is never paused.
+#[account]
+pub struct ProgramConfig {
+    /// Kill-switch: reject all mutating instructions
+    pub paused: bool,
+    
+    /// Last time the switch was flipped
+    pub updated_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+    
+    /// Owner of the token account that receives the treasury rake
+    /// Pubkey::default() until set_treasury names one; no rake is taken before
+    pub treasury: Pubkey,
+}
+
+/// Instructions that run while paused (file names under instructions/)
+/// Every other instruction calls ProgramConfig::check_not_paused first
+pub const PAUSE_EXEMPT_INSTRUCTIONS: [&str; 15] = [
+    // Read-only views
+    "get_constants",
+    "get_customer_flavors",
+    "get_error_hint",
+    "get_match_checksum",
+    "get_match_insight",
+    "get_projected_ready",
+    "preview_payout",
+    "view_smell",
+    // The admin's own controls (lifting the pause, resolving disputes)
+    "set_program_paused",
+    "set_treasury",
+    "set_game_config",
+    "resolve_dispute",
+    "reverse_finalization",
+    // Player protection: the challenge window keeps running during a pause
+    "freeze_settlement",
+    // Module declarations
+    "mod",
+];
+
+impl ProgramConfig {
+    /// 8 (discriminator) + 1 (paused) + 8 (updated_at) + 1 (bump) + 32 (treasury)
+    pub const SIZE: usize = 8 + 1 + 8 + 1 + 32;
+    
+    /// Read the Config, if one exists
+    /// `config` is the account at the Config PDA address (checked by the
+    /// caller's seeds constraint); an empty account means no Config exists.
+    fn load(config: &AccountInfo) -> Result<Option<ProgramConfig>> {
+        if config.data_is_empty() {
+            return Ok(None);
+        }
+        let data = config.try_borrow_data()?;
+        Ok(Some(ProgramConfig::try_deserialize(&mut &data[..])?))
+    }
+    
+    /// Reject if the kill-switch is set
+    pub fn check_not_paused(config: &AccountInfo) -> Result<()> {
+        if let Some(config) = Self::load(config)? {
+            require!(!config.paused, DroogError::ProgramPaused);
+        }
+        Ok(())
+    }
+    
+    /// The configured treasury, if any (settlements rake only when there is one)
+    pub fn treasury(config: &AccountInfo) -> Result<Option<Pubkey>> {
+        Ok(Self::load(config)?
+            .map(|config| config.treasury)
+            .filter(|treasury| *treasury != Pubkey::default()))
+    }
+}
+
+/// Emitted when the dispute admin sets or clears the kill-switch
+#[event]
+pub struct ProgramPauseChangedEvent {
+    pub paused: bool,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the dispute admin names the treasury
+#[event]
+pub struct TreasuryChangedEvent {
+    pub treasury: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn with_config<T>(data: &mut [u8], read: impl FnOnce(&AccountInfo) -> T) -> T {
+        let key = Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &crate::ID).0;
+        let mut lamports = 1_000_000;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, data, &crate::ID, false, 0);
+        read(&info)
+    }
+    
+    fn check_config(data: &mut [u8]) -> Result<()> {
+        with_config(data, ProgramConfig::check_not_paused)
+    }
+    
+    fn config_data(paused: bool) -> Vec<u8> {
+        config_with_treasury(paused, Pubkey::default())
+    }
+    
+    fn config_with_treasury(paused: bool, treasury: Pubkey) -> Vec<u8> {
+        let mut data = Vec::with_capacity(ProgramConfig::SIZE);
+        ProgramConfig { paused, updated_at: 1_000, bump: 255, treasury }
+            .try_serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), ProgramConfig::SIZE);
+        data
+    }
+    
+    #[test]
+    fn test_kill_switch_blocks_until_cleared() {
+        // No Config deployed: never paused
+        assert!(check_config(&mut []).is_ok());
+        
+        // Set: mutations are rejected
+        assert_eq!(
+            check_config(&mut config_data(true)).unwrap_err(),
+            DroogError::ProgramPaused.into()
+        );
+        
+        // Cleared: normal operation resumes
+        assert!(check_config(&mut config_data(false)).is_ok());
+    }
+    
+    #[test]
+    fn test_every_mutating_instruction_checks_the_kill_switch() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/instructions");
+        let mut exempt_seen = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+            let source = std::fs::read_to_string(&path).unwrap();
+            let checks = source.contains("ProgramConfig::check_not_paused(&ctx.accounts.program_config)?;");
+            if PAUSE_EXEMPT_INSTRUCTIONS.contains(&name.as_str()) {
+                assert!(!checks, "{name} is listed as exempt but checks the kill-switch");
+                exempt_seen += 1;
+            } else {
+                assert!(checks, "{name} must call ProgramConfig::check_not_paused or be listed as exempt");
+            }
+        }
+        assert_eq!(exempt_seen, PAUSE_EXEMPT_INSTRUCTIONS.len(), "stale PAUSE_EXEMPT_INSTRUCTIONS entry");
+    }
+    
+    #[test]
+    fn test_treasury_is_separate_from_the_admin() {
+        // No Config, or a Config that never named one: no treasury (no rake)
+        assert_eq!(with_config(&mut [], ProgramConfig::treasury).unwrap(), None);
+        assert_eq!(with_config(&mut config_data(false), ProgramConfig::treasury).unwrap(), None);
+        
+        // Once named, it is whatever the admin set - not the admin itself
+        let treasury = Pubkey::new_unique();
+        let mut data = config_with_treasury(true, treasury);
+        assert_eq!(with_config(&mut data, ProgramConfig::treasury).unwrap(), Some(treasury));
+        assert_ne!(treasury, crate::state::DISPUTE_ADMIN);
+    }
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+// ============================================================================
+// REPUTATION LEDGER
+// ============================================================================
+//
+// Opt-in (per match, at init) ring buffer of each player's most recent
+// reputation deltas, tagged with the modifiers that produced them. Together
+// with the starting reputation it makes final scores auditable from the
+// MatchState account alone: while `appended <= REP_LEDGER_LEN`,
+// starting_reputation + sum(deltas) == current reputation.
+//
+// Deltas are the amounts actually applied (after REP_MIN/REP_MAX clamping).
+
+/// Entries kept per player (oldest are overwritten first)
+pub const REP_LEDGER_LEN: usize = 16;
+
+/// Reason bits (a sale combines several; every reason has its own bit)
+pub const REP_REASON_BASE: u16 = 1 << 0;
+pub const REP_REASON_VARIANT: u16 = 1 << 1;
+pub const REP_REASON_DEMAND: u16 = 1 << 2;
+pub const REP_REASON_RUSH: u16 = 1 << 3;
+pub const REP_REASON_STEAL: u16 = 1 << 4;
+pub const REP_REASON_REFRESH: u16 = 1 << 5;
+pub const REP_REASON_REPLACE: u16 = 1 << 6;
+pub const REP_REASON_EXPANSION: u16 = 1 << 7;
+/// Permanent capacity upgrade
+pub const REP_REASON_UPGRADE: u16 = 1 << 8;
+/// Finalize-time diversity bonus
+pub const REP_REASON_DIVERSITY: u16 = 1 << 9;
+/// Police raid penalty
+pub const REP_REASON_RAID: u16 = 1 << 10;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, Pod, Zeroable)]
+#[repr(C)]
+pub struct RepLedgerEntry {
+    /// Applied reputation change
+    pub delta: i32,
+    /// REP_REASON_* bitfield
+    pub reasons: u16,
+    /// Explicit alignment padding (MatchState is zero-copy); always zero
+    pub padding: [u8; 2],
+}
+
+impl RepLedgerEntry {
+    /// 4 (delta) + 2 (reasons) + 2 (padding)
+    pub const SIZE: usize = 4 + 2 + 2;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, Pod, Zeroable)]
+#[repr(C)]
+pub struct RepLedger {
+    /// Reputation at init (after handicap clamping)
+    pub starting_reputation: i32,
+    /// Ring buffer; entry `appended % REP_LEDGER_LEN` is written next
+    pub entries: [RepLedgerEntry; REP_LEDGER_LEN],
+    /// Total entries ever appended (saturating)
+    pub appended: u32,
+}
+
+impl RepLedger {
+    /// 4 (starting_reputation) + 16 * 8 (entries) + 4 (appended) = 136 bytes
+    pub const SIZE: usize = 4 + REP_LEDGER_LEN * RepLedgerEntry::SIZE + 4;
+    
+    pub fn append(&mut self, delta: i32, reasons: u16) {
+        let index = self.appended as usize % REP_LEDGER_LEN;
+        self.entries[index] = RepLedgerEntry { delta, reasons, padding: [0; 2] };
+        self.appended = self.appended.saturating_add(1);
+    }
+    
+    /// Retained entries, oldest first
+    pub fn recent(&self) -> impl Iterator<Item = &RepLedgerEntry> {
+        let len = (self.appended as usize).min(REP_LEDGER_LEN);
+        let start = self.appended as usize - len;
+        (start..self.appended as usize).map(move |i| &self.entries[i % REP_LEDGER_LEN])
+    }
+    
+    /// Whether every delta since init is still retained
+    pub fn is_complete(&self) -> bool {
+        (self.appended as usize) <= REP_LEDGER_LEN
+    }
+    
+    /// starting_reputation + retained deltas (the current reputation when complete)
+    pub fn net_reputation(&self) -> i32 {
+        self.recent()
+            .fold(self.starting_reputation, |acc, e| acc.saturating_add(e.delta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_reason_bits_are_distinct() {
+        let reasons = [
+            REP_REASON_BASE,
+            REP_REASON_VARIANT,
+            REP_REASON_DEMAND,
+            REP_REASON_RUSH,
+            REP_REASON_STEAL,
+            REP_REASON_REFRESH,
+            REP_REASON_REPLACE,
+            REP_REASON_EXPANSION,
+            REP_REASON_UPGRADE,
+            REP_REASON_DIVERSITY,
+            REP_REASON_RAID,
+        ];
+        let mut seen = 0u16;
+        for reason in reasons {
+            // One nonzero bit each, never shared
+            assert_eq!(reason.count_ones(), 1, "{reason:#x}");
+            assert_eq!(seen & reason, 0, "{reason:#x}");
+            seen |= reason;
+        }
+        
+        // Entries stay 8 bytes (the wider bitfield took the padding)
+        assert_eq!(std::mem::size_of::<RepLedgerEntry>(), RepLedgerEntry::SIZE);
+        assert_eq!(std::mem::size_of::<RepLedger>(), RepLedger::SIZE);
+    }
+}
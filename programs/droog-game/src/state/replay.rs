@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// REPLAY FRAMES
+// ============================================================================
+//
+// Compact, fixed-layout interop record emitted by every gameplay instruction.
+// Concatenating frames in seq order reconstructs the whole match as a byte
+// stream. Distinct from the rich per-instruction events, which may evolve;
+// this layout is frozen.
+//
+// Packed layout (17 bytes, little-endian):
+//
+//   offset  size  field
+//   0       4     seq         u32, per-match, starts at 0, +1 per frame
+//   4       1     action      REPLAY_ACTION_*
+//   5       1     actor_flag  REPLAY_ACTOR_*
+//   6       1     p0          action-specific (see below)
+//   7       1     p1
+//   8       1     p2
+//   9       8     ts          i64 unix timestamp
+//
+// Parameters per action:
+//   PLANT    p0 = slot_index      p1 = strain_level  p2 = variant_id
+//   HARVEST  p0 = slot_index      p1 = strain_level  p2 = variant_id
+//   SELL     p0 = customer_index  p1 = strain_level  p2 = variant_id
+//            (actor is OTHER in hide_live_scores matches)
+//   REPLACE  p0 = slot_index      p1 = new strain    p2 = variant_id
+//   REFRESH  p0 = active_count    p1 = rush_customer p2 = 0
+//   EXPAND   p0 = cap bonus       p1 = new capacity  p2 = 0
+//   PLANT_SHARED    p0 = shared slot index  p1 = strain_level  p2 = variant_id
+//   HARVEST_SHARED  p0 = shared slot index  p1 = strain_level  p2 = variant_id
+//   FERTILIZE       p0 = slot_index  p1 = strain_level  p2 = seconds skipped (<= 255)
+//   UPGRADE         p0 = total capacity bonus  p1 = new capacity  p2 = 0
+
+pub const REPLAY_ACTION_PLANT: u8 = 0;
+pub const REPLAY_ACTION_HARVEST: u8 = 1;
+pub const REPLAY_ACTION_SELL: u8 = 2;
+pub const REPLAY_ACTION_REPLACE: u8 = 3;
+pub const REPLAY_ACTION_REFRESH: u8 = 4;
+pub const REPLAY_ACTION_EXPANSION: u8 = 5;
+pub const REPLAY_ACTION_PLANT_SHARED: u8 = 6;
+pub const REPLAY_ACTION_HARVEST_SHARED: u8 = 7;
+pub const REPLAY_ACTION_FERTILIZE: u8 = 8;
+pub const REPLAY_ACTION_UPGRADE: u8 = 9;
+
+pub const REPLAY_ACTOR_PLAYER_A: u8 = 0;
+pub const REPLAY_ACTOR_PLAYER_B: u8 = 1;
+/// Non-participant (e.g. keeper-triggered refresh)
+pub const REPLAY_ACTOR_OTHER: u8 = 2;
+
+#[event]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReplayFrame {
+    pub seq: u32,
+    pub action: u8,
+    pub actor_flag: u8,
+    pub p0: u8,
+    pub p1: u8,
+    pub p2: u8,
+    pub ts: i64,
+}
+
+impl ReplayFrame {
+    /// Packed frame size in bytes
+    pub const SIZE: usize = 4 + 1 + 1 + 1 + 1 + 1 + 8;
+    
+    /// Serialize to the frozen 17-byte layout
+    pub fn pack(&self) -> [u8; Self::SIZE] {
+        let mut out = [0u8; Self::SIZE];
+        out[0..4].copy_from_slice(&self.seq.to_le_bytes());
+        out[4] = self.action;
+        out[5] = self.actor_flag;
+        out[6] = self.p0;
+        out[7] = self.p1;
+        out[8] = self.p2;
+        out[9..17].copy_from_slice(&self.ts.to_le_bytes());
+        out
+    }
+}
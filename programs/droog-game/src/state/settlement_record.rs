@@ -0,0 +1,412 @@
+use anchor_lang::prelude::*;
+use crate::state::match_state::{DecidedBy, Standings};
+use crate::state::stake_state::{MatchStakeState, PendingSettlement};
+
+/// PDA seed for a match's SettlementRecord
+pub const SETTLEMENT_RECORD_SEED: &[u8] = b"settlement";
+
+/// How a match's pot finally left escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SettlementOutcome {
+    /// Paid at finalize (immediate settlement)
+    #[default]
+    Paid,
+    /// Recorded at finalize, waiting on execute_settlement
+    Pending,
+    /// Delayed settlement paid by execute_settlement
+    Executed,
+    /// Voided by the dispute admin; both players refunded
+    Reversed,
+    /// Winner's share carried into a double-or-nothing continuation
+    RolledOver,
+    /// A player conceded; paid by surrender_match
+    Surrendered,
+}
+
+/// Per-match settlement history PDA
+/// Seeds: ["settlement", match_id_hash]
+/// 
+/// Written by finalize_match (or surrender_match) with the match's whole
+/// money flow, so it stays queryable long after MatchActivatedEvent /
+/// StakePayoutEvent have expired from RPC history. A delayed settlement is
+/// rewritten when the pot actually moves: executed, reversed or rolled into a
+/// double-or-nothing. The outflows (amount_burned + treasury_fee +
+/// loser_payout + winner_payout + finalizer_reward) sum to total_escrowed, unless tokens were
+/// sent to the escrow account directly (the payout is the actual balance).
+#[account]
+pub struct SettlementRecord {
+    /// Unique match identifier
+    pub match_id: u64,
+    
+    /// 32-byte hash used for PDA derivation (matches MatchState)
+    pub match_id_hash: [u8; 32],
+    
+    /// Paid winner (Player A in a draw)
+    pub winner: Pubkey,
+    
+    /// Loser (Player B in a draw)
+    pub loser: Pubkey,
+    
+    /// Rule that picked the winner, or Draw if the pot was split
+    pub decided_by: DecidedBy,
+    
+    /// Both players' stakes as escrowed (pre-burn)
+    pub total_escrowed: u64,
+    
+    /// Burned at activation (BURN_PERCENTAGE of total_escrowed)
+    pub amount_burned: u64,
+    
+    /// Treasury rake (RAKE_BPS of the post-burn pot; 0 with no treasury configured)
+    pub treasury_fee: u64,
+    
+    /// Loser's reputation consolation, or Player B's half of a draw
+    pub loser_payout: u64,
+    
+    /// Winner's payout, or Player A's half of a draw
+    /// While a delayed settlement is Pending this is the recorded payout
+    pub winner_payout: u64,
+    
+    /// Caller of finalize_match (a player, or a keeper after the grace window)
+    pub finalizer: Pubkey,
+    
+    /// Reward carved from the pot and paid to the finalizer (0 when disabled)
+    pub finalizer_reward: u64,
+    
+    /// Payout was deferred to execute_settlement
+    pub delayed_settlement: bool,
+    
+    /// Practice match (everything is 0)
+    pub practice: bool,
+    
+    /// finalize_match timestamp
+    pub finalized_at: i64,
+    
+    /// PDA bump seed
+    pub bump: u8,
+    
+    /// Where the pot went (Pending until a delayed settlement is resolved)
+    pub outcome: SettlementOutcome,
+    
+    /// When the pot left escrow (0 while Pending)
+    pub settled_at: i64,
+}
+
+impl SettlementRecord {
+    /// 8 (discriminator) + 8 (match_id) + 32 (match_id_hash) + 32 (winner) + 32 (loser)
+    /// + 1 (decided_by) + 8 (total_escrowed) + 8 (amount_burned) + 8 (treasury_fee)
+    /// + 8 (loser_payout) + 8 (winner_payout) + 32 (finalizer) + 8 (finalizer_reward)
+    /// + 1 (delayed_settlement) + 1 (practice) + 8 (finalized_at) + 1 (bump)
+    /// + 1 (outcome) + 8 (settled_at) = 212 bytes
+    pub const SIZE: usize = 8 + 8 + 32 + 32 + 32 + 1 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 1 + 1 + 8 + 1 + 1 + 8;
+    
+    /// Fill in the record for a match being finalized
+    /// winner_payout / loser_payout / finalizer_reward / treasury_fee are the
+    /// amounts finalize pays (or records)
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &mut self,
+        stake_state: &MatchStakeState,
+        standings: &Standings,
+        decided_by: DecidedBy,
+        winner_payout: u64,
+        loser_payout: u64,
+        finalizer: Pubkey,
+        finalizer_reward: u64,
+        treasury_fee: u64,
+        finalized_at: i64,
+        bump: u8,
+    ) {
+        let total_escrowed = stake_state.player_a_escrowed.saturating_add(stake_state.player_b_escrowed);
+        
+        self.match_id = stake_state.match_id;
+        self.match_id_hash = stake_state.match_id_hash;
+        self.winner = standings.winner;
+        self.loser = standings.loser;
+        self.decided_by = decided_by;
+        self.total_escrowed = total_escrowed;
+        self.amount_burned = MatchStakeState::calculate_burn_amount(total_escrowed);
+        self.treasury_fee = treasury_fee;
+        self.loser_payout = loser_payout;
+        self.winner_payout = winner_payout;
+        self.finalizer = finalizer;
+        self.finalizer_reward = finalizer_reward;
+        self.delayed_settlement = stake_state.delayed_settlement;
+        self.practice = stake_state.practice;
+        self.finalized_at = finalized_at;
+        self.bump = bump;
+        if stake_state.delayed_settlement {
+            self.outcome = SettlementOutcome::Pending;
+            self.settled_at = 0;
+        } else {
+            self.outcome = SettlementOutcome::Paid;
+            self.settled_at = finalized_at;
+        }
+    }
+    
+    /// Rewrite a pending record with what execute_settlement paid
+    /// The winner may differ from finalize's if the dispute admin overturned it
+    pub fn record_execution(&mut self, pending: &PendingSettlement, winner_payout: u64, executed_at: i64) {
+        if pending.winner != self.winner {
+            self.loser = self.winner;
+            self.winner = pending.winner;
+        }
+        self.treasury_fee = pending.rake;
+        self.loser_payout = pending.loser_amount;
+        self.finalizer_reward = pending.finalizer_reward;
+        self.winner_payout = winner_payout;
+        self.outcome = SettlementOutcome::Executed;
+        self.settled_at = executed_at;
+    }
+    
+    /// Rewrite a pending record with the dispute refunds: nothing goes to the
+    /// treasury or the finalizer, and each player gets their refund
+    pub fn record_reversal(&mut self, stake_state: &MatchStakeState, player_a_refund: u64, player_b_refund: u64, reversed_at: i64) {
+        let (winner_refund, loser_refund) = if self.winner == stake_state.player_a {
+            (player_a_refund, player_b_refund)
+        } else {
+            (player_b_refund, player_a_refund)
+        };
+        self.treasury_fee = 0;
+        self.finalizer_reward = 0;
+        self.winner_payout = winner_refund;
+        self.loser_payout = loser_refund;
+        self.outcome = SettlementOutcome::Reversed;
+        self.settled_at = reversed_at;
+    }
+    
+    /// Rewrite a pending record for a double-or-nothing: the other shares are
+    /// paid as recorded, and winner_payout is what was carried over
+    pub fn record_rollover(&mut self, pending: &PendingSettlement, carried: u64, rolled_at: i64) {
+        self.record_execution(pending, carried, rolled_at);
+        self.outcome = SettlementOutcome::RolledOver;
+    }
+    
+    /// Fill in the record for a surrendered match: the conceding player is
+    /// the loser (and the recorded caller) and the opponent takes the raked pot
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_surrender(
+        &mut self,
+        stake_state: &MatchStakeState,
+        winner: Pubkey,
+        loser: Pubkey,
+        winner_payout: u64,
+        treasury_fee: u64,
+        surrendered_at: i64,
+        bump: u8,
+    ) {
+        let standings = Standings {
+            winner,
+            loser,
+            winner_sales: 0,
+            loser_sales: 0,
+            decided_by: DecidedBy::Surrender,
+        };
+        self.record(
+            stake_state,
+            &standings,
+            DecidedBy::Surrender,
+            winner_payout,
+            0,
+            loser,
+            0,
+            treasury_fee,
+            surrendered_at,
+            bump,
+        );
+        // Surrender pays out immediately, even for delayed-settlement matches
+        self.outcome = SettlementOutcome::Surrendered;
+        self.settled_at = surrendered_at;
+    }
+    
+    /// Derive the settlement record PDA for a match
+    pub fn derive_pda(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+        crate::pda::derive_settlement_record_pda(match_id_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::match_state::tests::test_match;
+    use crate::state::stake_state::tests::test_stake;
+    use crate::state::{MatchState, MatchStatus, STAKE_AMOUNT};
+    
+    fn empty_record() -> SettlementRecord {
+        SettlementRecord {
+            match_id: 0,
+            match_id_hash: [0u8; 32],
+            winner: Pubkey::default(),
+            loser: Pubkey::default(),
+            decided_by: DecidedBy::SalesMargin,
+            total_escrowed: 0,
+            amount_burned: 0,
+            treasury_fee: 0,
+            loser_payout: 0,
+            winner_payout: 0,
+            finalizer: Pubkey::default(),
+            finalizer_reward: 0,
+            delayed_settlement: false,
+            practice: false,
+            finalized_at: 0,
+            bump: 0,
+            outcome: SettlementOutcome::Paid,
+            settled_at: 0,
+        }
+    }
+    
+    fn outflows(record: &SettlementRecord) -> u64 {
+        record.amount_burned + record.treasury_fee + record.loser_payout + record.winner_payout
+            + record.finalizer_reward
+    }
+    
+    #[test]
+    fn test_record_accounts_for_whole_escrow() {
+        let mut m = test_match();
+        let stake = MatchStakeState {
+            status: MatchStatus::Active,
+            player_b_escrowed: STAKE_AMOUNT,
+            ..test_stake()
+        };
+        let total = 2 * STAKE_AMOUNT;
+        let escrow_balance = total - MatchStakeState::calculate_burn_amount(total);
+        
+        // B wins; after the treasury rake, A's reputation earns a consolation
+        // and A's finalize a reward, both carved from the pot
+        m.player_b_sales = 4;
+        m.player_a_reputation = 20;
+        let standings = m.determine_winner();
+        let rake = MatchStakeState::treasury_rake(escrow_balance, Some(Pubkey::new_unique())).unwrap();
+        let reward = stake.finalizer_reward(escrow_balance - rake, 100, &stake.player_a);
+        let (winner_amount, loser_amount) = stake.settlement_split(&standings, escrow_balance - rake - reward, 20);
+        assert!(loser_amount > 0 && reward > 0 && rake > 0);
+        
+        let mut record = empty_record();
+        record.record(
+            &stake, &standings, standings.decided_by, winner_amount, loser_amount, stake.player_a, reward, rake, 2_000, 254,
+        );
+        assert_eq!((record.winner, record.loser), (m.player_b, m.player_a));
+        assert_eq!(record.total_escrowed, total);
+        assert_eq!(record.amount_burned, MatchStakeState::calculate_burn_amount(total));
+        assert_eq!(record.treasury_fee, rake);
+        assert_eq!((record.finalizer, record.finalizer_reward), (stake.player_a, reward));
+        assert_eq!(outflows(&record), record.total_escrowed);
+        assert_eq!((record.match_id_hash, record.finalized_at, record.bump), (stake.match_id_hash, 2_000, 254));
+        
+        // Odd pot split in a draw still drains the escrow exactly
+        m.player_a_sales = 4;
+        m.player_a_reputation = 0;
+        let odd = MatchStakeState { player_a_escrowed: STAKE_AMOUNT + 1, ..stake };
+        let total = odd.player_a_escrowed + odd.player_b_escrowed;
+        let escrow_balance = total - MatchStakeState::calculate_burn_amount(total);
+        let standings = m.determine_winner();
+        assert!(odd.settles_as_draw(&standings));
+        let rake = MatchStakeState::treasury_rake(escrow_balance, Some(Pubkey::new_unique())).unwrap();
+        let (a_half, b_half) = odd.settlement_split(&standings, escrow_balance - rake, 0);
+        record.record(&odd, &standings, DecidedBy::Draw, a_half, b_half, m.player_b, 0, rake, 2_000, 254);
+        assert_eq!(record.decided_by, DecidedBy::Draw);
+        assert_eq!(outflows(&record), total);
+        
+        // Practice: nothing moved at all
+        let practice = MatchStakeState {
+            practice: true,
+            player_a_escrowed: 0,
+            stake_amount: 0,
+            player_b_stake_amount: 0,
+            ..test_stake()
+        };
+        record.record(&practice, &standings, DecidedBy::Draw, 0, 0, m.player_a, 0, 0, 2_000, 254);
+        assert!(record.practice);
+        assert_eq!((record.total_escrowed, outflows(&record)), (0, 0));
+    }
+    
+    #[test]
+    fn test_delayed_record_follows_the_pot() {
+        let mut stake = MatchStakeState {
+            status: MatchStatus::Active,
+            delayed_settlement: true,
+            player_b_escrowed: STAKE_AMOUNT,
+            ..test_stake()
+        };
+        let m = MatchState {
+            player_a: stake.player_a,
+            player_b: stake.player_b,
+            player_b_sales: 4,
+            ..test_match()
+        };
+        let total = 2 * STAKE_AMOUNT;
+        let escrow_balance = total - MatchStakeState::calculate_burn_amount(total);
+        let standings = m.determine_winner();
+        let pending = PendingSettlement {
+            winner: standings.winner,
+            amount: escrow_balance - 30_000,
+            rake: 18_000,
+            loser_amount: 8_000,
+            finalizer: stake.player_a,
+            finalizer_reward: 4_000,
+            ..Default::default()
+        };
+        stake.record_pending_settlement(pending, 2_000);
+        
+        // Finalize writes the recorded split, still Pending
+        let mut finalized = empty_record();
+        finalized.record(&stake, &standings, standings.decided_by, pending.amount, 8_000, stake.player_a, 4_000, 18_000, 2_000, 254);
+        assert_eq!((finalized.outcome, finalized.settled_at), (SettlementOutcome::Pending, 0));
+        assert_eq!(outflows(&finalized), total);
+        
+        // Executed: what execute paid, with the payout time
+        let mut record = finalized.clone();
+        let winner_payout = stake.pending_settlement.winner_payout(escrow_balance).unwrap();
+        record.record_execution(&stake.pending_settlement, winner_payout, 9_000);
+        assert_eq!((record.outcome, record.settled_at), (SettlementOutcome::Executed, 9_000));
+        assert_eq!((record.winner, record.winner_payout), (m.player_b, pending.amount));
+        assert_eq!(outflows(&record), total);
+        
+        // Overturned by the dispute admin: the record names the paid player
+        let mut overturned = stake.clone();
+        overturned.freeze_settlement(2_100).unwrap();
+        overturned.resolve_dispute(m.player_a, &m, 3_000).unwrap();
+        let mut record = finalized.clone();
+        let overturned_payout = overturned.pending_settlement.winner_payout(escrow_balance).unwrap();
+        record.record_execution(&overturned.pending_settlement, overturned_payout, 9_000);
+        assert_eq!((record.winner, record.loser), (m.player_a, m.player_b));
+        assert_eq!(record.loser_payout, overturned.pending_settlement.loser_amount);
+        assert_eq!(outflows(&record), total);
+        
+        // Reversed: refunds only, nothing to the treasury or the finalizer
+        let mut record = finalized.clone();
+        let (a_refund, b_refund) = stake.dispute_refund_split(escrow_balance);
+        record.record_reversal(&stake, a_refund, b_refund, 3_000);
+        assert_eq!(record.outcome, SettlementOutcome::Reversed);
+        assert_eq!((record.treasury_fee, record.finalizer_reward), (0, 0));
+        assert_eq!((record.winner_payout, record.loser_payout), (b_refund, a_refund));
+        assert_eq!(outflows(&record), total);
+        
+        // Rolled into a double-or-nothing: the winner's share moved on
+        let mut record = finalized.clone();
+        record.record_rollover(&stake.pending_settlement, winner_payout, 3_000);
+        assert_eq!((record.outcome, record.settled_at), (SettlementOutcome::RolledOver, 3_000));
+        assert_eq!(outflows(&record), total);
+    }
+    
+    #[test]
+    fn test_surrender_is_recorded() {
+        let stake = MatchStakeState {
+            status: MatchStatus::Active,
+            delayed_settlement: true,
+            player_b_escrowed: STAKE_AMOUNT,
+            ..test_stake()
+        };
+        let total = 2 * STAKE_AMOUNT;
+        let escrow_balance = total - MatchStakeState::calculate_burn_amount(total);
+        let rake = MatchStakeState::treasury_rake(escrow_balance, Some(Pubkey::new_unique())).unwrap();
+        
+        // A concedes: B takes the raked pot at once, even on a delayed match
+        let mut record = empty_record();
+        record.record_surrender(&stake, stake.player_b, stake.player_a, escrow_balance - rake, rake, 1_500, 253);
+        assert_eq!((record.winner, record.loser, record.finalizer), (stake.player_b, stake.player_a, stake.player_a));
+        assert_eq!(record.decided_by, DecidedBy::Surrender);
+        assert_eq!((record.outcome, record.settled_at, record.bump), (SettlementOutcome::Surrendered, 1_500, 253));
+        assert_eq!((record.treasury_fee, record.loser_payout, record.finalizer_reward), (rake, 0, 0));
+        assert_eq!(outflows(&record), total);
+    }
+}
@@ -1,12 +1,27 @@
 use anchor_lang::prelude::*;
+use crate::errors::DroogError;
+use crate::state::match_state::{DecidedBy, MatchState, Standings};
 
 // ============================================================================
 // STAKING CONSTANTS
 // ============================================================================
 
-/// Fixed stake amount per player in raw token units (1 token with 6 decimals)
+/// Standard (casual lobby) stake per player in raw token units (1 token with 6 decimals)
+/// Each match picks its own stake_amount at init; Player A may raise it while
+/// Pending (add_stake) and Player B matches it at join (or stakes their own
+/// agreed amount in a handicapped match)
 pub const STAKE_AMOUNT: u64 = 1_000_000;
 
+/// Largest per-player stake a match may use, including raises (1,000 tokens)
+pub const MAX_STAKE_AMOUNT: u64 = 1_000 * STAKE_AMOUNT;
+
+/// Loser consolation: raw token units carved from the pot per point of the
+/// loser's positive reputation (0.001 token per point)
+pub const LOSER_CONSOLATION_PER_REP: u64 = 1_000;
+
+/// Loser consolation never exceeds this percentage of the pot
+pub const LOSER_CONSOLATION_MAX_PERCENT: u64 = 10;
+
 /// Burn percentage (10% = burned at match activation)
 pub const BURN_PERCENTAGE: u64 = 10;
 
@@ -16,6 +31,29 @@ pub const TOKEN_DECIMALS: u8 = 6;
 /// Cancel timeout in seconds (Player A can cancel after this if Player B never joins)
 pub const CANCEL_TIMEOUT_SECONDS: i64 = 300; // 5 minutes
 
+/// Abandonment horizon in seconds (anyone can reap a Pending match after this)
+pub const ABANDONED_MATCH_HORIZON_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Challenge window for delayed settlement (either player may freeze the payout)
+pub const SETTLEMENT_CHALLENGE_WINDOW_SECONDS: i64 = 300; // 5 minutes
+
+/// How long a frozen settlement waits on the dispute admin, counted from the
+/// end of the challenge window; after this it executes to the recorded winner
+pub const DISPUTE_RESOLUTION_WINDOW_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+/// Dispute admin for the beta: may void a delayed settlement (refunding both
+/// players) while its challenge window is open or after a player froze it,
+/// or resolve a frozen settlement in favour of one player
+pub const DISPUTE_ADMIN: Pubkey = pubkey!("38Bn773NrznC7SGjceY68SUWuTz5dv6aodWcwvPXQcCf");
+
+/// Treasury rake: basis points of the pot skimmed at settlement (1%), paid
+/// to the treasury named in ProgramConfig
+pub const RAKE_BPS: u16 = 100;
+
+/// PDA seed prefix for the escrow authority
+/// Every instruction that signs for the escrow uses [ESCROW_AUTH_SEED, match_id_hash, bump]
+pub const ESCROW_AUTH_SEED: &[u8] = b"escrow_auth";
+
 // ============================================================================
 // MATCH STATUS
 // ============================================================================
@@ -25,7 +63,9 @@ pub const CANCEL_TIMEOUT_SECONDS: i64 = 300; // 5 minutes
 /// State transitions:
 /// - Pending -> Active (when Player B joins and burn occurs)
 /// - Pending -> Cancelled (when Player A cancels after timeout)
-/// - Active -> Finalized (when match ends and winner is paid)
+/// - Active -> Finalized (when match ends and winner is paid, or a player surrenders)
+/// - Active -> Settling -> Finalized (delayed settlement: payout after challenge window)
+/// - Settling -> Disputed (dispute admin voids the result, both players refunded)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum MatchStatus {
     /// Player A has staked, waiting for Player B
@@ -44,6 +84,14 @@ pub enum MatchStatus {
     /// Match cancelled before Player B joined
     /// Player A has been refunded, escrow is empty
     Cancelled,
+    
+    /// Match finalized with delayed settlement; payout recorded but not transferred
+    /// Escrow still holds the pot until execute_settlement
+    Settling,
+    
+    /// Finalization voided by the dispute admin during settlement
+    /// Both players refunded, escrow is empty
+    Disputed,
 }
 
 impl MatchStatus {
@@ -51,6 +99,241 @@ impl MatchStatus {
     pub const SIZE: usize = 1;
 }
 
+/// Coarse lifecycle phase derived from MatchStatus
+/// 
+/// Instructions declare the phases they are valid in (LifecycleAction) so
+/// the ordering rules live in one place instead of per-instruction status checks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LifecyclePhase {
+    /// Waiting for Player B (Pending)
+    Lobby,
+    /// Both players committed, match running (Active)
+    Live,
+    /// Result recorded, payout waiting on the challenge window (Settling)
+    Settling,
+    /// Nothing left in escrow (Finalized, Cancelled, Disputed)
+    Closed,
+}
+
+/// Stake-gated instructions, each declaring the lifecycle phases it is valid in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LifecycleAction {
+    Join,
+    Cancel,
+    Reap,
+    RaiseStake,
+    /// plant / harvest / sell / replace / expansion
+    Gameplay,
+    RefreshDelivery,
+    Finalize,
+    Surrender,
+    FreezeSettlement,
+    ExecuteSettlement,
+    ReverseFinalization,
+    ResolveDispute,
+    /// offer / accept a double-or-nothing continuation (pot still in escrow)
+    DoubleOrNothing,
+    /// close_match_accounts (rent reclaim)
+    CloseAccounts,
+}
+
+impl LifecycleAction {
+    /// Phases this instruction may run in
+    pub fn valid_phases(self) -> &'static [LifecyclePhase] {
+        match self {
+            LifecycleAction::Join
+            | LifecycleAction::Cancel
+            | LifecycleAction::Reap
+            | LifecycleAction::RaiseStake => &[LifecyclePhase::Lobby],
+            LifecycleAction::Gameplay
+            | LifecycleAction::RefreshDelivery
+            | LifecycleAction::Finalize
+            | LifecycleAction::Surrender => &[LifecyclePhase::Live],
+            LifecycleAction::FreezeSettlement
+            | LifecycleAction::ExecuteSettlement
+            | LifecycleAction::ReverseFinalization
+            | LifecycleAction::ResolveDispute
+            | LifecycleAction::DoubleOrNothing => &[LifecyclePhase::Settling],
+            LifecycleAction::CloseAccounts => &[LifecyclePhase::Closed],
+        }
+    }
+    
+    /// Error reported when invoked in an invalid phase
+    /// Gameplay in the Lobby gets MatchNotActivated so clients can tell
+    /// "opponent hasn't joined" apart from "match is over"
+    fn phase_error(self, phase: LifecyclePhase) -> DroogError {
+        match self {
+            LifecycleAction::Join
+            | LifecycleAction::Cancel
+            | LifecycleAction::Reap
+            | LifecycleAction::RaiseStake => DroogError::MatchNotPending,
+            LifecycleAction::Gameplay | LifecycleAction::RefreshDelivery
+                if phase == LifecyclePhase::Lobby =>
+            {
+                DroogError::MatchNotActivated
+            }
+            LifecycleAction::Gameplay
+            | LifecycleAction::RefreshDelivery
+            | LifecycleAction::Finalize
+            | LifecycleAction::Surrender => DroogError::MatchNotActive,
+            LifecycleAction::FreezeSettlement
+            | LifecycleAction::ExecuteSettlement
+            | LifecycleAction::ReverseFinalization
+            | LifecycleAction::ResolveDispute
+            | LifecycleAction::DoubleOrNothing => DroogError::SettlementNotPending,
+            LifecycleAction::CloseAccounts => DroogError::MatchNotClosed,
+        }
+    }
+}
+
+/// Intended payout recorded by finalize_match under delayed settlement
+/// The same split an immediate settlement would have paid, held for
+/// execute_settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PendingSettlement {
+    /// Player who will receive the winner's share (Player A in a draw)
+    pub winner: Pubkey,
+    
+    /// Winner's share: the escrow less the rake, finalizer reward and loser_amount
+    pub amount: u64,
+    
+    /// execute_settlement is allowed from this timestamp on
+    pub executable_at_ts: i64,
+    
+    /// Set by a player during the challenge window; blocks execution until
+    /// the dispute admin acts or DISPUTE_RESOLUTION_WINDOW_SECONDS pass
+    pub frozen: bool,
+    
+    /// Treasury rake taken off the pot at finalize, paid out with the winner
+    pub rake: u64,
+    
+    /// The other player's share: their consolation, or their half of a draw
+    pub loser_amount: u64,
+    
+    /// Participant who called finalize, and the reward carved out for them
+    pub finalizer: Pubkey,
+    pub finalizer_reward: u64,
+    
+    /// Equal standings: amount and loser_amount are Player A's and Player B's halves
+    pub is_draw: bool,
+}
+
+impl PendingSettlement {
+    /// 32 (winner) + 8 (amount) + 8 (executable_at_ts) + 1 (frozen) + 8 (rake)
+    /// + 8 (loser_amount) + 32 (finalizer) + 8 (finalizer_reward) + 1 (is_draw)
+    pub const SIZE: usize = 32 + 8 + 8 + 1 + 8 + 8 + 32 + 8 + 1;
+    
+    /// Winner's payout from the escrow balance at execute time: everything
+    /// left after the rake, the loser's share and the finalizer reward
+    pub fn winner_payout(&self, escrow: u64) -> Result<u64> {
+        escrow
+            .checked_sub(self.rake)
+            .and_then(|rest| rest.checked_sub(self.loser_amount))
+            .and_then(|rest| rest.checked_sub(self.finalizer_reward))
+            .ok_or_else(|| DroogError::CalculationOverflow.into())
+    }
+}
+
+/// Optional "mercy rule" that lets a blowout be finalized before end_ts
+/// Either threshold may be 0 (disabled); all zeros disables the rule.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MercyRule {
+    /// Leader's sales minus trailer's sales that triggers early end
+    pub sales_margin: u32,
+    
+    /// Absolute sales count for either player that triggers early end
+    pub sales_cap: u32,
+}
+
+impl MercyRule {
+    /// 4 (sales_margin) + 4 (sales_cap)
+    pub const SIZE: usize = 4 + 4;
+    
+    /// Whether the current sales counts hit either threshold
+    pub fn is_triggered(&self, player_a_sales: u32, player_b_sales: u32) -> bool {
+        let margin = player_a_sales.abs_diff(player_b_sales);
+        let leader = player_a_sales.max(player_b_sales);
+        (self.sales_margin > 0 && margin >= self.sales_margin)
+            || (self.sales_cap > 0 && leader >= self.sales_cap)
+    }
+}
+
+/// Basis-point denominator for PotSplit shares (10_000 = the whole pot)
+pub const POT_SPLIT_BPS_DENOMINATOR: u16 = 10_000;
+
+/// Optional pre-agreed pot split for handicapped (asymmetric-stake) matches
+/// Each share is Player A's cut of the post-burn pot, in basis points, for
+/// that outcome; Player B receives the rest. All zeros disables the split
+/// (winner-take-all less loser consolation, 50/50 draws).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PotSplit {
+    /// Sales margin at or above which a win counts as decisive (0 = every win)
+    pub decisive_margin: u32,
+    
+    /// Player A's share when Player A wins decisively
+    pub a_decisive_win_bps: u16,
+    
+    /// Player A's share when Player A wins below decisive_margin
+    pub a_narrow_win_bps: u16,
+    
+    /// Player A's share of a draw
+    pub draw_bps: u16,
+    
+    /// Player A's share when Player B wins below decisive_margin
+    pub b_narrow_win_bps: u16,
+    
+    /// Player A's share when Player B wins decisively
+    pub b_decisive_win_bps: u16,
+}
+
+impl PotSplit {
+    /// 4 (decisive_margin) + 5 * 2 (shares)
+    pub const SIZE: usize = 4 + 5 * 2;
+    
+    /// Whether the match settles by this matrix instead of winner-take-all
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+    
+    /// Every share is at most the whole pot
+    pub fn validate(&self) -> Result<()> {
+        let shares = [
+            self.a_decisive_win_bps,
+            self.a_narrow_win_bps,
+            self.draw_bps,
+            self.b_narrow_win_bps,
+            self.b_decisive_win_bps,
+        ];
+        require!(
+            shares.iter().all(|&bps| bps <= POT_SPLIT_BPS_DENOMINATOR),
+            DroogError::InvalidPotSplit
+        );
+        Ok(())
+    }
+    
+    /// Player A's share for an outcome (is_draw as decided by settles_as_draw)
+    pub fn player_a_bps(&self, standings: &Standings, player_a: &Pubkey, is_draw: bool) -> u16 {
+        if is_draw {
+            return self.draw_bps;
+        }
+        let decisive = standings.winner_sales.saturating_sub(standings.loser_sales) >= self.decisive_margin;
+        match (standings.winner == *player_a, decisive) {
+            (true, true) => self.a_decisive_win_bps,
+            (true, false) => self.a_narrow_win_bps,
+            (false, false) => self.b_narrow_win_bps,
+            (false, true) => self.b_decisive_win_bps,
+        }
+    }
+    
+    /// Split the pot for an outcome: (player_a, player_b)
+    /// Player B gets the rounding remainder so the escrow is always fully drained
+    pub fn split(&self, standings: &Standings, pot: u64, player_a: &Pubkey, is_draw: bool) -> (u64, u64) {
+        let bps = self.player_a_bps(standings, player_a, is_draw);
+        let player_a_amount = (pot as u128 * bps as u128 / POT_SPLIT_BPS_DENOMINATOR as u128) as u64;
+        (player_a_amount, pot - player_a_amount)
+    }
+}
+
 // ============================================================================
 // MATCH STAKE STATE
 // ============================================================================
@@ -100,12 +383,87 @@ pub struct MatchStakeState {
     
     /// Escrow token account bump (for PDA signing)
     pub escrow_bump: u8,
+    
+    /// Two-phase settlement: finalize records the payout, execute_settlement
+    /// transfers it after SETTLEMENT_CHALLENGE_WINDOW_SECONDS (set at init)
+    pub delayed_settlement: bool,
+    
+    /// Recorded payout while status == Settling
+    pub pending_settlement: PendingSettlement,
+    
+    /// Practice match: no escrow, burn, or payout (set at init)
+    pub practice: bool,
+    
+    /// Early-end thresholds for blowouts (set at init; default disabled)
+    pub mercy_rule: MercyRule,
+    
+    /// Player A's stake (set at init, raised by add_stake; 0 for practice)
+    pub stake_amount: u64,
+    
+    /// Player B must escrow exactly this to join (equal to stake_amount
+    /// unless the match was created with a handicap stake; 0 for practice)
+    pub player_b_stake_amount: u64,
+    
+    /// Pre-agreed settlement matrix (set at init; default disabled)
+    pub pot_split: PotSplit,
 }
 
 impl MatchStakeState {
     /// Account size for rent calculation
-    /// 8 (discriminator) + 8 + 32 + 32 + 32 + 1 + 8 + 8 + 8 + 1 + 1 = 139 bytes
-    pub const SIZE: usize = 8 + 8 + 32 + 32 + 32 + MatchStatus::SIZE + 8 + 8 + 8 + 1 + 1;
+    /// 8 (discriminator) + 8 + 32 + 32 + 32 + 1 + 8 + 8 + 8 + 1 + 1 + 1 + 106 + 1 + 8 + 8 + 8 + 14 = 285 bytes
+    pub const SIZE: usize = 8 + 8 + 32 + 32 + 32 + MatchStatus::SIZE + 8 + 8 + 8 + 1 + 1
+        + 1 + PendingSettlement::SIZE + 1 + MercyRule::SIZE + 8 + 8 + PotSplit::SIZE;
+    
+    /// Set up a freshly created stake account for match_state (Pending,
+    /// Player A's stake_amount escrowed, Player B to match it). Shared by
+    /// init_match and rematch; the caller sets delayed_settlement, practice,
+    /// mercy_rule and any handicap (player_b_stake_amount, pot_split).
+    pub fn initialize(
+        &mut self,
+        match_state: &MatchState,
+        stake_amount: u64,
+        created_at: i64,
+        bump: u8,
+        escrow_bump: u8,
+    ) {
+        self.match_id = match_state.match_id;
+        self.match_id_hash = match_state.match_id_hash;
+        self.player_a = match_state.player_a;
+        self.player_b = match_state.player_b;
+        self.status = MatchStatus::Pending;
+        self.player_a_escrowed = stake_amount;
+        self.player_b_escrowed = 0; // Not yet joined
+        self.created_at = created_at;
+        self.bump = bump;
+        self.escrow_bump = escrow_bump;
+        self.pending_settlement = PendingSettlement::default();
+        self.stake_amount = stake_amount;
+        self.player_b_stake_amount = stake_amount;
+        self.pot_split = PotSplit::default();
+    }
+    
+    /// Set up the stake account of a double-or-nothing continuation (see
+    /// accept_double_or_nothing): the winner's carried pot and the loser's
+    /// equal fresh stake are both escrowed, so it starts Active with the pot
+    /// doubled and nothing burned. Winner-take-all (no pot split) under
+    /// delayed settlement, so the next pot can be offered again; the mercy
+    /// rule carries over from the source match.
+    pub fn initialize_continuation(
+        &mut self,
+        match_state: &MatchState,
+        source: &MatchStakeState,
+        pot: u64,
+        created_at: i64,
+        bump: u8,
+        escrow_bump: u8,
+    ) {
+        self.initialize(match_state, pot, created_at, bump, escrow_bump);
+        self.player_b_escrowed = pot;
+        self.status = MatchStatus::Active;
+        self.delayed_settlement = true;
+        self.practice = false;
+        self.mercy_rule = source.mercy_rule;
+    }
     
     /// Calculate burn amount from total escrowed
     pub fn calculate_burn_amount(total_escrowed: u64) -> u64 {
@@ -116,23 +474,375 @@ impl MatchStakeState {
             .unwrap_or(0)
     }
     
+    /// Current lifecycle phase (single source for instruction ordering rules)
+    pub fn lifecycle_phase(&self) -> LifecyclePhase {
+        match self.status {
+            MatchStatus::Pending => LifecyclePhase::Lobby,
+            MatchStatus::Active => LifecyclePhase::Live,
+            MatchStatus::Settling => LifecyclePhase::Settling,
+            MatchStatus::Finalized | MatchStatus::Cancelled | MatchStatus::Disputed => {
+                LifecyclePhase::Closed
+            }
+        }
+    }
+    
+    /// Whether an instruction may run in the current phase
+    pub fn allows(&self, action: LifecycleAction) -> bool {
+        action.valid_phases().contains(&self.lifecycle_phase())
+    }
+    
+    /// Enforce an instruction's valid phases with its phase-specific error
+    pub fn check_lifecycle(&self, action: LifecycleAction) -> Result<()> {
+        if self.allows(action) {
+            Ok(())
+        } else {
+            Err(action.phase_error(self.lifecycle_phase()).into())
+        }
+    }
+    
+    /// A match can be replayed (rematch) once it was paid out or cancelled
+    pub fn check_rematch_source(&self) -> Result<()> {
+        require!(
+            matches!(self.status, MatchStatus::Finalized | MatchStatus::Cancelled),
+            DroogError::RematchSourceNotSettled
+        );
+        Ok(())
+    }
+    
+    /// Practice matches have no pot, so they can't use delayed settlement
+    pub fn validate_stake_mode(practice: bool, delayed_settlement: bool) -> Result<()> {
+        require!(!(practice && delayed_settlement), DroogError::PracticeMatchNoStake);
+        Ok(())
+    }
+    
+    /// Per-player stake chosen at init: nonzero and at most MAX_STAKE_AMOUNT
+    pub fn validate_stake_amount(stake_amount: u64) -> Result<()> {
+        require!(stake_amount > 0, DroogError::InvalidStakeAmount);
+        require!(stake_amount <= MAX_STAKE_AMOUNT, DroogError::StakeExceedsMaximum);
+        Ok(())
+    }
+    
+    /// A pot split needs a staked pot paid out at finalize, so it can't be
+    /// combined with practice or delayed settlement (single recipient)
+    pub fn validate_pot_split(pot_split: &PotSplit, practice: bool, delayed_settlement: bool) -> Result<()> {
+        if pot_split.is_enabled() {
+            require!(!practice, DroogError::PracticeMatchNoStake);
+            require!(!delayed_settlement, DroogError::PotSplitRequiresImmediateSettlement);
+        }
+        pot_split.validate()
+    }
+    
+    /// Raise Player A's stake while Pending (add_stake); returns the new stake
+    /// In a symmetric match Player B must match the raised amount at join;
+    /// a handicapped match keeps Player B's agreed stake (see join_stake_amount)
+    pub fn raise_stake(&mut self, amount: u64) -> Result<u64> {
+        self.check_lifecycle(LifecycleAction::RaiseStake)?;
+        require!(!self.practice, DroogError::PracticeMatchNoStake);
+        require!(amount > 0 && self.player_b_escrowed == 0, DroogError::InvalidStakeRaise);
+        let new_stake = self.stake_amount
+            .checked_add(amount)
+            .ok_or(DroogError::CalculationOverflow)?;
+        require!(new_stake <= MAX_STAKE_AMOUNT, DroogError::StakeExceedsMaximum);
+        if self.player_b_stake_amount == self.stake_amount {
+            self.player_b_stake_amount = new_stake;
+        }
+        self.stake_amount = new_stake;
+        self.player_a_escrowed = new_stake;
+        Ok(new_stake)
+    }
+    
+    /// Amount Player B must escrow to join: Player A's current stake_amount,
+    /// or the handicap stake agreed at init (0 for practice matches)
+    pub fn join_stake_amount(&self) -> u64 {
+        self.player_b_stake_amount
+    }
+    
+    /// Amount Player B escrows, provided it is still the amount they agreed
+    /// to: a raise_stake landing just before the join fails it instead
+    pub fn check_join_stake(&self, expected_stake_amount: u64) -> Result<u64> {
+        let stake_amount = self.join_stake_amount();
+        require!(stake_amount == expected_stake_amount, DroogError::StakeAmountChanged);
+        Ok(stake_amount)
+    }
+    
     /// Check if cancel is allowed (timeout elapsed and still pending)
     pub fn can_cancel(&self, current_ts: i64) -> bool {
-        self.status == MatchStatus::Pending 
+        self.allows(LifecycleAction::Cancel)
             && self.player_b_escrowed == 0
             && current_ts >= self.created_at + CANCEL_TIMEOUT_SECONDS
     }
     
-    /// Check if match can be activated (both players escrowed)
+    /// Check if a mutual cancel is allowed (still pending; no timeout because
+    /// both players sign)
+    pub fn can_cancel_mutual(&self) -> bool {
+        self.allows(LifecycleAction::Cancel) && self.player_b_escrowed == 0
+    }
+    
+    /// Check if an abandoned Pending match can be reaped by anyone
+    /// Requires the long ABANDONED_MATCH_HORIZON_SECONDS to have elapsed
+    pub fn can_reap(&self, current_ts: i64) -> bool {
+        self.allows(LifecycleAction::Reap)
+            && current_ts >= self.created_at.saturating_add(ABANDONED_MATCH_HORIZON_SECONDS)
+    }
+    
+    /// Check if match can be activated (both players escrowed, or practice)
     pub fn can_activate(&self) -> bool {
-        self.status == MatchStatus::Pending
-            && self.player_a_escrowed > 0
-            && self.player_b_escrowed > 0
+        self.allows(LifecycleAction::Join)
+            && (self.practice || (self.player_a_escrowed > 0 && self.player_b_escrowed > 0))
     }
     
     /// Check if match can be finalized
     pub fn can_finalize(&self) -> bool {
-        self.status == MatchStatus::Active
+        self.allows(LifecycleAction::Finalize)
+    }
+    
+    /// Check if gameplay (plant/harvest/sell) is legal under the staking lifecycle
+    /// Defense-in-depth: ties gameplay to the authoritative stake status rather
+    /// than only MatchState.is_finalized, in case the two ever diverge
+    pub fn allows_gameplay(&self) -> bool {
+        self.allows(LifecycleAction::Gameplay)
+    }
+    
+    /// Enforce gameplay legality with a distinct error per lifecycle state
+    /// - Pending: MatchNotActivated (Player B hasn't committed yet)
+    /// - Settling/Finalized/Cancelled/Disputed: MatchNotActive
+    pub fn check_gameplay(&self) -> Result<()> {
+        self.check_lifecycle(LifecycleAction::Gameplay)
+    }
+    
+    /// Record the payout instead of transferring it (delayed settlement)
+    /// `payout` holds the split finalize computed; the window is set here
+    /// Moves Active -> Settling and opens the challenge window
+    pub fn record_pending_settlement(&mut self, payout: PendingSettlement, current_ts: i64) {
+        self.pending_settlement = PendingSettlement {
+            executable_at_ts: current_ts.saturating_add(SETTLEMENT_CHALLENGE_WINDOW_SECONDS),
+            frozen: false,
+            ..payout
+        };
+        self.status = MatchStatus::Settling;
+    }
+    
+    /// Amount the winner is paid at settlement
+    /// Escrow balance is authoritative (post-burn amount); practice matches
+    /// have no escrow and pay nothing
+    pub fn payout_amount(&self, escrow_balance: Option<u64>) -> Result<u64> {
+        if self.practice {
+            return Ok(0);
+        }
+        escrow_balance.ok_or_else(|| DroogError::StakeAccountsRequired.into())
+    }
+    
+    /// Freeze a pending settlement (only inside the challenge window)
+    pub fn freeze_settlement(&mut self, current_ts: i64) -> Result<()> {
+        self.check_lifecycle(LifecycleAction::FreezeSettlement)?;
+        require!(!self.pending_settlement.frozen, DroogError::SettlementFrozen);
+        require!(
+            current_ts < self.pending_settlement.executable_at_ts,
+            DroogError::SettlementWindowClosed
+        );
+        self.pending_settlement.frozen = true;
+        Ok(())
+    }
+    
+    /// Check a pending settlement can be paid out now
+    /// A frozen settlement the dispute admin never acted on becomes payable
+    /// to the recorded winner once the resolution window has passed
+    pub fn check_execute_settlement(&self, current_ts: i64) -> Result<()> {
+        self.check_lifecycle(LifecycleAction::ExecuteSettlement)?;
+        require!(
+            !self.pending_settlement.frozen || current_ts >= self.freeze_expires_at(),
+            DroogError::SettlementFrozen
+        );
+        require!(
+            current_ts >= self.pending_settlement.executable_at_ts,
+            DroogError::SettlementWindowOpen
+        );
+        Ok(())
+    }
+    
+    /// When an unresolved freeze lapses
+    pub fn freeze_expires_at(&self) -> i64 {
+        self.pending_settlement
+            .executable_at_ts
+            .saturating_add(DISPUTE_RESOLUTION_WINDOW_SECONDS)
+    }
+    
+    /// Void a pending settlement (dispute admin only; signer checked by the instruction)
+    /// Allowed inside the challenge window, or any time once a player has frozen it.
+    /// Moves Settling -> Disputed; the caller refunds the escrow to both players.
+    pub fn reverse_finalization(&mut self, current_ts: i64) -> Result<()> {
+        self.check_lifecycle(LifecycleAction::ReverseFinalization)?;
+        require!(
+            self.pending_settlement.frozen || current_ts < self.pending_settlement.executable_at_ts,
+            DroogError::SettlementWindowClosed
+        );
+        self.status = MatchStatus::Disputed;
+        Ok(())
+    }
+    
+    /// Settle a frozen settlement in favour of one player (dispute admin only;
+    /// signer checked by the instruction). Upholding the recorded winner or
+    /// overturning to the other player both unfreeze it and make it
+    /// executable immediately; execute_settlement then pays the pot as usual.
+    /// 
+    /// Overturning re-splits the winner's and loser's shares for the new
+    /// loser (their consolation, or their pot-split share); the rake and
+    /// finalizer reward stand. A draw has no winner to pick: the admin voids
+    /// it with reverse_finalization instead.
+    pub fn resolve_dispute(&mut self, winner: Pubkey, match_state: &MatchState, current_ts: i64) -> Result<()> {
+        self.check_lifecycle(LifecycleAction::ResolveDispute)?;
+        require!(self.pending_settlement.frozen, DroogError::SettlementNotFrozen);
+        require!(winner == self.player_a || winner == self.player_b, DroogError::InvalidPlayer);
+        require!(!self.pending_settlement.is_draw, DroogError::InvalidPlayer);
+        
+        if winner != self.pending_settlement.winner {
+            let winner_is_a = winner == self.player_a;
+            let (winner_sales, loser_sales) = if winner_is_a {
+                (match_state.player_a_sales, match_state.player_b_sales)
+            } else {
+                (match_state.player_b_sales, match_state.player_a_sales)
+            };
+            let standings = Standings {
+                winner,
+                loser: self.pending_settlement.winner,
+                winner_sales,
+                loser_sales,
+                decided_by: DecidedBy::Overturned,
+            };
+            let pot = self.pending_settlement.amount
+                .checked_add(self.pending_settlement.loser_amount)
+                .ok_or(DroogError::CalculationOverflow)?;
+            let loser_reputation = match_state.reputation_of(!winner_is_a);
+            let (amount, loser_amount) = self.settlement_split(&standings, pot, loser_reputation);
+            self.pending_settlement.winner = winner;
+            self.pending_settlement.amount = amount;
+            self.pending_settlement.loser_amount = loser_amount;
+        }
+        self.pending_settlement.frozen = false;
+        self.pending_settlement.executable_at_ts = current_ts;
+        Ok(())
+    }
+    
+    /// Check an immediate payout goes to a token account the winner owns
+    /// Any account works (ATA or not); the owner is what matters
+    pub fn validate_payout_destination(destination_owner: &Pubkey, winner: &Pubkey) -> Result<()> {
+        require!(destination_owner == winner, DroogError::InvalidPayoutDestination);
+        Ok(())
+    }
+    
+    /// Check a payout token account holds the stake mint
+    /// transfer_checked would reject it anyway, but with an opaque token error
+    pub fn validate_payout_mint(account_mint: &Pubkey, mint: &Pubkey) -> Result<()> {
+        require!(account_mint == mint, DroogError::MintMismatch);
+        Ok(())
+    }
+    
+    /// Split a voided pot between the players: (player_a, player_b)
+    /// Each player gets back their share of what was escrowed (a handicap
+    /// match refunds unequal amounts); rounding remainders go to Player A so
+    /// the escrow is always fully drained
+    pub fn dispute_refund_split(&self, escrow_balance: u64) -> (u64, u64) {
+        let total_escrowed = self.player_a_escrowed as u128 + self.player_b_escrowed as u128;
+        if total_escrowed == 0 {
+            return (escrow_balance, 0);
+        }
+        let player_b_refund = (escrow_balance as u128 * self.player_b_escrowed as u128 / total_escrowed) as u64;
+        (escrow_balance - player_b_refund, player_b_refund)
+    }
+    
+    /// Whether finalize splits the pot instead of paying a winner: equal sales
+    /// and reputation, whether the settlement is immediate or delayed
+    pub fn settles_as_draw(&self, standings: &Standings) -> bool {
+        standings.decided_by == DecidedBy::FirstMoverTiebreak
+    }
+    
+    /// Consolation carved from the pot for a loser with positive reputation
+    /// LOSER_CONSOLATION_PER_REP per point, capped at LOSER_CONSOLATION_MAX_PERCENT
+    /// of the pot, so winner + consolation never exceeds the escrow
+    pub fn loser_consolation(pot: u64, loser_reputation: i32) -> u64 {
+        let earned = (loser_reputation.max(0) as u64).saturating_mul(LOSER_CONSOLATION_PER_REP);
+        let cap = (pot as u128 * LOSER_CONSOLATION_MAX_PERCENT as u128 / 100) as u64;
+        earned.min(cap)
+    }
+    
+    /// Settlement split of the pot: (winner_amount, loser_amount)
+    /// - Pot split: each player's agreed share for the outcome (no consolation)
+    /// - Draw: the two halves (standings.winner is Player A in a draw)
+    /// - Otherwise: the loser's consolation is carved out, the rest goes to the winner
+    /// 
+    /// Delayed settlements record the same split for execute_settlement.
+    pub fn settlement_split(&self, standings: &Standings, pot: u64, loser_reputation: i32) -> (u64, u64) {
+        if self.pot_split.is_enabled() {
+            let is_draw = self.settles_as_draw(standings);
+            let (player_a_amount, player_b_amount) =
+                self.pot_split.split(standings, pot, &self.player_a, is_draw);
+            return if standings.winner == self.player_a {
+                (player_a_amount, player_b_amount)
+            } else {
+                (player_b_amount, player_a_amount)
+            };
+        }
+        if self.settles_as_draw(standings) {
+            return Self::draw_split(pot, &standings.winner, &standings.loser);
+        }
+        let consolation = Self::loser_consolation(pot, loser_reputation);
+        (pot - consolation, consolation)
+    }
+    
+    /// Treasury rake: RAKE_BPS of the pot, taken before any other split
+    /// (checked math; 0 when it rounds down to zero). Applies to every paid
+    /// settlement - immediate, delayed and surrender - once ProgramConfig
+    /// names a treasury; 0 while none is configured.
+    pub fn treasury_rake(pot: u64, treasury: Option<Pubkey>) -> Result<u64> {
+        if treasury.is_none() {
+            return Ok(0);
+        }
+        let rake = (pot as u128)
+            .checked_mul(RAKE_BPS as u128)
+            .and_then(|scaled| scaled.checked_div(POT_SPLIT_BPS_DENOMINATOR as u128))
+            .ok_or(DroogError::CalculationOverflow)?;
+        u64::try_from(rake).map_err(|_| DroogError::CalculationOverflow.into())
+    }
+    
+    /// Check the rake goes to a token account the configured treasury owns
+    pub fn validate_treasury_destination(destination_owner: &Pubkey, treasury: Option<Pubkey>) -> Result<()> {
+        require!(Some(*destination_owner) == treasury, DroogError::InvalidPayoutDestination);
+        Ok(())
+    }
+    
+    /// Reward carved from the pot for the participant who calls finalize
+    /// reward_bps of the pot (never more than the pot); 0 for a keeper
+    /// finalizing an abandoned match. The split of the rest is unchanged.
+    /// Delayed settlements record it and pay it at execute_settlement.
+    pub fn finalizer_reward(&self, pot: u64, reward_bps: u16, finalizer: &Pubkey) -> u64 {
+        let is_participant = *finalizer == self.player_a || *finalizer == self.player_b;
+        if !is_participant {
+            return 0;
+        }
+        let bps = reward_bps.min(POT_SPLIT_BPS_DENOMINATOR);
+        (pot as u128 * bps as u128 / POT_SPLIT_BPS_DENOMINATOR as u128) as u64
+    }
+    
+    /// Split a drawn pot between the players: (player_a, player_b)
+    /// The odd remainder goes to the lower pubkey so the escrow is fully drained
+    pub fn draw_split(escrow_balance: u64, player_a: &Pubkey, player_b: &Pubkey) -> (u64, u64) {
+        let half = escrow_balance / 2;
+        let remainder = escrow_balance - 2 * half;
+        if player_a <= player_b {
+            (half + remainder, half)
+        } else {
+            (half, half + remainder)
+        }
+    }
+    
+    /// Derive the escrow authority PDA for a match
+    /// 
+    /// The escrow authority is signing-only today: it owns the escrow token
+    /// account and signs burn/refund/payout CPIs, but holds no lamports.
+    /// If a future feature needs it to pay for anything, top it up with
+    /// fund_escrow_authority first.
+    pub fn derive_escrow_authority(match_id_hash: &[u8; 32]) -> (Pubkey, u8) {
+        crate::pda::derive_escrow_authority_pda(match_id_hash)
     }
 }
 
@@ -147,6 +857,32 @@ pub struct MatchStakeInitializedEvent {
     pub player_a: Pubkey,
     pub player_b: Pubkey,
     pub amount_escrowed: u64,
+    /// Amount Player B must escrow to join
+    pub player_b_stake_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when rematch creates a new match for a settled match's players
+#[event]
+pub struct RematchCreatedEvent {
+    /// The settled match being replayed
+    pub previous_match_id: u64,
+    pub match_id: u64,
+    pub player_a: Pubkey,
+    pub player_b: Pubkey,
+    /// Per-player stake carried over (0 for practice)
+    pub stake_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when Player A raises the stake before Player B joins
+#[event]
+pub struct StakeRaisedEvent {
+    pub match_id: u64,
+    pub player_a: Pubkey,
+    pub amount_added: u64,
+    /// Player A's total stake, which Player B must match to join
+    pub new_stake: u64,
     pub timestamp: i64,
 }
 
@@ -168,6 +904,94 @@ pub struct MatchCancelledEvent {
     pub match_id: u64,
     pub player_a: Pubkey,
     pub amount_refunded: u64,
+    /// Both players signed (cancel_match_mutual); no timeout applied
+    pub mutual: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an abandoned Pending match is reaped
+#[event]
+pub struct MatchReapedEvent {
+    pub match_id: u64,
+    pub player_a: Pubkey,
+    pub reaper: Pubkey,
+    pub amount_refunded: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the escrow authority is topped up with SOL
+#[event]
+pub struct EscrowAuthorityFundedEvent {
+    pub match_id: u64,
+    pub escrow_authority: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when finalize records a delayed payout
+#[event]
+pub struct SettlementPendingEvent {
+    pub match_id: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+    /// Treasury rake recorded alongside (paid at execute)
+    pub rake: u64,
+    /// Loser's consolation (Player B's half in a draw) and the finalizer's reward
+    pub loser_amount: u64,
+    pub finalizer_reward: u64,
+    pub is_draw: bool,
+    pub executable_at_ts: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player freezes a pending settlement
+#[event]
+pub struct SettlementFrozenEvent {
+    pub match_id: u64,
+    pub frozen_by: Pubkey,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the dispute admin resolves a frozen settlement
+#[event]
+pub struct DisputeResolvedEvent {
+    pub match_id: u64,
+    pub admin: Pubkey,
+    /// Winner recorded at finalize
+    pub recorded_winner: Pubkey,
+    /// Player the pot is released to
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the dispute admin voids a pending settlement
+#[event]
+pub struct FinalizationReversedEvent {
+    pub match_id: u64,
+    pub admin: Pubkey,
+    pub voided_winner: Pubkey,
+    pub player_a_refund: u64,
+    pub player_b_refund: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted instead of StakePayoutEvent when a drawn pot is split
+#[event]
+pub struct MatchDrawEvent {
+    pub match_id: u64,
+    pub player_a: Pubkey,
+    pub player_b: Pubkey,
+    /// Each player's share (a share that rounds to zero is not transferred)
+    pub player_a_amount: u64,
+    pub player_b_amount: u64,
+    /// Sales count both players tied on
+    pub sales: u32,
+    /// Treasury rake taken off the pot before it was split
+    pub rake: u64,
     pub timestamp: i64,
 }
 
@@ -180,5 +1004,838 @@ pub struct StakePayoutEvent {
     pub amount: u64,
     pub winner_sales: u32,
     pub loser_sales: u32,
+    /// Rule that picked the winner (sales, reputation tiebreak, or Player A by default)
+    pub decided_by: DecidedBy,
+    /// Carved from the pot for the loser's reputation, or the loser's
+    /// pot-split share (amount excludes it)
+    pub loser_consolation: u64,
+    /// Treasury rake taken off the pot first (0 for delayed settlements)
+    pub rake: u64,
     pub timestamp: i64,
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    
+    pub(crate) fn test_stake() -> MatchStakeState {
+        MatchStakeState {
+            match_id: 1,
+            match_id_hash: [1u8; 32],
+            player_a: Pubkey::new_unique(),
+            player_b: Pubkey::new_unique(),
+            status: MatchStatus::Pending,
+            player_a_escrowed: STAKE_AMOUNT,
+            player_b_escrowed: 0,
+            created_at: 1_000,
+            bump: 255,
+            escrow_bump: 255,
+            delayed_settlement: false,
+            pending_settlement: PendingSettlement::default(),
+            practice: false,
+            mercy_rule: MercyRule::default(),
+            stake_amount: STAKE_AMOUNT,
+            player_b_stake_amount: STAKE_AMOUNT,
+            pot_split: PotSplit::default(),
+        }
+    }
+    
+    #[test]
+    fn test_rematch_only_from_settled_match() {
+        for (status, ok) in [
+            (MatchStatus::Pending, false),
+            (MatchStatus::Active, false),
+            (MatchStatus::Settling, false),
+            (MatchStatus::Disputed, false),
+            (MatchStatus::Finalized, true),
+            (MatchStatus::Cancelled, true),
+        ] {
+            let stake = MatchStakeState { status, ..test_stake() };
+            match stake.check_rematch_source() {
+                Ok(()) => assert!(ok, "{:?}", status),
+                Err(e) => {
+                    assert!(!ok, "{:?}", status);
+                    assert_eq!(e, DroogError::RematchSourceNotSettled.into());
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_delayed_settlement_two_phase() {
+        let mut stake = MatchStakeState {
+            status: MatchStatus::Active,
+            delayed_settlement: true,
+            ..test_stake()
+        };
+        let winner = stake.player_a;
+        
+        // Phase 1: finalize records the payout, split like an immediate one
+        let recorded = PendingSettlement {
+            winner,
+            amount: 1_756_180,
+            rake: 18_000,
+            loser_amount: 8_000,
+            finalizer: stake.player_b,
+            finalizer_reward: 17_820,
+            executable_at_ts: 1,
+            frozen: true,
+            ..Default::default()
+        };
+        stake.record_pending_settlement(recorded, 2_000);
+        assert_eq!(stake.status, MatchStatus::Settling);
+        assert_eq!(stake.pending_settlement.winner, winner);
+        assert_eq!((stake.pending_settlement.amount, stake.pending_settlement.rake), (1_756_180, 18_000));
+        assert_eq!(stake.pending_settlement.finalizer, stake.player_b);
+        assert_eq!(stake.pending_settlement.executable_at_ts, 2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS);
+        assert!(!stake.pending_settlement.frozen);
+        
+        // Execute pays every recorded share; the winner gets what is left
+        assert_eq!(stake.pending_settlement.winner_payout(1_800_000).unwrap(), 1_756_180);
+        assert_eq!(stake.pending_settlement.winner_payout(1_800_001).unwrap(), 1_756_181);
+        assert_eq!(
+            stake.pending_settlement.winner_payout(43_819).unwrap_err(),
+            DroogError::CalculationOverflow.into()
+        );
+        
+        // No gameplay while settling
+        assert!(stake.check_gameplay().is_err());
+        
+        // Phase 2: rejected inside the window, allowed after
+        assert_eq!(
+            stake.check_execute_settlement(2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS - 1).unwrap_err(),
+            DroogError::SettlementWindowOpen.into()
+        );
+        assert!(stake.check_execute_settlement(2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS).is_ok());
+        
+        // Only a Settling match has anything to execute
+        stake.status = MatchStatus::Finalized;
+        assert_eq!(
+            stake.check_execute_settlement(i64::MAX).unwrap_err(),
+            DroogError::SettlementNotPending.into()
+        );
+    }
+    
+    #[test]
+    fn test_freeze_interrupts_settlement() {
+        let mut stake = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        stake.record_pending_settlement(PendingSettlement { winner: stake.player_b, amount: 1_800_000, ..Default::default() }, 2_000);
+        
+        // Freeze inside the window blocks execution even after it closes...
+        assert!(stake.freeze_settlement(2_100).is_ok());
+        let window_end = 2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS;
+        assert_eq!(
+            stake.check_execute_settlement(window_end).unwrap_err(),
+            DroogError::SettlementFrozen.into()
+        );
+        assert_eq!(
+            stake.check_execute_settlement(window_end + DISPUTE_RESOLUTION_WINDOW_SECONDS - 1).unwrap_err(),
+            DroogError::SettlementFrozen.into()
+        );
+        
+        // ...until the dispute admin has had the resolution window to act
+        assert_eq!(stake.freeze_expires_at(), window_end + DISPUTE_RESOLUTION_WINDOW_SECONDS);
+        assert!(stake.check_execute_settlement(window_end + DISPUTE_RESOLUTION_WINDOW_SECONDS).is_ok());
+        assert_eq!(stake.pending_settlement.winner, stake.player_b);
+        assert_eq!(
+            stake.freeze_settlement(2_101).unwrap_err(),
+            DroogError::SettlementFrozen.into()
+        );
+        
+        // Freezing after the window is too late
+        let mut late = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        late.record_pending_settlement(PendingSettlement { winner: late.player_a, amount: 1, ..Default::default() }, 2_000);
+        assert_eq!(
+            late.freeze_settlement(2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS).unwrap_err(),
+            DroogError::SettlementWindowClosed.into()
+        );
+    }
+    
+    #[test]
+    fn test_reverse_finalization_window() {
+        let mut stake = MatchStakeState { status: MatchStatus::Active, player_b_escrowed: STAKE_AMOUNT, ..test_stake() };
+        stake.record_pending_settlement(PendingSettlement { winner: stake.player_a, amount: 1_800_001, ..Default::default() }, 2_000);
+        
+        // Inside the window the admin can void; the result can't then be executed
+        assert!(stake.reverse_finalization(2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS - 1).is_ok());
+        assert_eq!(stake.status, MatchStatus::Disputed);
+        assert!(stake.check_execute_settlement(i64::MAX).is_err());
+        assert!(stake.check_gameplay().is_err());
+        assert_eq!(stake.dispute_refund_split(1_800_001), (900_001, 900_000));
+        
+        // After the window an unfrozen settlement stands
+        let mut late = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        late.record_pending_settlement(PendingSettlement { winner: late.player_a, amount: 1, ..Default::default() }, 2_000);
+        assert_eq!(
+            late.reverse_finalization(2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS).unwrap_err(),
+            DroogError::SettlementWindowClosed.into()
+        );
+        
+        // ...unless a player froze it in time
+        let mut frozen = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        frozen.record_pending_settlement(PendingSettlement { winner: frozen.player_b, amount: 1, ..Default::default() }, 2_000);
+        frozen.freeze_settlement(2_100).unwrap();
+        assert!(frozen.reverse_finalization(2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS * 10).is_ok());
+        
+        // Immediate settlements have already paid out
+        let mut paid = MatchStakeState { status: MatchStatus::Finalized, ..test_stake() };
+        assert_eq!(paid.reverse_finalization(2_000).unwrap_err(), DroogError::SettlementNotPending.into());
+    }
+    
+    #[test]
+    fn test_dispute_refund_follows_the_escrowed_stakes() {
+        // Handicap: B escrowed a third of A's stake
+        let stake = MatchStakeState {
+            status: MatchStatus::Settling,
+            delayed_settlement: true,
+            player_a_escrowed: 3_000_000,
+            player_b_escrowed: 1_000_000,
+            ..test_stake()
+        };
+        
+        // Each gets their share of the post-burn escrow back; A the remainder
+        let (a_refund, b_refund) = stake.dispute_refund_split(3_600_001);
+        assert_eq!((a_refund, b_refund), (2_700_001, 900_000));
+        assert_eq!(a_refund + b_refund, 3_600_001);
+        
+        // Not an even split of one player's stake into the other's pocket
+        assert_ne!(b_refund, 3_600_001 / 2);
+        assert_eq!(stake.dispute_refund_split(0), (0, 0));
+    }
+    
+    #[test]
+    fn test_resolve_dispute_releases_frozen_pot() {
+        let frozen = || {
+            let mut stake = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+            stake.record_pending_settlement(PendingSettlement { winner: stake.player_b, amount: 1_800_000, ..Default::default() }, 2_000);
+            stake.freeze_settlement(2_100).unwrap();
+            stake
+        };
+        let later = 2_000 + SETTLEMENT_CHALLENGE_WINDOW_SECONDS * 10;
+        let stake = frozen();
+        let m = MatchState { player_a: stake.player_a, player_b: stake.player_b, ..crate::state::match_state::tests::test_match() };
+        
+        // Upheld: the recorded winner is paid, without waiting again
+        let mut upheld = frozen();
+        let recorded_winner = upheld.pending_settlement.winner;
+        upheld.resolve_dispute(recorded_winner, &m, later).unwrap();
+        assert!(!upheld.pending_settlement.frozen);
+        assert_eq!(upheld.pending_settlement.winner, recorded_winner);
+        assert_eq!(upheld.pending_settlement.amount, 1_800_000);
+        assert!(upheld.check_execute_settlement(later).is_ok());
+        assert_eq!(upheld.status, MatchStatus::Settling);
+        
+        // Overturned: the pot goes to the other player instead
+        let mut overturned = frozen();
+        overturned.resolve_dispute(overturned.player_a, &m, 2_200).unwrap();
+        assert_eq!(overturned.pending_settlement.winner, overturned.player_a);
+        assert!(overturned.check_execute_settlement(2_200).is_ok());
+        
+        // Only between the two players, only once, and only when frozen
+        let mut stake = frozen();
+        assert_eq!(
+            stake.resolve_dispute(Pubkey::new_unique(), &m, later).unwrap_err(),
+            DroogError::InvalidPlayer.into()
+        );
+        stake.resolve_dispute(stake.player_b, &m, later).unwrap();
+        assert_eq!(
+            stake.resolve_dispute(stake.player_b, &m, later).unwrap_err(),
+            DroogError::SettlementNotFrozen.into()
+        );
+        
+        // Refunded instead: a voided settlement can no longer be resolved
+        let mut voided = frozen();
+        voided.reverse_finalization(later).unwrap();
+        assert_eq!(
+            voided.resolve_dispute(voided.player_b, &m, later).unwrap_err(),
+            DroogError::SettlementNotPending.into()
+        );
+        assert!(voided.check_execute_settlement(later).is_err());
+    }
+    
+    #[test]
+    fn test_overturned_settlement_resplits_for_the_new_loser() {
+        // B won 6-4 and A, the loser, earned a 20-rep consolation
+        let mut stake = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        let m = MatchState {
+            player_a: stake.player_a,
+            player_b: stake.player_b,
+            player_a_sales: 4,
+            player_b_sales: 6,
+            player_a_reputation: 20,
+            player_b_reputation: 5,
+            ..crate::state::match_state::tests::test_match()
+        };
+        let (rake, finalizer_reward, pot) = (45_000, 18_000, 1_737_000);
+        let recorded_consolation = MatchStakeState::loser_consolation(pot, 20);
+        stake.record_pending_settlement(
+            PendingSettlement {
+                winner: stake.player_b,
+                amount: pot - recorded_consolation,
+                rake,
+                loser_amount: recorded_consolation,
+                finalizer: stake.player_b,
+                finalizer_reward,
+                ..Default::default()
+            },
+            2_000,
+        );
+        let escrow = rake + finalizer_reward + pot;
+        stake.freeze_settlement(2_100).unwrap();
+        
+        // Overturned to A: B is now the loser and gets their own consolation
+        stake.resolve_dispute(stake.player_a, &m, 3_000).unwrap();
+        let pending = stake.pending_settlement;
+        let consolation = MatchStakeState::loser_consolation(pot, 5);
+        assert_ne!(consolation, recorded_consolation);
+        assert_eq!(pending.winner, stake.player_a);
+        assert_eq!((pending.amount, pending.loser_amount), (pot - consolation, consolation));
+        assert_eq!((pending.rake, pending.finalizer_reward), (rake, finalizer_reward));
+        
+        // Executed split: A takes the rest, B the consolation, nothing left over
+        let winner_payout = pending.winner_payout(escrow).unwrap();
+        assert_eq!(winner_payout, pot - consolation);
+        assert_eq!(winner_payout + pending.loser_amount + rake + finalizer_reward, escrow);
+        
+        // A drawn settlement can't be handed to one player
+        let mut drawn = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        drawn.record_pending_settlement(
+            PendingSettlement { winner: drawn.player_a, amount: 900_000, loser_amount: 900_000, is_draw: true, ..Default::default() },
+            2_000,
+        );
+        drawn.freeze_settlement(2_100).unwrap();
+        assert_eq!(
+            drawn.resolve_dispute(drawn.player_b, &m, 3_000).unwrap_err(),
+            DroogError::InvalidPlayer.into()
+        );
+        assert!(drawn.pending_settlement.frozen);
+    }
+    
+    #[test]
+    fn test_draw_splits_pot_without_dust() {
+        let low = Pubkey::new_from_array([1u8; 32]);
+        let high = Pubkey::new_from_array([2u8; 32]);
+        
+        // Even pot splits evenly; the odd unit goes to the lower pubkey either way round
+        assert_eq!(MatchStakeState::draw_split(1_800_000, &low, &high), (900_000, 900_000));
+        assert_eq!(MatchStakeState::draw_split(1_800_001, &low, &high), (900_001, 900_000));
+        assert_eq!(MatchStakeState::draw_split(1_800_001, &high, &low), (900_000, 900_001));
+        
+        // A single unit can't be split: the zero half is skipped by finalize
+        assert_eq!(MatchStakeState::draw_split(1, &low, &high), (1, 0));
+        assert_eq!(MatchStakeState::draw_split(0, &low, &high), (0, 0));
+        
+        // Ties are draws, immediate or delayed
+        let tie = Standings {
+            winner: low,
+            loser: high,
+            winner_sales: 4,
+            loser_sales: 4,
+            decided_by: DecidedBy::FirstMoverTiebreak,
+        };
+        let margin = Standings { winner_sales: 5, decided_by: DecidedBy::SalesMargin, ..tie };
+        let stake = test_stake();
+        assert!(stake.settles_as_draw(&tie));
+        assert!(!stake.settles_as_draw(&margin));
+        let delayed = MatchStakeState { delayed_settlement: true, ..test_stake() };
+        assert!(delayed.settles_as_draw(&tie));
+    }
+    
+    #[test]
+    fn test_raised_stake_must_be_matched() {
+        let mut stake = test_stake();
+        assert_eq!(stake.join_stake_amount(), STAKE_AMOUNT);
+        
+        // Player A raises twice before anyone joins
+        assert_eq!(stake.raise_stake(500_000).unwrap(), STAKE_AMOUNT + 500_000);
+        assert_eq!(stake.raise_stake(250_000).unwrap(), STAKE_AMOUNT + 750_000);
+        assert_eq!(stake.raise_stake(0).unwrap_err(), DroogError::InvalidStakeRaise.into());
+        assert_eq!(stake.raise_stake(u64::MAX).unwrap_err(), DroogError::CalculationOverflow.into());
+        assert_eq!(stake.raise_stake(MAX_STAKE_AMOUNT).unwrap_err(), DroogError::StakeExceedsMaximum.into());
+        
+        // A joiner who read the stake before the last raise is turned away
+        assert_eq!(
+            stake.check_join_stake(STAKE_AMOUNT + 500_000).unwrap_err(),
+            DroogError::StakeAmountChanged.into()
+        );
+        
+        // Player B matches the raised amount; burn comes off the combined total
+        let matched = stake.check_join_stake(STAKE_AMOUNT + 750_000).unwrap();
+        assert_eq!(matched, STAKE_AMOUNT + 750_000);
+        stake.player_b_escrowed = matched;
+        stake.status = MatchStatus::Active;
+        let total = stake.player_a_escrowed + stake.player_b_escrowed;
+        assert_eq!(MatchStakeState::calculate_burn_amount(total), 350_000);
+        
+        // No raising once active, nor in practice matches
+        assert_eq!(stake.raise_stake(1).unwrap_err(), DroogError::MatchNotPending.into());
+        let mut practice = MatchStakeState {
+            practice: true,
+            player_a_escrowed: 0,
+            stake_amount: 0,
+            player_b_stake_amount: 0,
+            ..test_stake()
+        };
+        assert_eq!(practice.raise_stake(1).unwrap_err(), DroogError::PracticeMatchNoStake.into());
+        assert_eq!(practice.join_stake_amount(), 0);
+        assert_eq!(practice.check_join_stake(0).unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_asymmetric_stake_pot_split() {
+        // A stakes 3 tokens, B 1; A only profits by winning by 3+ sales
+        let split = PotSplit {
+            decisive_margin: 3,
+            a_decisive_win_bps: 10_000,
+            a_narrow_win_bps: 7_500,
+            draw_bps: 7_500,
+            b_narrow_win_bps: 5_000,
+            b_decisive_win_bps: 2_500,
+        };
+        let mut stake = MatchStakeState {
+            stake_amount: 3 * STAKE_AMOUNT,
+            player_a_escrowed: 3 * STAKE_AMOUNT,
+            player_b_stake_amount: STAKE_AMOUNT,
+            pot_split: split,
+            ..test_stake()
+        };
+        
+        // Raising A's stake leaves B's handicap stake alone; B escrows their own amount
+        stake.raise_stake(STAKE_AMOUNT).unwrap();
+        assert_eq!((stake.stake_amount, stake.join_stake_amount()), (4 * STAKE_AMOUNT, STAKE_AMOUNT));
+        stake.player_b_escrowed = stake.join_stake_amount();
+        stake.status = MatchStatus::Active;
+        let total = stake.player_a_escrowed + stake.player_b_escrowed;
+        let pot = total - MatchStakeState::calculate_burn_amount(total);
+        assert_eq!(pot, 4_500_000);
+        
+        let (a, b) = (stake.player_a, stake.player_b);
+        let standings = |winner: Pubkey, winner_sales: u32, loser_sales: u32, decided_by: DecidedBy| Standings {
+            winner,
+            loser: if winner == a { b } else { a },
+            winner_sales,
+            loser_sales,
+            decided_by,
+        };
+        
+        // (outcome, expected (winner_amount, loser_amount)); reputation never adds consolation
+        for (outcome, expected) in [
+            (standings(a, 8, 5, DecidedBy::SalesMargin), (pot, 0)),
+            (standings(a, 7, 5, DecidedBy::SalesMargin), (3_375_000, 1_125_000)),
+            (standings(a, 5, 5, DecidedBy::ReputationTiebreak), (3_375_000, 1_125_000)),
+            (standings(a, 5, 5, DecidedBy::FirstMoverTiebreak), (3_375_000, 1_125_000)),
+            (standings(b, 6, 4, DecidedBy::SalesMargin), (2_250_000, 2_250_000)),
+            (standings(b, 9, 2, DecidedBy::SalesMargin), (3_375_000, 1_125_000)),
+        ] {
+            let (winner_amount, loser_amount) = stake.settlement_split(&outcome, pot, 50);
+            assert_eq!((winner_amount, loser_amount), expected, "{:?}", outcome.decided_by);
+            assert_eq!(winner_amount + loser_amount, pot);
+        }
+        
+        // Rounding remainder goes to B, so the escrow always drains
+        let (a_share, b_share) = split.split(&standings(a, 6, 5, DecidedBy::SalesMargin), 7, &a, false);
+        assert_eq!((a_share, b_share), (5, 2));
+        
+        // Symmetric matches keep B matching A's raises, and the default split is off
+        let mut symmetric = test_stake();
+        symmetric.raise_stake(STAKE_AMOUNT).unwrap();
+        assert_eq!(symmetric.join_stake_amount(), 2 * STAKE_AMOUNT);
+        assert!(!symmetric.pot_split.is_enabled());
+        
+        // Validation
+        assert!(MatchStakeState::validate_pot_split(&split, false, false).is_ok());
+        assert!(MatchStakeState::validate_pot_split(&PotSplit::default(), true, true).is_ok());
+        assert_eq!(
+            MatchStakeState::validate_pot_split(&PotSplit { draw_bps: 10_001, ..split }, false, false).unwrap_err(),
+            DroogError::InvalidPotSplit.into()
+        );
+        assert_eq!(
+            MatchStakeState::validate_pot_split(&split, true, false).unwrap_err(),
+            DroogError::PracticeMatchNoStake.into()
+        );
+        assert_eq!(
+            MatchStakeState::validate_pot_split(&split, false, true).unwrap_err(),
+            DroogError::PotSplitRequiresImmediateSettlement.into()
+        );
+    }
+    
+    #[test]
+    fn test_five_token_match_end_to_end() {
+        let five_tokens = 5 * STAKE_AMOUNT;
+        assert!(MatchStakeState::validate_stake_amount(five_tokens).is_ok());
+        assert_eq!(MatchStakeState::validate_stake_amount(0).unwrap_err(), DroogError::InvalidStakeAmount.into());
+        assert!(MatchStakeState::validate_stake_amount(MAX_STAKE_AMOUNT).is_ok());
+        assert_eq!(
+            MatchStakeState::validate_stake_amount(MAX_STAKE_AMOUNT + 1).unwrap_err(),
+            DroogError::StakeExceedsMaximum.into()
+        );
+        
+        // init: Player A escrows the chosen stake
+        let mut stake = MatchStakeState {
+            stake_amount: five_tokens,
+            player_a_escrowed: five_tokens,
+            player_b_stake_amount: five_tokens,
+            ..test_stake()
+        };
+        
+        // join: Player B matches it, burn is 10% of the 10-token total
+        assert_eq!(stake.join_stake_amount(), five_tokens);
+        stake.player_b_escrowed = stake.join_stake_amount();
+        let total = stake.player_a_escrowed + stake.player_b_escrowed;
+        let burned = MatchStakeState::calculate_burn_amount(total);
+        assert_eq!(burned, STAKE_AMOUNT);
+        stake.status = MatchStatus::Active;
+        
+        // finalize: the winner takes the 9-token post-burn escrow
+        assert!(stake.can_finalize());
+        assert_eq!(stake.payout_amount(Some(total - burned)).unwrap(), 9 * STAKE_AMOUNT);
+    }
+    
+    #[test]
+    fn test_loser_consolation_from_reputation() {
+        use crate::state::match_state::MatchState;
+        
+        let pot = 1_800_000;
+        let standings = Standings {
+            winner: Pubkey::new_unique(),
+            loser: Pubkey::new_unique(),
+            winner_sales: 8,
+            loser_sales: 5,
+            decided_by: DecidedBy::SalesMargin,
+        };
+        let stake = test_stake();
+        
+        // High-reputation loser: paid per point, winner gets the rest
+        let (winner_amount, loser_amount) = stake.settlement_split(&standings, pot, 50);
+        assert_eq!(loser_amount, 50 * LOSER_CONSOLATION_PER_REP);
+        assert_eq!(winner_amount + loser_amount, pot);
+        
+        // Capped at LOSER_CONSOLATION_MAX_PERCENT of the pot
+        let (winner_amount, loser_amount) = stake.settlement_split(&standings, pot, MatchState::REP_MAX);
+        assert_eq!(loser_amount, pot * LOSER_CONSOLATION_MAX_PERCENT / 100);
+        assert_eq!(winner_amount + loser_amount, pot);
+        assert_eq!(MatchStakeState::loser_consolation(u64::MAX, i32::MAX), i32::MAX as u64 * LOSER_CONSOLATION_PER_REP);
+        
+        // Zero or negative reputation earns nothing
+        assert_eq!(stake.settlement_split(&standings, pot, 0), (pot, 0));
+        assert_eq!(stake.settlement_split(&standings, pot, -40), (pot, 0));
+        
+        // Delayed settlements record the same consolation; empty pots pay nothing
+        let delayed = MatchStakeState { delayed_settlement: true, ..test_stake() };
+        assert_eq!(delayed.settlement_split(&standings, pot, 50), stake.settlement_split(&standings, pot, 50));
+        assert_eq!(stake.settlement_split(&standings, 0, 50), (0, 0));
+    }
+    
+    #[test]
+    fn test_treasury_rake_comes_off_the_top() {
+        let stake = test_stake();
+        let standings = Standings {
+            winner: stake.player_a,
+            loser: stake.player_b,
+            winner_sales: 8,
+            loser_sales: 5,
+            decided_by: DecidedBy::SalesMargin,
+        };
+        
+        // Winner takes the rest: winner + rake == original escrow
+        let escrow = 1_800_001;
+        let owner = Pubkey::new_unique();
+        let treasury = Some(owner);
+        let rake = MatchStakeState::treasury_rake(escrow, treasury).unwrap();
+        assert_eq!(rake, escrow * RAKE_BPS as u64 / POT_SPLIT_BPS_DENOMINATOR as u64);
+        let (winner_amount, loser_amount) = stake.settlement_split(&standings, escrow - rake, 0);
+        assert_eq!((winner_amount + rake, loser_amount), (escrow, 0));
+        
+        // Rounds to zero on dust pots (nothing transferred), never overflows
+        assert_eq!(MatchStakeState::treasury_rake(99, treasury).unwrap(), 0);
+        assert_eq!(MatchStakeState::treasury_rake(0, treasury).unwrap(), 0);
+        assert_eq!(MatchStakeState::treasury_rake(u64::MAX, treasury).unwrap(), u64::MAX / 100);
+        
+        // Nothing is raked until a treasury is configured
+        assert_eq!(MatchStakeState::treasury_rake(escrow, None).unwrap(), 0);
+        
+        // Only the configured treasury may receive it - not the dispute admin
+        assert!(MatchStakeState::validate_treasury_destination(&owner, treasury).is_ok());
+        for wrong in [stake.player_a, DISPUTE_ADMIN] {
+            assert_eq!(
+                MatchStakeState::validate_treasury_destination(&wrong, treasury).unwrap_err(),
+                DroogError::InvalidPayoutDestination.into()
+            );
+        }
+        assert!(MatchStakeState::validate_treasury_destination(&owner, None).is_err());
+    }
+    
+    #[test]
+    fn test_finalizer_reward_paid_to_either_participant() {
+        let pot = 1_800_000;
+        let stake = test_stake();
+        let standings = Standings {
+            winner: stake.player_b,
+            loser: stake.player_a,
+            winner_sales: 8,
+            loser_sales: 5,
+            decided_by: DecidedBy::SalesMargin,
+        };
+        // Finalize's order: carve the reward, then split the rest
+        let settle = |stake: &MatchStakeState, reward_bps: u16, finalizer: &Pubkey| {
+            let reward = stake.finalizer_reward(pot, reward_bps, finalizer);
+            let (winner_amount, loser_amount) = stake.settlement_split(&standings, pot - reward, 0);
+            (winner_amount, loser_amount, reward)
+        };
+        
+        // Winner finalizes: reward plus the remainder
+        assert_eq!(settle(&stake, 100, &standings.winner), (pot - 18_000, 0, 18_000));
+        
+        // Loser finalizes: the same reward, the winner still gets the remainder
+        assert_eq!(settle(&stake, 100, &standings.loser), (pot - 18_000, 0, 18_000));
+        
+        // Zero-able, and never paid to a keeper; delayed settlements earn it too
+        assert_eq!(settle(&stake, 0, &standings.loser), (pot, 0, 0));
+        assert_eq!(settle(&stake, 100, &Pubkey::new_unique()), (pot, 0, 0));
+        let delayed = MatchStakeState { delayed_settlement: true, ..test_stake() };
+        assert_eq!(delayed.finalizer_reward(pot, 100, &delayed.player_a), 18_000);
+        
+        // Never more than the pot, even at absurd rates or balances
+        assert_eq!(stake.finalizer_reward(pot, u16::MAX, &stake.player_a), pot);
+        assert_eq!(stake.finalizer_reward(u64::MAX, 500, &stake.player_a), u64::MAX / 20);
+    }
+    
+    #[test]
+    fn test_lifecycle_rejects_out_of_order_instructions() {
+        let all_actions = [
+            LifecycleAction::Join,
+            LifecycleAction::Cancel,
+            LifecycleAction::Reap,
+            LifecycleAction::RaiseStake,
+            LifecycleAction::Gameplay,
+            LifecycleAction::RefreshDelivery,
+            LifecycleAction::Finalize,
+            LifecycleAction::Surrender,
+            LifecycleAction::FreezeSettlement,
+            LifecycleAction::ExecuteSettlement,
+            LifecycleAction::ReverseFinalization,
+            LifecycleAction::ResolveDispute,
+            LifecycleAction::DoubleOrNothing,
+            LifecycleAction::CloseAccounts,
+        ];
+        let all_statuses = [
+            MatchStatus::Pending,
+            MatchStatus::Active,
+            MatchStatus::Finalized,
+            MatchStatus::Cancelled,
+            MatchStatus::Settling,
+            MatchStatus::Disputed,
+        ];
+        
+        // Every instruction runs in exactly its declared phases
+        for status in all_statuses {
+            let stake = MatchStakeState { status, ..test_stake() };
+            for action in all_actions {
+                let valid = action.valid_phases().contains(&stake.lifecycle_phase());
+                assert_eq!(stake.check_lifecycle(action).is_ok(), valid, "{:?} in {:?}", action, status);
+            }
+        }
+        
+        // Refreshing delivery on an Active match_state whose stake is still Pending
+        let pending = test_stake();
+        assert_eq!(
+            pending.check_lifecycle(LifecycleAction::RefreshDelivery).unwrap_err(),
+            DroogError::MatchNotActivated.into()
+        );
+        
+        // Each action keeps its established error
+        let active = MatchStakeState { status: MatchStatus::Active, ..test_stake() };
+        assert_eq!(active.check_lifecycle(LifecycleAction::Join).unwrap_err(), DroogError::MatchNotPending.into());
+        assert_eq!(
+            active.check_lifecycle(LifecycleAction::ExecuteSettlement).unwrap_err(),
+            DroogError::SettlementNotPending.into()
+        );
+        let closed = MatchStakeState { status: MatchStatus::Finalized, ..test_stake() };
+        assert_eq!(closed.check_lifecycle(LifecycleAction::Finalize).unwrap_err(), DroogError::MatchNotActive.into());
+        assert_eq!(closed.check_lifecycle(LifecycleAction::Gameplay).unwrap_err(), DroogError::MatchNotActive.into());
+        
+        // Accounts close only once nothing is left in escrow
+        assert!(closed.check_lifecycle(LifecycleAction::CloseAccounts).is_ok());
+        for status in [MatchStatus::Pending, MatchStatus::Active, MatchStatus::Settling] {
+            assert_eq!(
+                MatchStakeState { status, ..test_stake() }.check_lifecycle(LifecycleAction::CloseAccounts).unwrap_err(),
+                DroogError::MatchNotClosed.into()
+            );
+        }
+    }
+    
+    #[test]
+    fn test_payout_destination_owner_validation() {
+        let stake = test_stake();
+        let winner = stake.player_a;
+        
+        // A non-ATA token account is just another account with owner == winner
+        assert!(MatchStakeState::validate_payout_destination(&winner, &winner).is_ok());
+        
+        // Third parties and the loser can't receive the pot
+        assert_eq!(
+            MatchStakeState::validate_payout_destination(&Pubkey::new_unique(), &winner).unwrap_err(),
+            DroogError::InvalidPayoutDestination.into()
+        );
+        assert_eq!(
+            MatchStakeState::validate_payout_destination(&stake.player_b, &winner).unwrap_err(),
+            DroogError::InvalidPayoutDestination.into()
+        );
+    }
+    
+    #[test]
+    fn test_payout_mint_validation() {
+        let mint = Pubkey::new_unique();
+        assert!(MatchStakeState::validate_payout_mint(&mint, &mint).is_ok());
+        
+        // The winner's own account, but for some other token
+        assert_eq!(
+            MatchStakeState::validate_payout_mint(&Pubkey::new_unique(), &mint).unwrap_err(),
+            DroogError::MintMismatch.into()
+        );
+    }
+    
+    #[test]
+    fn test_practice_match_moves_no_tokens() {
+        assert!(MatchStakeState::validate_stake_mode(true, false).is_ok());
+        assert_eq!(
+            MatchStakeState::validate_stake_mode(true, true).unwrap_err(),
+            DroogError::PracticeMatchNoStake.into()
+        );
+        
+        // Init escrows nothing, yet Player B can still opt in
+        let mut stake = MatchStakeState { practice: true, player_a_escrowed: 0, ..test_stake() };
+        assert!(stake.can_activate());
+        stake.status = MatchStatus::Active;
+        
+        // Nothing escrowed means nothing burned
+        let total = stake.player_a_escrowed + stake.player_b_escrowed;
+        assert_eq!(total, 0);
+        assert_eq!(MatchStakeState::calculate_burn_amount(total), 0);
+        
+        // Gameplay and finalization follow the normal lifecycle
+        assert!(stake.check_gameplay().is_ok());
+        assert!(stake.can_finalize());
+        
+        // A staked match with an unfunded side still can't activate
+        let staked = MatchStakeState { player_a_escrowed: 0, ..test_stake() };
+        assert!(!staked.can_activate());
+    }
+    
+    #[test]
+    fn test_mercy_rule_thresholds() {
+        let rule = MercyRule { sales_margin: 5, sales_cap: 12 };
+        
+        // Blowout by margin, or a runaway leader at the cap
+        assert!(rule.is_triggered(7, 2));
+        assert!(rule.is_triggered(2, 7));
+        assert!(rule.is_triggered(12, 11));
+        
+        // Close match stays open
+        assert!(!rule.is_triggered(6, 2));
+        assert!(!rule.is_triggered(11, 10));
+        
+        // Disabled thresholds never fire
+        assert!(!MercyRule::default().is_triggered(100, 0));
+        assert!(!MercyRule { sales_margin: 0, sales_cap: 12 }.is_triggered(11, 0));
+    }
+    
+    #[test]
+    fn test_can_reap_after_horizon() {
+        let mut stake = test_stake();
+        
+        // Rejected before the horizon, even after the normal cancel timeout
+        assert!(!stake.can_reap(1_000 + CANCEL_TIMEOUT_SECONDS));
+        assert!(!stake.can_reap(1_000 + ABANDONED_MATCH_HORIZON_SECONDS - 1));
+        
+        // Allowed once the horizon has passed
+        assert!(stake.can_reap(1_000 + ABANDONED_MATCH_HORIZON_SECONDS));
+        
+        // Practice matches too (nothing escrowed, only rent to reclaim)
+        let practice = MatchStakeState { practice: true, player_a_escrowed: 0, ..test_stake() };
+        assert!(!practice.can_reap(1_000 + ABANDONED_MATCH_HORIZON_SECONDS - 1));
+        assert!(practice.can_reap(1_000 + ABANDONED_MATCH_HORIZON_SECONDS));
+        
+        // Never for a match that has left Pending
+        stake.status = MatchStatus::Active;
+        assert!(!stake.can_reap(1_000 + ABANDONED_MATCH_HORIZON_SECONDS));
+    }
+    
+    #[test]
+    fn test_allows_gameplay_follows_stake_status() {
+        let mut stake = test_stake();
+        
+        stake.status = MatchStatus::Active;
+        assert!(stake.allows_gameplay());
+        
+        // Stake Finalized while MatchState.is_finalized might still be false
+        stake.status = MatchStatus::Finalized;
+        assert!(!stake.allows_gameplay());
+        
+        stake.status = MatchStatus::Cancelled;
+        assert!(!stake.allows_gameplay());
+    }
+    
+    #[test]
+    fn test_check_gameplay_rejects_pending() {
+        let mut stake = test_stake();
+        
+        // Player A planting before Player B commits
+        assert_eq!(
+            stake.check_gameplay().unwrap_err(),
+            DroogError::MatchNotActivated.into()
+        );
+        
+        stake.status = MatchStatus::Active;
+        assert!(stake.check_gameplay().is_ok());
+        
+        stake.status = MatchStatus::Finalized;
+        assert_eq!(
+            stake.check_gameplay().unwrap_err(),
+            DroogError::MatchNotActive.into()
+        );
+    }
+    
+    #[test]
+    fn test_escrow_authority_derivation_stable() {
+        for match_id_hash in [[0u8; 32], [7u8; 32], [255u8; 32]] {
+            let (pda1, bump1) = MatchStakeState::derive_escrow_authority(&match_id_hash);
+            let (pda2, bump2) = MatchStakeState::derive_escrow_authority(&match_id_hash);
+            assert_eq!(pda1, pda2);
+            assert_eq!(bump1, bump2);
+            
+            // The signer seeds built by join/cancel/finalize must resolve to the same PDA
+            let signed = Pubkey::create_program_address(
+                &[ESCROW_AUTH_SEED, match_id_hash.as_ref(), &[bump1]],
+                &crate::ID,
+            ).unwrap();
+            assert_eq!(pda1, signed);
+        }
+        
+        // Different matches never share an escrow authority
+        let (a, _) = MatchStakeState::derive_escrow_authority(&[1u8; 32]);
+        let (b, _) = MatchStakeState::derive_escrow_authority(&[2u8; 32]);
+        assert_ne!(a, b);
+    }
+    
+    #[test]
+    fn test_mutual_cancel_skips_the_timeout() {
+        let mut stake = test_stake();
+        
+        // Unilateral cancel waits out the timeout; mutual does not
+        assert!(!stake.can_cancel(stake.created_at));
+        assert!(stake.can_cancel(stake.created_at + CANCEL_TIMEOUT_SECONDS));
+        assert!(stake.can_cancel_mutual());
+        
+        // Still Pending only
+        stake.status = MatchStatus::Active;
+        assert!(!stake.can_cancel_mutual());
+        stake.status = MatchStatus::Cancelled;
+        assert!(!stake.can_cancel_mutual());
+        
+        // Never once Player B has escrowed
+        stake.status = MatchStatus::Pending;
+        stake.player_b_escrowed = stake.stake_amount;
+        assert!(!stake.can_cancel_mutual());
+    }
+}
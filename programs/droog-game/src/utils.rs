@@ -0,0 +1,55 @@
+// ============================================================================
+// SHARED INSTRUCTION HELPERS
+// ============================================================================
+//
+// CPI steps that several instructions perform identically. Account validation
+// stays in each instruction's Accounts struct; these helpers only take the
+// already-validated accounts.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked,
+};
+use crate::errors::DroogError;
+
+/// Move Player A's stake into a new match's escrow (NO BURN)
+/// 
+/// Option C: 100% goes to escrow, burn happens only when Player B joins.
+/// Used by init_match, setup_match and rematch. Practice matches escrow
+/// nothing and may omit every token account; a staked match must supply them
+/// all (StakeAccountsRequired) and Player A must hold the full stake.
+#[allow(clippy::too_many_arguments)]
+pub fn escrow_player_a_stake<'info>(
+    practice: bool,
+    stake_amount: u64,
+    mint: Option<&InterfaceAccount<'info, Mint>>,
+    player_a_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    escrow_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    escrow_authority: Option<&UncheckedAccount<'info>>,
+    player_a: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if practice {
+        return Ok(());
+    }
+    let (Some(mint), Some(player_a_token_account), Some(escrow_token_account), Some(_)) =
+        (mint, player_a_token_account, escrow_token_account, escrow_authority)
+    else {
+        return err!(DroogError::StakeAccountsRequired);
+    };
+    
+    // Validate player has sufficient balance
+    require!(
+        player_a_token_account.amount >= stake_amount,
+        DroogError::InsufficientStakeBalance
+    );
+    
+    let transfer_accounts = TransferChecked {
+        from: player_a_token_account.to_account_info(),
+        to: escrow_token_account.to_account_info(),
+        mint: mint.to_account_info(),
+        authority: player_a.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), transfer_accounts);
+    transfer_checked(cpi_ctx, stake_amount, mint.decimals)
+}
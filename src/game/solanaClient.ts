@@ -364,6 +364,24 @@ export interface MatchState {
   playerAReputation: number
   playerBReputation: number
   isFinalized: boolean
+  // Served-customer bitmasks (bit i = customer i), for the finalize diversity bonus
+  playerAServedMask?: number
+  playerBServedMask?: number
+  // Reputation ledgers (only kept when the match opted in at init)
+  playerARepLedger?: RepLedger
+  playerBRepLedger?: RepLedger
+}
+
+// One applied reputation change and the REP_REASON_* bits behind it
+export interface RepLedgerEntry {
+  delta: number
+  reasons: number // u16 bitfield
+}
+
+export interface RepLedger {
+  startingReputation: number
+  entries: RepLedgerEntry[] // ring buffer of REP_LEDGER_LEN
+  appended: number
 }
 
 export interface CustomerState {
@@ -371,6 +389,9 @@ export interface CustomerState {
   lastServedTs: BN
   totalServes: number
   lastServedBy: PublicKey | null
+  // Per-player cooldown anchors (0 if that player never served here)
+  playerALastServedTs?: BN
+  playerBLastServedTs?: BN
 }
 
 // PlantState enum from Anchor program
@@ -378,6 +399,7 @@ export type PlantState =
   | { __kind: 'Empty' }
   | { __kind: 'Growing'; strainLevel: number; plantedAt: BN }
   | { __kind: 'Ready'; strainLevel: number }
+  | { __kind: 'Rotten'; strainLevel: number }
 
 export interface GrowSlot {
   // New structure (from chain)
@@ -440,6 +462,22 @@ export const DELIVERY_ROTATION_INTERVAL = 60
 // Number of grow slots per player
 export const SLOTS_PER_PLAYER = 6
 
+// Reputation ledger reason bits (matching on-chain REP_REASON_*; one bit each)
+export const REP_LEDGER_LEN = 16
+export const REP_REASON = {
+  BASE: 1 << 0,
+  VARIANT: 1 << 1,
+  DEMAND: 1 << 2,
+  RUSH: 1 << 3,
+  STEAL: 1 << 4,
+  REFRESH: 1 << 5,
+  REPLACE: 1 << 6,
+  EXPANSION: 1 << 7,
+  UPGRADE: 1 << 8,
+  DIVERSITY: 1 << 9,
+  RAID: 1 << 10,
+} as const
+
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 type AnyProgram = Program<any>
 
@@ -1735,6 +1773,17 @@ export class DroogGameClient {
     )
   }
 
+  /**
+   * Derive settlement record PDA (written once at finalize).
+   * Seeds: ["settlement", match_id_hash]
+   */
+  static deriveSettlementRecordPDA(matchIdHash: Buffer): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('settlement'), matchIdHash],
+      PROGRAM_ID
+    )
+  }
+
   /**
    * Get or create the player's Associated Token Account for $PACKS.
    */
@@ -1821,10 +1870,13 @@ export class DroogGameClient {
    * - Player B escrows 100% of stake
    * - Burn occurs here (10% of total)
    * - Match becomes Active atomically with burn
+   * 
+   * expectedStakeAmount is the stake Player B agreed to match; the program
+   * rejects the join if Player A raised it since. Defaults to the amount
+   * currently on chain.
    */
-  async joinMatchWithStake(matchIdString: string): Promise<string> {
+  async joinMatchWithStake(matchIdString: string, expectedStakeAmount?: BN): Promise<string> {
     const matchIdentity = await createMatchIdentity(matchIdString)
-    const matchIdHashArray = Array.from(matchIdentity.hash32)
     
     // Derive PDAs
     const [stakePDA] = DroogGameClient.deriveStakePDA(matchIdentity.hash32)
@@ -1839,8 +1891,11 @@ export class DroogGameClient {
     
     const methods = this.program.methods as any
     
+    const stakeAmount = expectedStakeAmount
+      ?? (await (this.program.account as any).matchStakeState.fetch(stakePDA)).playerBStakeAmount
+    
     const instruction = await methods
-      .joinMatchWithStake()
+      .joinMatchWithStake(stakeAmount)
       .accounts({
         stakeState: stakePDA,
         mint: PACKS_MINT,
@@ -1999,13 +2054,15 @@ export class DroogGameClient {
           layer: c.layer,
           lastServedTs: c.lastServedTs,
           totalServes: c.totalServes,
-          lastServedBy: c.lastServedBy,
+          lastServedBy: c.served ? c.lastServedBy : null,
+          playerALastServedTs: c.playerALastServedTs,
+          playerBLastServedTs: c.playerBLastServedTs,
         })),
         playerASales: account.playerASales.toNumber ? account.playerASales.toNumber() : account.playerASales,
         playerBSales: account.playerBSales.toNumber ? account.playerBSales.toNumber() : account.playerBSales,
         playerAReputation: account.playerAReputation.toNumber ? account.playerAReputation.toNumber() : account.playerAReputation,
         playerBReputation: account.playerBReputation.toNumber ? account.playerBReputation.toNumber() : account.playerBReputation,
-        isFinalized: account.isFinalized,
+        isFinalized: account.isFinalized !== 0,
       }
     } catch (error) {
       // PDA doesn't exist yet
@@ -2557,7 +2614,7 @@ export class DroogGameClient {
    * Can only be called once, after end_ts, by a match participant.
    * 
    * The winner is determined purely by sales count (on-chain authoritative).
-   * Winner receives entire remaining escrow balance.
+   * Winner receives the escrow less any loser consolation (split 50/50 on a draw).
    */
   async finalizeMatch(
     matchIdString: string,
@@ -2575,6 +2632,7 @@ export class DroogGameClient {
     const [stakePDA] = DroogGameClient.deriveStakePDA(matchIdentity.hash32)
     const [escrowPDA] = DroogGameClient.deriveEscrowPDA(matchIdentity.hash32)
     const [escrowAuthPDA] = DroogGameClient.deriveEscrowAuthorityPDA(matchIdentity.hash32)
+    const [settlementRecordPDA] = DroogGameClient.deriveSettlementRecordPDA(matchIdentity.hash32)
     
     const player = this.provider.wallet.publicKey
     
@@ -2584,10 +2642,17 @@ export class DroogGameClient {
       throw new Error('Match state not found')
     }
     
-    // Determine winner (same logic as on-chain: A wins ties)
-    const winner = matchState.playerASales >= matchState.playerBSales 
-      ? matchState.playerA 
-      : matchState.playerB
+    // Determine winner (same logic as on-chain: sales, then reputation,
+    // then Player A; equal on both is a draw that splits the pot)
+    // Finalize first adds 1 reputation per distinct customer served
+    const distinctCustomers = (mask = 0) => (mask & 0x7fffff).toString(2).split('1').length - 1
+    const aReputation = matchState.playerAReputation + distinctCustomers(matchState.playerAServedMask)
+    const bReputation = matchState.playerBReputation + distinctCustomers(matchState.playerBServedMask)
+    const salesTied = matchState.playerASales === matchState.playerBSales
+    const aWins = salesTied
+      ? aReputation >= bReputation
+      : matchState.playerASales > matchState.playerBSales
+    const winner = aWins ? matchState.playerA : matchState.playerB
     
     // Get winner's token account
     const winnerTokenAccount = await getAssociatedTokenAddress(
@@ -2597,6 +2662,16 @@ export class DroogGameClient {
       TOKEN_PROGRAM_ID,
       ASSOCIATED_TOKEN_PROGRAM_ID
     )
+    
+    // The loser may be paid too (reputation consolation, or Player B's half of a draw)
+    const loser = winner.equals(matchState.playerA) ? matchState.playerB : matchState.playerA
+    const loserTokenAccount = await getAssociatedTokenAddress(
+      PACKS_MINT,
+      loser,
+      false,
+      TOKEN_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    )
 
     const tx = await (this.program.methods as any)
       .finalizeMatch()
@@ -2606,7 +2681,9 @@ export class DroogGameClient {
         mint: PACKS_MINT,
         escrowTokenAccount: escrowPDA,
         escrowAuthority: escrowAuthPDA,
+        settlementRecord: settlementRecordPDA,
         winnerTokenAccount: winnerTokenAccount,
+        loserTokenAccount,
         player,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -2635,13 +2712,19 @@ export class DroogGameClient {
             layer: c.layer,
             lastServedTs: c.lastServedTs,
             totalServes: c.totalServes,
-            lastServedBy: c.lastServedBy,
+            lastServedBy: c.served ? c.lastServedBy : null,
+            playerALastServedTs: c.playerALastServedTs,
+            playerBLastServedTs: c.playerBLastServedTs,
           })),
           playerASales: account.playerASales.toNumber ? account.playerASales.toNumber() : account.playerASales,
           playerBSales: account.playerBSales.toNumber ? account.playerBSales.toNumber() : account.playerBSales,
           playerAReputation: account.playerAReputation.toNumber ? account.playerAReputation.toNumber() : account.playerAReputation,
           playerBReputation: account.playerBReputation.toNumber ? account.playerBReputation.toNumber() : account.playerBReputation,
-          isFinalized: account.isFinalized,
+          isFinalized: account.isFinalized !== 0,
+          playerAServedMask: account.playerAServedMask,
+          playerBServedMask: account.playerBServedMask,
+          playerARepLedger: account.playerARepLedger,
+          playerBRepLedger: account.playerBRepLedger,
         }
     } catch (error) {
       console.error('Error fetching match state:', error)
@@ -2761,6 +2844,13 @@ export class DroogGameClient {
               strainLevel: readyData.strainLevel ?? readyData.strain_level ?? 0
             }
           }
+          else if ('rotten' in rawPlantState) {
+            const rottenData = rawPlantState.rotten
+            plantState = { 
+              __kind: 'Rotten', 
+              strainLevel: rottenData.strainLevel ?? rottenData.strain_level ?? 0
+            }
+          }
           // Check for discriminator format
           else if (rawPlantState.discriminator !== undefined) {
             const disc = rawPlantState.discriminator
@@ -2777,6 +2867,11 @@ export class DroogGameClient {
                 __kind: 'Ready', 
                 strainLevel: rawPlantState.strainLevel || rawPlantState.strain_level || 0
               }
+            } else if (disc === 3) {
+              plantState = { 
+                __kind: 'Rotten', 
+                strainLevel: rawPlantState.strainLevel || rawPlantState.strain_level || 0
+              }
             } else {
               plantState = { __kind: 'Empty' }
             }
@@ -3246,23 +3341,31 @@ export class DroogGameClient {
   /**
    * Check if customer is available (cooldown passed)
    * Uses match-anchored time to prevent clock drift issues
+   * Cooldowns are per player: pass the acting player to ignore the opponent's serves
    */
-  async isCustomerAvailable(matchId: number, customerIndex: number): Promise<boolean> {
+  async isCustomerAvailable(matchId: number, customerIndex: number, player?: PublicKey): Promise<boolean> {
     const matchState = await this.getMatchState(matchId)
     if (!matchState) return false
 
     const customer = matchState.customers[customerIndex]
     if (!customer) return false
 
+    let lastServedTs = customer.lastServedTs.toNumber()
+    if (player?.equals(matchState.playerA) && customer.playerALastServedTs) {
+      lastServedTs = customer.playerALastServedTs.toNumber()
+    } else if (player?.equals(matchState.playerB) && customer.playerBLastServedTs) {
+      lastServedTs = customer.playerBLastServedTs.toNumber()
+    }
+
     // If never served, available
-    if (customer.lastServedTs.toNumber() === 0) return true
+    if (lastServedTs === 0) return true
 
     // Check cooldown using match-anchored time
     const cooldowns = [30, 45, 75] // Layer 1, 2, 3 in seconds
     const cooldown = cooldowns[customer.layer - 1] || 0
     const matchStartTs = matchState.startTs.toNumber()
     const currentTs = getCurrentMatchTime(matchStartTs)
-    const timeSinceLastServe = currentTs - customer.lastServedTs.toNumber()
+    const timeSinceLastServe = currentTs - lastServedTs
 
     return timeSinceLastServe >= cooldown
   }
@@ -3331,13 +3434,13 @@ export class DroogGameClient {
               layer: c.layer,
               lastServedTs: c.lastServedTs,
               totalServes: c.totalServes,
-              lastServedBy: c.lastServedBy,
+              lastServedBy: c.served ? c.lastServedBy : null,
             })),
             playerASales: matchState.playerASales.toNumber ? matchState.playerASales.toNumber() : matchState.playerASales,
             playerBSales: matchState.playerBSales.toNumber ? matchState.playerBSales.toNumber() : matchState.playerBSales,
             playerAReputation: matchState.playerAReputation.toNumber ? matchState.playerAReputation.toNumber() : matchState.playerAReputation,
             playerBReputation: matchState.playerBReputation.toNumber ? matchState.playerBReputation.toNumber() : matchState.playerBReputation,
-            isFinalized: matchState.isFinalized,
+            isFinalized: matchState.isFinalized !== 0,
           })
         } catch (error) {
           console.error('Error decoding match state:', error)